@@ -125,7 +125,8 @@ where
 
     #[inline]
     fn size_hint(&self) -> (usize, Option<usize>) {
-        (self.data.len(), Some(self.data.len()))
+        let len = self.data.len() - self.pos;
+        (len, Some(len))
     }
 }
 
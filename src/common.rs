@@ -1,5 +1,104 @@
+use std::{error, fmt};
+
 /// Stream sample rate (samples per second per channel).
 pub type SampleRate = u32;
 
 /// Number of channels in a stream.
 pub type ChannelCount = u16;
+
+/// Number of bits used to store one sample, between 1 and 32.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BitDepth(u32);
+
+impl BitDepth {
+    /// 8 bits per sample.
+    pub const BITS_8: BitDepth = BitDepth(8);
+    /// 16 bits per sample, as used on CDs.
+    pub const BITS_16: BitDepth = BitDepth(16);
+    /// 24 bits per sample.
+    pub const BITS_24: BitDepth = BitDepth(24);
+    /// 32 bits per sample.
+    pub const BITS_32: BitDepth = BitDepth(32);
+
+    /// Returns `None` if `bits` is not between 1 and 32.
+    pub const fn new(bits: u32) -> Option<BitDepth> {
+        if matches!(bits, 1..=32) {
+            Some(BitDepth(bits))
+        } else {
+            None
+        }
+    }
+
+    /// The number of bits per sample.
+    pub const fn bits(self) -> u32 {
+        self.0
+    }
+
+    /// The largest value of a signed integer sample with this bit depth, for example
+    /// `32767` for 16 bits.
+    pub const fn max_value(self) -> u32 {
+        (1 << (self.0 - 1)) - 1
+    }
+
+    /// The factor between a float sample from `-1.0` to `1.0` and a signed integer
+    /// sample with this bit depth, for example `32768.0` for 16 bits.
+    ///
+    /// Divide integer samples by it to get floats, multiply floats by it and clamp
+    /// to [`max_value`](BitDepth::max_value) to get integers.
+    pub fn scale_factor(self) -> f32 {
+        (1u64 << (self.0 - 1)) as f32
+    }
+}
+
+impl From<BitDepth> for u32 {
+    fn from(bits: BitDepth) -> u32 {
+        bits.0
+    }
+}
+
+impl TryFrom<u32> for BitDepth {
+    type Error = BitDepthError;
+
+    fn try_from(bits: u32) -> Result<BitDepth, BitDepthError> {
+        BitDepth::new(bits).ok_or(BitDepthError { bits })
+    }
+}
+
+/// Error returned when converting a number of bits that is not between 1 and 32 to a
+/// [`BitDepth`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BitDepthError {
+    /// The number of bits that was asked for.
+    pub bits: u32,
+}
+
+impl fmt::Display for BitDepthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "a bit depth of {} bits is not between 1 and 32",
+            self.bits
+        )
+    }
+}
+
+impl error::Error for BitDepthError {}
+
+#[cfg(test)]
+mod tests {
+    use super::{BitDepth, BitDepthError};
+
+    #[test]
+    fn bit_depth_scaling() {
+        let bits = BitDepth::BITS_24;
+        assert_eq!(bits.max_value(), (1 << 23) - 1);
+        assert_eq!(bits.scale_factor(), 8_388_608.0);
+        assert_eq!(BitDepth::BITS_32.max_value(), i32::MAX as u32);
+        assert_eq!(BitDepth::BITS_16.scale_factor(), 32768.0);
+        assert_eq!(BitDepth::new(1).unwrap().max_value(), 0);
+
+        assert_eq!(u32::from(bits), 24);
+        assert_eq!(BitDepth::try_from(24), Ok(bits));
+        assert_eq!(BitDepth::try_from(33), Err(BitDepthError { bits: 33 }));
+    }
+}
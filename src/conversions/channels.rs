@@ -1,5 +1,7 @@
+use super::Latency;
 use crate::common::ChannelCount;
 use dasp_sample::Sample;
+use std::time::Duration;
 
 /// Iterator that converts from a certain channel count to another.
 #[derive(Clone, Debug)]
@@ -93,9 +95,10 @@ where
         let (min, max) = self.input.size_hint();
 
         let consumed = std::cmp::min(self.from, self.next_output_sample_pos) as usize;
-        let calculate = |size| {
-            (size + consumed) / self.from as usize * self.to as usize
-                - self.next_output_sample_pos as usize
+        let calculate = |size: usize| {
+            (size.saturating_add(consumed) / self.from as usize)
+                .saturating_mul(self.to as usize)
+                .saturating_sub(self.next_output_sample_pos as usize)
         };
 
         let min = calculate(min);
@@ -112,6 +115,16 @@ where
 {
 }
 
+impl<I> Latency for ChannelCountConverter<I>
+where
+    I: Iterator + Latency,
+{
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::ChannelCountConverter;
@@ -128,6 +141,13 @@ mod test {
         assert_eq!(output, [1, 5]);
     }
 
+    #[test]
+    fn size_hint_saturates() {
+        let input = std::iter::repeat_n(0i16, usize::MAX - 1);
+        let converter = ChannelCountConverter::new(input, 1, 6);
+        assert_eq!(converter.size_hint(), (usize::MAX, Some(usize::MAX)));
+    }
+
     #[test]
     fn add_channels() {
         let input = vec![1i16, 2, 3, 4];
@@ -0,0 +1,107 @@
+/// Interleaves one buffer per channel into `out`, frame by frame.
+///
+/// Every channel of `planar` must hold the same number of samples, and `out` must
+/// hold exactly that many samples for every channel.
+///
+/// # Panics
+///
+/// Panics if the lengths of the buffers do not match.
+///
+/// # Example
+///
+/// ```
+/// use rodio::interleave;
+///
+/// let left = [1.0, 2.0];
+/// let right = [-1.0, -2.0];
+/// let mut out = [0.0; 4];
+/// interleave(&[&left, &right], &mut out);
+/// assert_eq!(out, [1.0, -1.0, 2.0, -2.0]);
+/// ```
+pub fn interleave(planar: &[&[f32]], out: &mut [f32]) {
+    let channels = planar.len();
+    if channels == 0 {
+        assert!(out.is_empty(), "no channels to interleave into `out`");
+        return;
+    }
+    let frames = planar[0].len();
+    assert!(
+        planar.iter().all(|channel| channel.len() == frames),
+        "every channel must hold the same number of samples"
+    );
+    assert_eq!(
+        out.len(),
+        frames * channels,
+        "`out` must hold every sample of every channel"
+    );
+
+    for (frame, out_frame) in out.chunks_exact_mut(channels).enumerate() {
+        for (sample, channel) in out_frame.iter_mut().zip(planar) {
+            *sample = channel[frame];
+        }
+    }
+}
+
+/// Splits interleaved samples with `channels` channels into one buffer per channel.
+///
+/// The buffers in `out` are cleared first, which keeps their allocations. A trailing
+/// incomplete frame is dropped.
+///
+/// # Panics
+///
+/// Panics if `out` does not hold exactly `channels` buffers.
+///
+/// # Example
+///
+/// ```
+/// use rodio::deinterleave;
+///
+/// let mut out = vec![Vec::new(); 2];
+/// deinterleave(&[1.0, -1.0, 2.0, -2.0], &mut out, 2);
+/// assert_eq!(out, [vec![1.0, 2.0], vec![-1.0, -2.0]]);
+/// ```
+pub fn deinterleave(interleaved: &[f32], out: &mut [Vec<f32>], channels: usize) {
+    assert_eq!(out.len(), channels, "`out` needs one buffer per channel");
+    for buffer in out.iter_mut() {
+        buffer.clear();
+    }
+    if channels == 0 {
+        return;
+    }
+
+    for frame in interleaved.chunks_exact(channels) {
+        for (buffer, &sample) in out.iter_mut().zip(frame) {
+            buffer.push(sample);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{deinterleave, interleave};
+
+    #[test]
+    fn stereo_round_trip() {
+        let stereo: Vec<f32> = (0..20).map(|i| i as f32 * 0.05 - 0.5).collect();
+
+        let mut planar = vec![Vec::new(); 2];
+        deinterleave(&stereo, &mut planar, 2);
+        assert_eq!(
+            planar[0],
+            stereo.iter().step_by(2).copied().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            planar[1],
+            stereo
+                .iter()
+                .skip(1)
+                .step_by(2)
+                .copied()
+                .collect::<Vec<_>>()
+        );
+
+        let mut out = vec![0.0; stereo.len()];
+        interleave(&[&planar[0], &planar[1]], &mut out);
+        assert_eq!(out, stereo);
+    }
+}
@@ -1,17 +1,26 @@
 /*!
 This module contains function that will convert from one PCM format to another.
 
-This includes conversion between sample formats, channels or sample rates.
+This includes conversion between sample formats, channels or sample rates, and between
+interleaved and planar buffers.
 
 */
 
 pub use self::channels::ChannelCountConverter;
+pub use self::interleave::{deinterleave, interleave};
 pub use self::sample::DataConverter;
 pub use self::sample::Sample;
 pub use self::sample_rate::SampleRateConverter;
 
 mod channels;
+mod interleave;
 // TODO: < shouldn't be public ; there's a bug in Rust 1.4 and below that makes This
 // `pub` mandatory
 pub mod sample;
 mod sample_rate;
+
+/// Delay of a converter, forwarded from the source it converts, see
+/// [`Source::latency`](crate::Source::latency).
+pub(crate) trait Latency {
+    fn latency(&self) -> std::time::Duration;
+}
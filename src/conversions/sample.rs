@@ -1,5 +1,7 @@
+use super::Latency;
 use dasp_sample::{FromSample, Sample as DaspSample};
 use std::marker::PhantomData;
+use std::time::Duration;
 
 /// Converts the samples data type to `O`.
 #[derive(Clone, Debug)]
@@ -58,6 +60,16 @@ where
 {
 }
 
+impl<I, O> Latency for DataConverter<I, O>
+where
+    I: Latency,
+{
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+}
+
 /// Represents a value of a single sample.
 ///
 /// This trait is implemented by default on three types: `i16`, `u16` and `f32`.
@@ -1,8 +1,9 @@
-use crate::conversions::Sample;
+use crate::conversions::{Latency, Sample};
 
 use crate::common::{ChannelCount, SampleRate};
 use num_rational::Ratio;
 use std::mem;
+use std::time::Duration;
 
 /// Iterator that converts from a certain sample rate to another.
 #[derive(Clone, Debug)]
@@ -203,42 +204,62 @@ where
 
     #[inline]
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let apply = |samples: usize| {
-            // `samples_after_chunk` will contain the number of samples remaining after the chunk
-            // currently being processed
-            let samples_after_chunk = samples;
-            // adding the samples of the next chunk that may have already been read
-            let samples_after_chunk = if self.current_span_pos_in_chunk == self.from - 1 {
-                samples_after_chunk + self.next_span.len()
-            } else {
-                samples_after_chunk
-            };
-            // removing the samples of the current chunk that have not yet been read
-            let samples_after_chunk = samples_after_chunk.saturating_sub(
-                self.from.saturating_sub(self.current_span_pos_in_chunk + 2) as usize
-                    * usize::from(self.channels),
-            );
-            // calculating the number of samples after the transformation
-            // TODO: this is wrong here \|/
-            let samples_after_chunk = samples_after_chunk * self.to as usize / self.from as usize;
-
-            // `samples_current_chunk` will contain the number of samples remaining to be output
-            // for the chunk currently being processed
-            let samples_current_chunk = (self.to - self.next_output_span_pos_in_chunk) as usize
-                * usize::from(self.channels);
-
-            samples_current_chunk + samples_after_chunk + self.output_buffer.len()
-        };
-
         if self.from == self.to {
             self.input.size_hint()
         } else {
             let (min, max) = self.input.size_hint();
-            (apply(min), max.map(apply))
+            (self.output_len(min), max.map(|max| self.output_len(max)))
         }
     }
 }
 
+impl<I> SampleRateConverter<I>
+where
+    I: Iterator,
+    I::Item: Sample,
+{
+    // Number of samples left to output if `input_left` samples are left in the input.
+    fn output_len(&self, input_left: usize) -> usize {
+        if self.current_span.is_empty() {
+            return self.output_buffer.len();
+        }
+
+        let channels = self.channels as usize;
+        // Frames counted from the start of the current chunk, the last one may be incomplete.
+        let frames = self.current_span_pos_in_chunk as u128
+            + 1
+            + !self.next_span.is_empty() as u128
+            + input_left.div_ceil(channels) as u128;
+        let last_len = if input_left > 0 {
+            match input_left % channels {
+                0 => channels,
+                rest => rest,
+            }
+        } else if !self.next_span.is_empty() {
+            self.next_span.len()
+        } else {
+            self.current_span.len()
+        };
+
+        // Number of outputs left whose left input frame comes before `frame`.
+        let outputs_before = |frame: u128| {
+            (frame * self.to as u128)
+                .div_ceil(self.from as u128)
+                .saturating_sub(self.next_output_span_pos_in_chunk as u128)
+        };
+        // Interpolated between two complete frames, then between the last two frames.
+        let full = outputs_before(frames.saturating_sub(2));
+        let partial = outputs_before(frames - 1) - full;
+        // The last frame is output on its own once.
+        let last = (outputs_before(frames) > outputs_before(frames - 1)) as u128;
+
+        let samples = full * channels as u128
+            + (partial + last) * last_len as u128
+            + self.output_buffer.len() as u128;
+        samples.try_into().unwrap_or(usize::MAX)
+    }
+}
+
 impl<I> ExactSizeIterator for SampleRateConverter<I>
 where
     I: ExactSizeIterator,
@@ -246,6 +267,16 @@ where
 {
 }
 
+impl<I> Latency for SampleRateConverter<I>
+where
+    I: Iterator + Latency,
+{
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::SampleRateConverter;
@@ -339,6 +370,30 @@ mod test {
                          .step_by(k as usize).collect::<Vec<_>>().concat())
         }
 
+        /// Check that the length reported before and while converting is exact.
+        fn exact_len(from: u8, to: u8, channels: u8, input: Vec<u16>) -> TestResult {
+            if channels == 0 || channels > 8 {
+                return TestResult::discard();
+            }
+            let rates = [8000, 11025, 16000, 22050, 44100, 48000, 96000];
+            let from = rates[from as usize % rates.len()];
+            let to = rates[to as usize % rates.len()];
+
+            let mut output =
+                SampleRateConverter::new(input.into_iter(), from, to, channels as ChannelCount);
+            let mut remaining = output.len();
+            while output.next().is_some() {
+                if remaining == 0 {
+                    return TestResult::failed();
+                }
+                remaining -= 1;
+                if output.len() != remaining {
+                    return TestResult::failed();
+                }
+            }
+            TestResult::from_bool(remaining == 0)
+        }
+
         #[ignore]
         /// Check that resampling does not change the audio duration,
         ///  except by a negligible amount (± 1ms).  Reproduces #316.
@@ -381,6 +436,18 @@ mod test {
         assert!((size_estimation as f32 / output.len() as f32).abs() < 2.0);
     }
 
+    #[test]
+    fn len_is_exact() {
+        let input = Vec::from_iter(0u16..2000);
+        let mut output = SampleRateConverter::new(input.into_iter(), 44100, 48000, 2);
+        let len = output.len();
+        assert_eq!(len, 2178);
+
+        output.by_ref().take(100).for_each(drop);
+        assert_eq!(output.len(), len - 100);
+        assert_eq!(output.count(), len - 100);
+    }
+
     #[test]
     fn downsample() {
         let input = Vec::from_iter(0u16..17);
@@ -0,0 +1,105 @@
+use std::io::{Read, Seek};
+use std::time::Duration;
+
+use super::{extension_from_hint, Decoder, DecoderError, SeekMode};
+
+/// Configures and builds a [`Decoder`].
+///
+/// # Example
+///
+/// ```
+/// use std::fs::File;
+/// use std::time::Duration;
+/// use rodio::decoder::DecoderBuilder;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let file = File::open("assets/music.wav")?;
+/// // Only play the chorus
+/// let decoder = DecoderBuilder::new(file)
+///     .with_hint("wav")
+///     .with_range(Duration::from_secs(1), Duration::from_secs(3))
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct DecoderBuilder<R> {
+    data: R,
+    hint: Option<String>,
+    range: Option<(Duration, Duration)>,
+    seek_mode: SeekMode,
+    track_index: usize,
+}
+
+impl<R> DecoderBuilder<R>
+where
+    R: Read + Seek + Send + Sync + 'static,
+{
+    /// Starts building a decoder for `data`.
+    pub fn new(data: R) -> Self {
+        DecoderBuilder {
+            data,
+            hint: None,
+            range: None,
+            seek_mode: SeekMode::default(),
+            track_index: 0,
+        }
+    }
+
+    /// Tries the format named by `hint` first.
+    ///
+    /// The `hint` is either a file extension such as `"mp3"` or a MIME type such as
+    /// `"audio/mpeg"`. See [`Decoder::from_bytes_with_hint`].
+    pub fn with_hint(mut self, hint: &str) -> Self {
+        self.hint = extension_from_hint(hint);
+        self
+    }
+
+    /// Only decodes the part of the data between `start` and `end`.
+    ///
+    /// The decoder seeks to `start` when it is built and ends once it reaches `end`. If
+    /// the data can not be seeked, or the seek lands before `start` as it can with
+    /// [`SeekMode::Fastest`], the samples before `start` are decoded and dropped.
+    /// Positions passed to [`Source::try_seek`](crate::Source::try_seek) are relative to `start`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `end` comes before `start`.
+    pub fn with_range(mut self, start: Duration, end: Duration) -> Self {
+        assert!(start <= end, "the range must not end before it starts");
+        self.range = Some((start, end));
+        self
+    }
+
+    /// Chooses between fast and precise seeking, the default is [`SeekMode::Nearest`].
+    ///
+    /// See [`SeekMode`] for which decoders make a difference.
+    pub fn with_seek_mode(mut self, seek_mode: SeekMode) -> Self {
+        self.seek_mode = seek_mode;
+        self
+    }
+
+    /// Decodes the audio track at `track_index` instead of the first one, for example
+    /// another language of a film's soundtrack.
+    ///
+    /// The index counts the audio tracks of the file in the order it lists them, from
+    /// `0` to [`Decoder::track_count`] minus one. Building fails with
+    /// [`DecoderError::NoStreams`] if there is no audio track at that index.
+    pub fn with_track_index(mut self, track_index: usize) -> Self {
+        self.track_index = track_index;
+        self
+    }
+
+    /// Detects the format of the data and builds the decoder.
+    pub fn build(self) -> Result<Decoder<R>, DecoderError> {
+        let mut decoder = Decoder::open(self.data, self.hint.as_deref(), self.track_index)?;
+        decoder.set_seek_mode(self.seek_mode);
+        if let Some((start, end)) = self.range {
+            // Without seeking the decoder is still at the start of the data.
+            let landed = decoder.try_seek_reported(start).unwrap_or(Duration::ZERO);
+            let pos = decoder.skip_to(landed, start);
+            decoder.set_range(start, end, pos);
+        }
+        Ok(decoder)
+    }
+}
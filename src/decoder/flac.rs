@@ -6,6 +6,7 @@ use std::time::Duration;
 use crate::source::SeekError;
 use crate::Source;
 
+use super::ProbeResult;
 use crate::common::{ChannelCount, SampleRate};
 use claxon::FlacReader;
 
@@ -50,6 +51,30 @@ where
             samples: spec.samples,
         })
     }
+
+    /// Reads the format from the metadata blocks of Flac data, without decoding any
+    /// audio. Gives back the data if it is not Flac.
+    pub(crate) fn probe(mut data: R) -> Result<ProbeResult, R> {
+        if !is_flac(data.by_ref()) {
+            return Err(data);
+        }
+
+        let reader = FlacReader::new(data).unwrap();
+        let spec = reader.streaminfo();
+        Ok(ProbeResult {
+            codec: "flac",
+            channels: spec.channels as ChannelCount,
+            sample_rate: spec.sample_rate,
+            total_duration: spec.samples.map(|s| total_duration(s, spec.sample_rate)),
+            bits_per_sample: Some(spec.bits_per_sample),
+        })
+    }
+    pub fn total_frames(&self) -> Option<u64> {
+        // `samples` in FLAC means "inter-channel samples" aka frames
+        self.samples
+    }
+
+    #[inline]
     pub fn into_inner(self) -> R {
         self.reader.into_inner()
     }
@@ -78,8 +103,7 @@ where
     fn total_duration(&self) -> Option<Duration> {
         // `samples` in FLAC means "inter-channel samples" aka frames
         // so we do not divide by `self.channels` here.
-        self.samples
-            .map(|s| Duration::from_micros(s * 1_000_000 / self.sample_rate as u64))
+        self.samples.map(|s| total_duration(s, self.sample_rate))
     }
 
     #[inline]
@@ -128,6 +152,11 @@ where
     }
 }
 
+// `frames` is what Flac calls inter-channel samples.
+fn total_duration(frames: u64, sample_rate: SampleRate) -> Duration {
+    Duration::from_micros(frames * 1_000_000 / sample_rate as u64)
+}
+
 /// Returns true if the stream contains Flac data, then resets it to where it was.
 fn is_flac<R>(mut data: R) -> bool
 where
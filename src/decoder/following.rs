@@ -0,0 +1,197 @@
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufReader, Seek, SeekFrom};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::{Decoder, DecoderError};
+use crate::common::{ChannelCount, SampleRate};
+use crate::math;
+use crate::source::SeekError;
+use crate::Source;
+
+// Silence played between two looks for new data at the end of the file.
+const RETRY_PERIOD: Duration = Duration::from_millis(20);
+
+/// Ends a [`FollowingDecoder`] from anywhere, for example once the recording it
+/// follows is finished.
+#[derive(Clone, Debug)]
+pub struct FollowHandle {
+    stopped: Arc<AtomicBool>,
+}
+
+impl FollowHandle {
+    /// Stops waiting for more data. The decoder plays what has been written so far,
+    /// then ends.
+    #[inline]
+    pub fn stop(&self) {
+        self.stopped.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if [`stop`](FollowHandle::stop) was called.
+    #[inline]
+    pub fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::Relaxed)
+    }
+}
+
+/// Decodes a file that is still being written, see [`Decoder::new_following`].
+pub struct FollowingDecoder {
+    // Handle to the file to rebuild the decoder from when the previous one was lost.
+    file: File,
+    decoder: Option<Decoder<BufReader<File>>>,
+    // Length of the file when the decoder was last built, it is only built again
+    // once the file grew.
+    len: u64,
+    handle: FollowHandle,
+    channels: ChannelCount,
+    sample_rate: SampleRate,
+    // Samples of the file played so far.
+    samples_played: u64,
+    // Samples of silence left before looking for new data again.
+    silence_left: usize,
+}
+
+impl FollowingDecoder {
+    pub(super) fn new(file: File) -> Result<(FollowingDecoder, FollowHandle), DecoderError> {
+        let reader = file
+            .try_clone()
+            .map_err(|err| DecoderError::IoError(err.to_string()))?;
+        let len = file_len(&file);
+        let decoder = Decoder::new(BufReader::new(reader))?;
+        let handle = FollowHandle {
+            stopped: Arc::new(AtomicBool::new(false)),
+        };
+        let following = FollowingDecoder {
+            file,
+            channels: decoder.channels(),
+            sample_rate: decoder.sample_rate(),
+            decoder: Some(decoder),
+            len,
+            handle: handle.clone(),
+            samples_played: 0,
+            silence_left: 0,
+        };
+        Ok((following, handle))
+    }
+
+    /// Returns a handle that stops following the file.
+    #[inline]
+    pub fn handle(&self) -> FollowHandle {
+        self.handle.clone()
+    }
+
+    // Decodes the file again from its start, which picks up the data and the headers
+    // written since it was last decoded, and moves to the sample that plays next.
+    fn reopen(&mut self) -> Result<(), SeekError> {
+        // Taken before decoding, data written while decoding makes the file grow again.
+        self.len = file_len(&self.file);
+        let mut reader = match self.decoder.take() {
+            Some(decoder) => decoder.into_inner(),
+            None => BufReader::new(self.file.try_clone().map_err(other)?),
+        };
+        reader.seek(SeekFrom::Start(0)).map_err(other)?;
+        let mut decoder = Decoder::new(reader).map_err(other)?;
+
+        self.channels = decoder.channels();
+        self.sample_rate = decoder.sample_rate();
+        let channels = self.channels as u64;
+        let frame = self.samples_played / channels;
+        let target = math::frames_to_duration(frame, self.sample_rate);
+        let landed = decoder.try_seek_reported(target)?;
+        // Formats that seek coarsely can land before the frame, never decode it twice.
+        let behind = frame.saturating_sub(math::duration_to_frames(landed, self.sample_rate));
+        let skip = behind * channels + self.samples_played % channels;
+        decoder.by_ref().take(skip as usize).for_each(drop);
+        self.decoder = Some(decoder);
+        Ok(())
+    }
+
+    fn next_decoded(&mut self) -> Option<i16> {
+        let sample = self.decoder.as_mut()?.next()?;
+        self.samples_played += 1;
+        Some(sample)
+    }
+}
+
+impl Iterator for FollowingDecoder {
+    type Item = i16;
+
+    #[inline]
+    fn next(&mut self) -> Option<i16> {
+        if self.silence_left > 0 {
+            self.silence_left -= 1;
+            return Some(0);
+        }
+        if let Some(sample) = self.next_decoded() {
+            return Some(sample);
+        }
+
+        // Data written before the stop is still played, even if it only changed the
+        // headers of the file.
+        let stopped = self.handle.is_stopped();
+        if (stopped || file_len(&self.file) > self.len) && self.reopen().is_ok() {
+            if let Some(sample) = self.next_decoded() {
+                return Some(sample);
+            }
+        }
+        if stopped {
+            return None;
+        }
+
+        // Waits in silence, completing a frame the file ended in the middle of.
+        let channels = self.channels as u64;
+        let partial = (channels - self.samples_played % channels) % channels;
+        self.samples_played += partial;
+        let frames = math::duration_to_frames(RETRY_PERIOD, self.sample_rate).max(1);
+        self.silence_left = (partial + frames * channels) as usize - 1;
+        Some(0)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // More data can be written at any time.
+        (self.silence_left, None)
+    }
+}
+
+impl Source for FollowingDecoder {
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.channels
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.sample_rate
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+
+    // Seeking past the data written so far waits there until it is written.
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        let frame = math::duration_to_frames(pos, self.sample_rate);
+        self.samples_played = frame * self.channels as u64;
+        self.silence_left = 0;
+        self.reopen()
+    }
+}
+
+// Zero if the length can not be read, so the file counts as grown once it can.
+fn file_len(file: &File) -> u64 {
+    file.metadata().map_or(0, |metadata| metadata.len())
+}
+
+fn other(err: impl Error + Send + 'static) -> SeekError {
+    SeekError::Other(Box::new(err))
+}
@@ -1,24 +1,36 @@
 //! Decodes samples from an audio file.
 
+use std::collections::HashMap;
 use std::error::Error;
 use std::fmt;
+use std::fs::File;
+use std::hash::{DefaultHasher, Hash, Hasher};
 #[allow(unused_imports)]
-use std::io::{Read, Seek, SeekFrom};
+use std::io::{BufReader, Cursor, Read, Seek, SeekFrom};
 use std::mem;
+use std::path::Path;
 use std::str::FromStr;
+use std::sync::Arc;
+#[cfg(feature = "symphonia")]
+use std::sync::{Mutex, PoisonError};
 use std::time::Duration;
 
+use crate::math;
 use crate::source::SeekError;
-use crate::Source;
+use crate::{Sample, Source};
 
+pub use self::builder::DecoderBuilder;
+pub use self::following::{FollowHandle, FollowingDecoder};
 #[cfg(feature = "symphonia")]
 use self::read_seek_source::ReadSeekSource;
 use crate::common::{ChannelCount, SampleRate};
 #[cfg(feature = "symphonia")]
 use ::symphonia::core::io::{MediaSource, MediaSourceStream};
 
+mod builder;
 #[cfg(all(feature = "flac", not(feature = "symphonia-flac")))]
 mod flac;
+mod following;
 #[cfg(all(feature = "minimp3", not(feature = "symphonia-mp3")))]
 mod mp3;
 #[cfg(feature = "symphonia")]
@@ -34,9 +46,47 @@ mod wav;
 /// Source of audio samples from decoding a file.
 ///
 /// Supports MP3, WAV, Vorbis and Flac.
-pub struct Decoder<R>(DecoderImpl<R>)
+pub struct Decoder<R>
 where
-    R: Read + Seek;
+    R: Read + Seek,
+{
+    inner: DecoderImpl<R>,
+    // Sub-range set with `DecoderBuilder::with_range`, positions are relative to its start.
+    range: Option<DecodeRange>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct DecodeRange {
+    start: Duration,
+    end: Duration,
+    // Samples left to produce before reaching `end`.
+    samples_left: u64,
+}
+
+/// How precisely [`Source::try_seek`] lands on the requested position, see
+/// [`DecoderBuilder::with_seek_mode`].
+///
+/// How each backend seeks:
+///
+/// - The Symphonia decoders jump close to the position using the index or bitrate of
+///   the format, then with [`SeekMode::Nearest`] decode up to the requested sample.
+/// - The native WAV, FLAC and Vorbis decoders always land on the requested sample.
+/// - The native MP3 decoder (`minimp3`) can not jump, it decodes from the start of the
+///   file up to the position. With [`SeekMode::Fastest`] it lands on the start of the
+///   MP3 frame containing the position, up to 1152 samples early, with
+///   [`SeekMode::Nearest`] on the requested sample. Either way seeking far into a long
+///   file takes a while.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SeekMode {
+    /// Jumps to the nearest point the format can seek to without decoding, for
+    /// example the start of a packet or an estimate from the bitrate. The position
+    /// can be off by a fraction of a second.
+    Fastest,
+    /// Decodes from the point [`SeekMode::Fastest`] jumps to up to the requested
+    /// position.
+    #[default]
+    Nearest,
+}
 
 /// Source of audio samples from decoding a file that never ends. When the
 /// end of the file is reached the decoder starts again from the beginning.
@@ -46,6 +96,20 @@ pub struct LoopedDecoder<R>(DecoderImpl<R>)
 where
     R: Read + Seek;
 
+/// Samples of a fully decoded file that can be played any number of times, built
+/// with [`Decoder::into_replayable`].
+///
+/// Cloning is cheap, all clones share the decoded samples. A clone continues from
+/// the position of the original, [`ReplayableDecoder::replay`] returns one that
+/// starts at the beginning.
+#[derive(Debug, Clone)]
+pub struct ReplayableDecoder {
+    samples: Arc<[f32]>,
+    pos: usize,
+    channels: ChannelCount,
+    sample_rate: SampleRate,
+}
+
 // Cannot really reduce the size of the VorbisDecoder. There are not any
 // arrays just a lot of struct fields.
 #[allow(clippy::large_enum_variant)]
@@ -62,7 +126,7 @@ where
     #[cfg(all(feature = "minimp3", not(feature = "symphonia-mp3")))]
     Mp3(mp3::Mp3Decoder<R>),
     #[cfg(feature = "symphonia")]
-    Symphonia(symphonia::SymphoniaDecoder),
+    Symphonia(symphonia::SymphoniaDecoder, Arc<Mutex<R>>),
     None(::std::marker::PhantomData<R>),
 }
 
@@ -79,7 +143,7 @@ impl<R: Read + Seek> DecoderImpl<R> {
             #[cfg(all(feature = "minimp3", not(feature = "symphonia-mp3")))]
             DecoderImpl::Mp3(source) => source.next(),
             #[cfg(feature = "symphonia")]
-            DecoderImpl::Symphonia(source) => source.next(),
+            DecoderImpl::Symphonia(source, _) => source.next(),
             DecoderImpl::None(_) => None,
         }
     }
@@ -96,7 +160,7 @@ impl<R: Read + Seek> DecoderImpl<R> {
             #[cfg(all(feature = "minimp3", not(feature = "symphonia-mp3")))]
             DecoderImpl::Mp3(source) => source.size_hint(),
             #[cfg(feature = "symphonia")]
-            DecoderImpl::Symphonia(source) => source.size_hint(),
+            DecoderImpl::Symphonia(source, _) => source.size_hint(),
             DecoderImpl::None(_) => (0, None),
         }
     }
@@ -113,7 +177,7 @@ impl<R: Read + Seek> DecoderImpl<R> {
             #[cfg(all(feature = "minimp3", not(feature = "symphonia-mp3")))]
             DecoderImpl::Mp3(source) => source.current_span_len(),
             #[cfg(feature = "symphonia")]
-            DecoderImpl::Symphonia(source) => source.current_span_len(),
+            DecoderImpl::Symphonia(source, _) => source.current_span_len(),
             DecoderImpl::None(_) => Some(0),
         }
     }
@@ -130,7 +194,7 @@ impl<R: Read + Seek> DecoderImpl<R> {
             #[cfg(all(feature = "minimp3", not(feature = "symphonia-mp3")))]
             DecoderImpl::Mp3(source) => source.channels(),
             #[cfg(feature = "symphonia")]
-            DecoderImpl::Symphonia(source) => source.channels(),
+            DecoderImpl::Symphonia(source, _) => source.channels(),
             DecoderImpl::None(_) => 0,
         }
     }
@@ -147,7 +211,7 @@ impl<R: Read + Seek> DecoderImpl<R> {
             #[cfg(all(feature = "minimp3", not(feature = "symphonia-mp3")))]
             DecoderImpl::Mp3(source) => source.sample_rate(),
             #[cfg(feature = "symphonia")]
-            DecoderImpl::Symphonia(source) => source.sample_rate(),
+            DecoderImpl::Symphonia(source, _) => source.sample_rate(),
             DecoderImpl::None(_) => 1,
         }
     }
@@ -164,7 +228,7 @@ impl<R: Read + Seek> DecoderImpl<R> {
             #[cfg(all(feature = "minimp3", not(feature = "symphonia-mp3")))]
             DecoderImpl::Mp3(source) => source.total_duration(),
             #[cfg(feature = "symphonia")]
-            DecoderImpl::Symphonia(source) => source.total_duration(),
+            DecoderImpl::Symphonia(source, _) => source.total_duration(),
             DecoderImpl::None(_) => Some(Duration::default()),
         }
     }
@@ -181,12 +245,78 @@ impl<R: Read + Seek> DecoderImpl<R> {
             #[cfg(all(feature = "minimp3", not(feature = "symphonia-mp3")))]
             DecoderImpl::Mp3(source) => source.try_seek(pos),
             #[cfg(feature = "symphonia")]
-            DecoderImpl::Symphonia(source) => source.try_seek(pos),
+            DecoderImpl::Symphonia(source, _) => source.try_seek(pos),
             DecoderImpl::None(_) => Err(SeekError::NotSupported {
                 underlying_source: "DecoderImpl::None",
             }),
         }
     }
+
+    fn total_frames(&self) -> Option<u64> {
+        match self {
+            #[cfg(all(feature = "wav", not(feature = "symphonia-wav")))]
+            DecoderImpl::Wav(source) => Some(source.total_frames()),
+            #[cfg(all(feature = "vorbis", not(feature = "symphonia-vorbis")))]
+            DecoderImpl::Vorbis(_) => None,
+            #[cfg(all(feature = "flac", not(feature = "symphonia-flac")))]
+            DecoderImpl::Flac(source) => source.total_frames(),
+            #[cfg(all(feature = "minimp3", not(feature = "symphonia-mp3")))]
+            DecoderImpl::Mp3(_) => None,
+            #[cfg(feature = "symphonia")]
+            DecoderImpl::Symphonia(source, _) => source.total_frames(),
+            DecoderImpl::None(_) => Some(0),
+        }
+    }
+
+    fn chapters(&self) -> Vec<Chapter> {
+        #[cfg(feature = "symphonia")]
+        if let DecoderImpl::Symphonia(source, _) = self {
+            return source.chapters();
+        }
+        Vec::new()
+    }
+
+    fn track_count(&self) -> usize {
+        #[cfg(feature = "symphonia")]
+        if let DecoderImpl::Symphonia(source, _) = self {
+            return source.track_count();
+        }
+        usize::from(!matches!(self, DecoderImpl::None(_)))
+    }
+
+    fn decode_error_count(&self) -> u64 {
+        #[cfg(feature = "symphonia")]
+        if let DecoderImpl::Symphonia(source, _) = self {
+            return source.decode_error_count();
+        }
+        0
+    }
+
+    #[allow(unused_variables)]
+    fn set_seek_mode(&mut self, mode: SeekMode) {
+        #[cfg(feature = "symphonia")]
+        if let DecoderImpl::Symphonia(source, _) = self {
+            source.set_seek_mode(mode);
+        }
+        #[cfg(all(feature = "minimp3", not(feature = "symphonia-mp3")))]
+        if let DecoderImpl::Mp3(source) = self {
+            source.set_seek_mode(mode);
+        }
+    }
+
+    fn try_seek_reported(&mut self, pos: Duration) -> Result<Duration, SeekError> {
+        #[cfg(feature = "symphonia")]
+        if let DecoderImpl::Symphonia(source, _) = self {
+            return source.seek_reported(pos);
+        }
+        #[cfg(all(feature = "minimp3", not(feature = "symphonia-mp3")))]
+        if let DecoderImpl::Mp3(source) = self {
+            return source.seek_reported(pos);
+        }
+        // The other decoders land on the requested sample, or stop at the end.
+        self.try_seek(pos)?;
+        Ok(self.total_duration().map_or(pos, |total| pos.min(total)))
+    }
 }
 
 impl<R> Decoder<R>
@@ -196,54 +326,211 @@ where
     /// Builds a new decoder.
     ///
     /// Attempts to automatically detect the format of the source of data.
-    #[allow(unused_variables)]
     pub fn new(data: R) -> Result<Decoder<R>, DecoderError> {
-        #[cfg(all(feature = "wav", not(feature = "symphonia-wav")))]
-        let data = match wav::WavDecoder::new(data) {
+        Self::open(data, None, 0)
+    }
+
+    /// Detects the format of the data, trying the format named by `extension` first,
+    /// and decodes the audio track at `track_index`.
+    #[allow(unused_variables)]
+    fn open(
+        data: R,
+        extension: Option<&str>,
+        track_index: usize,
+    ) -> Result<Decoder<R>, DecoderError> {
+        let data = match extension {
+            Some(extension) => match Self::probe_native(data, Some(extension)) {
+                Ok(decoder) => return Self::single_track(decoder, track_index),
+                Err(data) => data,
+            },
+            None => data,
+        };
+        let data = match Self::probe_native(data, None) {
+            Ok(decoder) => return Self::single_track(decoder, track_index),
             Err(data) => data,
-            Ok(decoder) => {
-                return Ok(Decoder(DecoderImpl::Wav(decoder)));
+        };
+
+        #[cfg(feature = "symphonia")]
+        {
+            let source = ReadSeekSource::new(data);
+            let reader = source.handle();
+            let mss = MediaSourceStream::new(
+                Box::new(source) as Box<dyn MediaSource>,
+                Default::default(),
+            );
+
+            match symphonia::SymphoniaDecoder::with_track(mss, extension, track_index) {
+                Err(e) => Err(e),
+                Ok(decoder) => Ok(Decoder::from_impl(DecoderImpl::Symphonia(decoder, reader))),
             }
+        }
+        #[cfg(not(feature = "symphonia"))]
+        Err(DecoderError::UnrecognizedFormat)
+    }
+
+    // The formats of the native decoders hold a single track.
+    fn single_track(decoder: Decoder<R>, track_index: usize) -> Result<Decoder<R>, DecoderError> {
+        match track_index {
+            0 => Ok(decoder),
+            _ => Err(DecoderError::NoStreams),
+        }
+    }
+
+    /// Reads the format of the data without decoding it.
+    ///
+    /// Only the headers are read, which is much faster than building a decoder when
+    /// scanning a large library. Fails if none of the enabled decoders recognizes the
+    /// data.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::fs::File;
+    /// use rodio::Decoder;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let info = Decoder::probe(File::open("assets/music.wav")?)?;
+    /// println!("{} Hz, {} channels", info.sample_rate, info.channels);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn probe(data: R) -> Result<ProbeResult, DecoderError> {
+        let data = match Self::probe_native_format(data) {
+            Ok(result) => return Ok(result),
+            Err(data) => data,
+        };
+
+        #[cfg(feature = "symphonia")]
+        {
+            let mss = MediaSourceStream::new(
+                Box::new(ReadSeekSource::new(data)) as Box<dyn MediaSource>,
+                Default::default(),
+            );
+            symphonia::SymphoniaDecoder::probe(mss)
+        }
+        #[cfg(not(feature = "symphonia"))]
+        {
+            drop(data);
+            Err(DecoderError::UnrecognizedFormat)
+        }
+    }
+
+    /// Reads the format of the data like [`Decoder::probe`], reusing the result of an
+    /// earlier probe of data that starts with the same bytes.
+    ///
+    /// Files of the same length whose first few kilobytes match are assumed to have the
+    /// same format, for example copies of a file or recordings made with the same
+    /// settings. Probing those again is skipped, which speeds up scanning a large
+    /// library.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::fs::File;
+    /// use rodio::decoder::ProbeCache;
+    /// use rodio::Decoder;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut cache = ProbeCache::new();
+    /// for path in ["assets/music.wav", "assets/beep.wav"] {
+    ///     let info = Decoder::probe_cached(File::open(path)?, &mut cache)?;
+    ///     println!("{path}: {} Hz", info.sample_rate);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn probe_cached(mut data: R, cache: &mut ProbeCache) -> Result<ProbeResult, DecoderError> {
+        let Ok(key) = ProbeCache::key(&mut data) else {
+            return Self::probe(data);
+        };
+        if let Some(result) = cache.entries.get(&key) {
+            cache.hits += 1;
+            return Ok(result.clone());
+        }
+        cache.misses += 1;
+        let result = Self::probe(data)?;
+        cache.entries.insert(key, result.clone());
+        Ok(result)
+    }
+
+    /// Reads the format of the data with the decoders that do not use symphonia, like
+    /// `probe_native` but from the headers only. Gives back the data if none of them
+    /// matched.
+    fn probe_native_format(data: R) -> Result<ProbeResult, R> {
+        #[cfg(all(feature = "wav", not(feature = "symphonia-wav")))]
+        let data = match wav::WavDecoder::probe(data) {
+            Err(data) => data,
+            Ok(result) => return Ok(result),
         };
 
         #[cfg(all(feature = "flac", not(feature = "symphonia-flac")))]
-        let data = match flac::FlacDecoder::new(data) {
+        let data = match flac::FlacDecoder::probe(data) {
             Err(data) => data,
-            Ok(decoder) => {
-                return Ok(Decoder(DecoderImpl::Flac(decoder)));
-            }
+            Ok(result) => return Ok(result),
         };
 
         #[cfg(all(feature = "vorbis", not(feature = "symphonia-vorbis")))]
-        let data = match vorbis::VorbisDecoder::new(data) {
+        let data = match vorbis::VorbisDecoder::probe(data) {
             Err(data) => data,
-            Ok(decoder) => {
-                return Ok(Decoder(DecoderImpl::Vorbis(decoder)));
-            }
+            Ok(result) => return Ok(result),
         };
 
         #[cfg(all(feature = "minimp3", not(feature = "symphonia-mp3")))]
-        let data = match mp3::Mp3Decoder::new(data) {
+        let data = match mp3::Mp3Decoder::probe(data) {
             Err(data) => data,
-            Ok(decoder) => {
-                return Ok(Decoder(DecoderImpl::Mp3(decoder)));
+            Ok(result) => return Ok(result),
+        };
+
+        Err(data)
+    }
+
+    /// Tries the decoders that do not use symphonia. If `only` is set just the decoder
+    /// for that file extension is tried. Gives back the data if none of them matched.
+    #[allow(unused_variables)]
+    fn probe_native(data: R, only: Option<&str>) -> Result<Decoder<R>, R> {
+        let tried = |extension: &str| only.is_none() || only == Some(extension);
+
+        #[cfg(all(feature = "wav", not(feature = "symphonia-wav")))]
+        let data = if tried("wav") {
+            match wav::WavDecoder::new(data) {
+                Err(data) => data,
+                Ok(decoder) => return Ok(Decoder::from_impl(DecoderImpl::Wav(decoder))),
             }
+        } else {
+            data
         };
 
-        #[cfg(feature = "symphonia")]
-        {
-            let mss = MediaSourceStream::new(
-                Box::new(ReadSeekSource::new(data)) as Box<dyn MediaSource>,
-                Default::default(),
-            );
+        #[cfg(all(feature = "flac", not(feature = "symphonia-flac")))]
+        let data = if tried("flac") {
+            match flac::FlacDecoder::new(data) {
+                Err(data) => data,
+                Ok(decoder) => return Ok(Decoder::from_impl(DecoderImpl::Flac(decoder))),
+            }
+        } else {
+            data
+        };
 
-            match symphonia::SymphoniaDecoder::new(mss, None) {
-                Err(e) => Err(e),
-                Ok(decoder) => Ok(Decoder(DecoderImpl::Symphonia(decoder))),
+        #[cfg(all(feature = "vorbis", not(feature = "symphonia-vorbis")))]
+        let data = if tried("ogg") {
+            match vorbis::VorbisDecoder::new(data) {
+                Err(data) => data,
+                Ok(decoder) => return Ok(Decoder::from_impl(DecoderImpl::Vorbis(decoder))),
             }
-        }
-        #[cfg(not(feature = "symphonia"))]
-        Err(DecoderError::UnrecognizedFormat)
+        } else {
+            data
+        };
+
+        #[cfg(all(feature = "minimp3", not(feature = "symphonia-mp3")))]
+        let data = if tried("mp3") {
+            match mp3::Mp3Decoder::new(data) {
+                Err(data) => data,
+                Ok(decoder) => return Ok(Decoder::from_impl(DecoderImpl::Mp3(decoder))),
+            }
+        } else {
+            data
+        };
+
+        Err(data)
     }
 
     /// Builds a new looped decoder.
@@ -258,7 +545,7 @@ where
     pub fn new_wav(data: R) -> Result<Decoder<R>, DecoderError> {
         match wav::WavDecoder::new(data) {
             Err(_) => Err(DecoderError::UnrecognizedFormat),
-            Ok(decoder) => Ok(Decoder(DecoderImpl::Wav(decoder))),
+            Ok(decoder) => Ok(Decoder::from_impl(DecoderImpl::Wav(decoder))),
         }
     }
 
@@ -273,7 +560,7 @@ where
     pub fn new_flac(data: R) -> Result<Decoder<R>, DecoderError> {
         match flac::FlacDecoder::new(data) {
             Err(_) => Err(DecoderError::UnrecognizedFormat),
-            Ok(decoder) => Ok(Decoder(DecoderImpl::Flac(decoder))),
+            Ok(decoder) => Ok(Decoder::from_impl(DecoderImpl::Flac(decoder))),
         }
     }
 
@@ -288,7 +575,7 @@ where
     pub fn new_vorbis(data: R) -> Result<Decoder<R>, DecoderError> {
         match vorbis::VorbisDecoder::new(data) {
             Err(_) => Err(DecoderError::UnrecognizedFormat),
-            Ok(decoder) => Ok(Decoder(DecoderImpl::Vorbis(decoder))),
+            Ok(decoder) => Ok(Decoder::from_impl(DecoderImpl::Vorbis(decoder))),
         }
     }
 
@@ -303,7 +590,7 @@ where
     pub fn new_mp3(data: R) -> Result<Decoder<R>, DecoderError> {
         match mp3::Mp3Decoder::new(data) {
             Err(_) => Err(DecoderError::UnrecognizedFormat),
-            Ok(decoder) => Ok(Decoder(DecoderImpl::Mp3(decoder))),
+            Ok(decoder) => Ok(Decoder::from_impl(DecoderImpl::Mp3(decoder))),
         }
     }
 
@@ -319,6 +606,18 @@ where
         Decoder::new_symphonia(data, "aac")
     }
 
+    /// Builds a new decoder from aiff data.
+    #[cfg(feature = "symphonia-aiff")]
+    pub fn new_aiff(data: R) -> Result<Decoder<R>, DecoderError> {
+        Decoder::new_symphonia(data, "aiff")
+    }
+
+    /// Builds a new decoder from caf data.
+    #[cfg(feature = "symphonia-caf")]
+    pub fn new_caf(data: R) -> Result<Decoder<R>, DecoderError> {
+        Decoder::new_symphonia(data, "caf")
+    }
+
     /// Builds a new decoder from mp4 data.
     #[cfg(feature = "symphonia-isomp4")]
     pub fn new_mp4(data: R, hint: Mp4Type) -> Result<Decoder<R>, DecoderError> {
@@ -327,18 +626,261 @@ where
 
     #[cfg(feature = "symphonia")]
     fn new_symphonia(data: R, hint: &str) -> Result<Decoder<R>, DecoderError> {
-        let mss = MediaSourceStream::new(
-            Box::new(ReadSeekSource::new(data)) as Box<dyn MediaSource>,
-            Default::default(),
-        );
+        let source = ReadSeekSource::new(data);
+        let reader = source.handle();
+        let mss =
+            MediaSourceStream::new(Box::new(source) as Box<dyn MediaSource>, Default::default());
 
         match symphonia::SymphoniaDecoder::new(mss, Some(hint)) {
             Err(e) => Err(e),
-            Ok(decoder) => Ok(Decoder(DecoderImpl::Symphonia(decoder))),
+            Ok(decoder) => Ok(Decoder::from_impl(DecoderImpl::Symphonia(decoder, reader))),
+        }
+    }
+}
+
+impl<R> Decoder<R>
+where
+    R: Read + Seek,
+{
+    #[allow(dead_code)] // Unused when no decoder is enabled
+    fn from_impl(inner: DecoderImpl<R>) -> Self {
+        Decoder { inner, range: None }
+    }
+
+    // Limits the decoder to `start..end` given that the next sample is at `pos`.
+    fn set_range(&mut self, start: Duration, end: Duration, pos: Duration) {
+        let frames = math::duration_to_frames(end.saturating_sub(pos), self.sample_rate());
+        self.range = Some(DecodeRange {
+            start,
+            end,
+            samples_left: frames * self.channels() as u64,
+        });
+    }
+
+    // Drops the samples from `landed`, where a seek that is not exact can leave the
+    // decoder, up to `pos`. Returns the position of the next sample.
+    fn skip_to(&mut self, landed: Duration, pos: Duration) -> Duration {
+        if landed >= pos {
+            return landed;
+        }
+        let frames = math::duration_to_frames(pos - landed, self.sample_rate());
+        for _ in 0..frames * self.channels() as u64 {
+            self.inner.next();
+        }
+        pos
+    }
+
+    /// Returns the number of frames in the file if its headers state it, for example
+    /// `1000` for 1000 samples on each channel.
+    ///
+    /// Unlike [`Source::total_duration`] this is exact. For a decoder built with
+    /// [`DecoderBuilder::with_range`] the frames of the range are counted, rounded to
+    /// the nearest frame.
+    pub fn total_frames(&self) -> Option<u64> {
+        match self.range {
+            Some(_) => self
+                .total_duration()
+                .map(|duration| math::duration_to_frames(duration, self.sample_rate())),
+            None => self.inner.total_frames(),
+        }
+    }
+
+    /// Returns the chapters marked in the file, in order, for example the tracks of a
+    /// DJ mix or the chapters of an audiobook.
+    ///
+    /// Only the Symphonia backend reads chapters, currently from FLAC cue sheets. For a
+    /// decoder built with [`DecoderBuilder::with_range`] the start times are relative to
+    /// the start of the range and chapters after its end are left out.
+    pub fn chapters(&self) -> Vec<Chapter> {
+        let mut chapters = self.inner.chapters();
+        if let Some(range) = self.range {
+            chapters.retain(|chapter| chapter.start < range.end);
+            for chapter in &mut chapters {
+                chapter.start = chapter.start.saturating_sub(range.start);
+            }
+            // Of the chapters that began before the range only the one playing is kept.
+            let playing = chapters
+                .iter()
+                .rposition(|chapter| chapter.start.is_zero())
+                .unwrap_or(0);
+            chapters.drain(..playing);
+        }
+        chapters
+    }
+
+    /// Returns the number of audio tracks in the file, for example the languages of a
+    /// film's soundtrack in an MP4 or MKV file. Choose one with
+    /// [`DecoderBuilder::with_track_index`].
+    ///
+    /// Only the Symphonia backend reads files with several tracks, the formats of the
+    /// other backends always hold a single one.
+    pub fn track_count(&self) -> usize {
+        self.inner.track_count()
+    }
+
+    /// Returns the number of packets skipped so far because they could not be
+    /// decoded, for example to tell the user that a damaged file was played.
+    ///
+    /// A few damaged packets in a row are skipped and playback continues after them.
+    /// Only the Symphonia backend counts them, the other backends always return `0`.
+    pub fn decode_error_count(&self) -> u64 {
+        self.inner.decode_error_count()
+    }
+
+    /// Seeks to `pos` like [`Source::try_seek`] and returns the position the decoder
+    /// actually landed on.
+    ///
+    /// The position can differ from `pos`, for example when seeking past the end or
+    /// because the format can only seek to whole units of its time base. A progress
+    /// bar can use the returned position to show where playback really continues.
+    pub fn try_seek_reported(&mut self, pos: Duration) -> Result<Duration, SeekError> {
+        match self.range {
+            Some(range) => {
+                let target = (range.start + pos).min(range.end);
+                let landed = self.inner.try_seek_reported(target)?;
+                let landed = self.skip_to(landed, range.start);
+                self.set_range(range.start, range.end, landed);
+                Ok(landed.saturating_sub(range.start))
+            }
+            None => self.inner.try_seek_reported(pos),
+        }
+    }
+
+    /// Decodes the rest of the file into memory, so it can be played again and again
+    /// without reading or decoding it another time. Useful for short sounds that play
+    /// often, for example the sound effects of a game.
+    ///
+    /// All samples are played with the channel count and sample rate the decoder has
+    /// when this is called.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::File::open("assets/beep.wav")?;
+    /// let beep = rodio::Decoder::new(std::io::BufReader::new(file))?.into_replayable();
+    /// // Each play shares the decoded samples.
+    /// let first = beep.replay();
+    /// let second = beep.replay();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn into_replayable(self) -> ReplayableDecoder {
+        let channels = self.channels();
+        let sample_rate = self.sample_rate();
+        ReplayableDecoder {
+            samples: self.map(Sample::to_f32).collect(),
+            pos: 0,
+            channels,
+            sample_rate,
+        }
+    }
+
+    pub(crate) fn set_seek_mode(&mut self, mode: SeekMode) {
+        self.inner.set_seek_mode(mode);
+    }
+
+    /// Consumes the decoder and returns the underlying reader.
+    ///
+    /// The position of the reader is wherever the decoder left it. Decoders read
+    /// ahead, so this is usually beyond the last sample returned. Seek it before
+    /// reusing it, for example back to the start to decode the data again.
+    pub fn into_inner(self) -> R {
+        match self.inner {
+            #[cfg(all(feature = "wav", not(feature = "symphonia-wav")))]
+            DecoderImpl::Wav(source) => source.into_inner(),
+            #[cfg(all(feature = "vorbis", not(feature = "symphonia-vorbis")))]
+            DecoderImpl::Vorbis(source) => source.into_inner().into_inner().into_inner(),
+            #[cfg(all(feature = "flac", not(feature = "symphonia-flac")))]
+            DecoderImpl::Flac(source) => source.into_inner(),
+            #[cfg(all(feature = "minimp3", not(feature = "symphonia-mp3")))]
+            DecoderImpl::Mp3(source) => source.into_inner(),
+            #[cfg(feature = "symphonia")]
+            DecoderImpl::Symphonia(source, reader) => {
+                // Dropping symphonia's media source stream releases its handle to the reader.
+                drop(source);
+                Arc::into_inner(reader)
+                    .expect("the decoder holds the only other handle to the reader")
+                    .into_inner()
+                    .unwrap_or_else(PoisonError::into_inner)
+            }
+            DecoderImpl::None(_) => unreachable!("a `Decoder` always has a backend"),
         }
     }
 }
 
+impl Decoder<BufReader<File>> {
+    /// Decodes a file that is still being written, for example a recording in
+    /// progress, and returns a handle that ends it.
+    ///
+    /// When the decoder reaches the end of the data written so far it plays silence
+    /// and checks every 20 milliseconds whether the file grew, instead of ending.
+    /// Once the returned [`FollowHandle`] is stopped it plays the rest of the data
+    /// and ends. New data is picked up by decoding the file again from its start and
+    /// seeking to where playback was, so the format has to support seeking and the
+    /// headers of the file have to be updated as it grows.
+    ///
+    /// # Errors
+    /// Fails if the file can not be opened or its format is not recognized, for
+    /// example because not even its headers have been written yet.
+    pub fn new_following(
+        path: impl AsRef<Path>,
+    ) -> Result<(FollowingDecoder, FollowHandle), DecoderError> {
+        let file = File::open(path).map_err(|err| DecoderError::IoError(err.to_string()))?;
+        FollowingDecoder::new(file)
+    }
+}
+
+impl<T> Decoder<Cursor<T>>
+where
+    T: AsRef<[u8]> + Send + Sync + 'static,
+{
+    /// Builds a new decoder from in-memory data using a hint about its format.
+    ///
+    /// The `hint` is either a file extension such as `"mp3"` or a MIME type such as
+    /// `"audio/mpeg"`, for example the `Content-Type` of an HTTP response. The hinted
+    /// format is tried first, if the data turns out to be in another format the
+    /// remaining formats are probed just like [`Decoder::new`] does.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let bytes = std::fs::read("assets/music.wav")?;
+    /// let decoder = rodio::Decoder::from_bytes_with_hint(bytes, "audio/wav")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_bytes_with_hint(data: T, hint: &str) -> Result<Self, DecoderError> {
+        let extension = extension_from_hint(hint);
+        Decoder::open(Cursor::new(data), extension.as_deref(), 0)
+    }
+}
+
+/// Turns a file extension or MIME type into the lowercase file extension used to
+/// select a decoder. Returns `None` for unknown MIME types.
+fn extension_from_hint(hint: &str) -> Option<String> {
+    let hint = hint.trim().to_ascii_lowercase();
+    if !hint.contains('/') {
+        return Some(hint.trim_start_matches('.').to_owned());
+    }
+
+    // Ignore parameters like in `audio/ogg; codecs=vorbis`
+    let mime = hint.split(';').next().unwrap_or_default().trim();
+    let extension = match mime {
+        "audio/mpeg" | "audio/mp3" | "audio/mpeg3" | "audio/x-mpeg-3" => "mp3",
+        "audio/wav" | "audio/wave" | "audio/x-wav" | "audio/vnd.wave" => "wav",
+        "audio/flac" | "audio/x-flac" => "flac",
+        "audio/ogg" | "audio/vorbis" | "application/ogg" => "ogg",
+        "audio/aac" | "audio/aacp" => "aac",
+        "audio/mp4" | "audio/m4a" | "audio/x-m4a" => "m4a",
+        "audio/aiff" | "audio/x-aiff" => "aiff",
+        "audio/x-caf" => "caf",
+        _ => return None,
+    };
+    Some(extension.to_owned())
+}
+
 #[allow(missing_docs)] // Reason: will be removed, see: #612
 #[derive(Debug)]
 pub enum Mp4Type {
@@ -388,7 +930,7 @@ where
     R: Read + Seek,
 {
     fn new(decoder: Decoder<R>) -> LoopedDecoder<R> {
-        Self(decoder.0)
+        Self(decoder.inner)
     }
 }
 
@@ -400,12 +942,28 @@ where
 
     #[inline]
     fn next(&mut self) -> Option<i16> {
-        self.0.next()
+        if let Some(range) = &mut self.range {
+            if range.samples_left == 0 {
+                return None;
+            }
+            range.samples_left -= 1;
+        }
+        self.inner.next()
     }
 
     #[inline]
     fn size_hint(&self) -> (usize, Option<usize>) {
-        self.0.size_hint()
+        let (lower, upper) = self.inner.size_hint();
+        match self.range {
+            Some(range) => {
+                let left = usize::try_from(range.samples_left).unwrap_or(usize::MAX);
+                (
+                    lower.min(left),
+                    Some(upper.map_or(left, |upper| upper.min(left))),
+                )
+            }
+            None => (lower, upper),
+        }
     }
 }
 
@@ -415,26 +973,42 @@ where
 {
     #[inline]
     fn current_span_len(&self) -> Option<usize> {
-        self.0.current_span_len()
+        let len = self.inner.current_span_len();
+        match self.range {
+            Some(range) => {
+                let left = usize::try_from(range.samples_left).unwrap_or(usize::MAX);
+                Some(len.map_or(left, |len| len.min(left)))
+            }
+            None => len,
+        }
     }
 
     #[inline]
     fn channels(&self) -> ChannelCount {
-        self.0.channels()
+        self.inner.channels()
     }
 
     fn sample_rate(&self) -> SampleRate {
-        self.0.sample_rate()
+        self.inner.sample_rate()
     }
 
     #[inline]
     fn total_duration(&self) -> Option<Duration> {
-        self.0.total_duration()
+        match self.range {
+            Some(range) => {
+                let end = match self.inner.total_duration() {
+                    Some(total) => total.min(range.end),
+                    None => range.end,
+                };
+                Some(end.saturating_sub(range.start))
+            }
+            None => self.inner.total_duration(),
+        }
     }
 
     #[inline]
     fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
-        self.0.try_seek(pos)
+        self.try_seek_reported(pos).map(drop)
     }
 }
 
@@ -487,12 +1061,12 @@ where
                     (DecoderImpl::Mp3(source), sample)
                 }
                 #[cfg(feature = "symphonia")]
-                DecoderImpl::Symphonia(source) => {
+                DecoderImpl::Symphonia(source, handle) => {
                     let mut reader = source.into_inner();
                     reader.seek(SeekFrom::Start(0)).ok()?;
                     let mut source = symphonia::SymphoniaDecoder::new(reader, None).ok()?;
                     let sample = source.next();
-                    (DecoderImpl::Symphonia(source), sample)
+                    (DecoderImpl::Symphonia(source, handle), sample)
                 }
                 none @ DecoderImpl::None(_) => (none, None),
             };
@@ -536,6 +1110,158 @@ where
     }
 }
 
+impl ReplayableDecoder {
+    /// Returns a source that plays the sound from the beginning.
+    #[inline]
+    pub fn replay(&self) -> ReplayableDecoder {
+        ReplayableDecoder {
+            pos: 0,
+            ..self.clone()
+        }
+    }
+}
+
+impl Iterator for ReplayableDecoder {
+    type Item = f32;
+
+    #[inline]
+    fn next(&mut self) -> Option<f32> {
+        let sample = self.samples.get(self.pos).copied()?;
+        self.pos += 1;
+        Some(sample)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = self.samples.len() - self.pos;
+        (left, Some(left))
+    }
+}
+
+impl ExactSizeIterator for ReplayableDecoder {}
+
+impl Source for ReplayableDecoder {
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.channels
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.sample_rate
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        let frames = math::samples_to_frames(self.samples.len(), self.channels);
+        Some(math::frames_to_duration(frames as u64, self.sample_rate))
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        let channels = self.channels as usize;
+        // Keeps the channel of the next sample.
+        let channel = self.pos % channels;
+        let frames = math::duration_to_frames(pos, self.sample_rate);
+        let frame_start = usize::try_from(frames)
+            .unwrap_or(usize::MAX)
+            .saturating_mul(channels)
+            .min(self.samples.len() / channels * channels);
+        self.pos = (frame_start + channel).min(self.samples.len());
+        Ok(())
+    }
+}
+
+/// Format of audio data, returned by [`Decoder::probe`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProbeResult {
+    /// Short name of the codec, for example `"mp3"` or `"flac"`.
+    pub codec: &'static str,
+    /// Number of channels.
+    pub channels: ChannelCount,
+    /// Number of frames per second.
+    pub sample_rate: SampleRate,
+    /// Duration of the audio, if the headers contain it.
+    pub total_duration: Option<Duration>,
+    /// Bits per sample of the encoded audio, if the codec stores samples at a fixed
+    /// bit depth.
+    pub bits_per_sample: Option<u32>,
+}
+
+/// Formats found by [`Decoder::probe_cached`], keyed on the start and length of the
+/// probed data.
+#[derive(Debug, Clone, Default)]
+pub struct ProbeCache {
+    // Hash of the first `ProbeCache::SIGNATURE_LEN` bytes and the length of the data.
+    entries: HashMap<(u64, u64), ProbeResult>,
+    hits: u64,
+    misses: u64,
+}
+
+impl ProbeCache {
+    // Long enough to hold the headers of common formats including a short ID3 tag.
+    const SIGNATURE_LEN: u64 = 4096;
+
+    /// Creates an empty cache.
+    pub fn new() -> ProbeCache {
+        ProbeCache::default()
+    }
+
+    /// Returns the number of probes answered from the cache.
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Returns the number of probes that had to read the data.
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    /// Returns the number of formats in the cache.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if nothing has been probed yet.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Forgets every format, for example after files changed on disk.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    // Reads the signature of `data` and returns to where it started.
+    fn key<R: Read + Seek>(data: &mut R) -> std::io::Result<(u64, u64)> {
+        let start = data.stream_position()?;
+        let mut signature = Vec::new();
+        data.by_ref()
+            .take(Self::SIGNATURE_LEN)
+            .read_to_end(&mut signature)?;
+        let len = data.seek(SeekFrom::End(0))?.saturating_sub(start);
+        data.seek(SeekFrom::Start(start))?;
+
+        let mut hasher = DefaultHasher::new();
+        signature.hash(&mut hasher);
+        Ok((hasher.finish(), len))
+    }
+}
+
+/// A chapter of a file, returned by [`Decoder::chapters`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chapter {
+    /// Position where the chapter starts.
+    pub start: Duration,
+    /// Title of the chapter, if the file names it.
+    pub title: Option<String>,
+}
+
 /// Error that can happen when creating a decoder.
 #[derive(Debug, Clone)]
 pub enum DecoderError {
@@ -543,7 +1269,6 @@ pub enum DecoderError {
     UnrecognizedFormat,
 
     /// An IO error occurred while reading, writing, or seeking the stream.
-    #[cfg(feature = "symphonia")]
     IoError(String),
 
     /// The stream contained malformed data and could not be decoded or demuxed.
@@ -559,8 +1284,8 @@ pub enum DecoderError {
     #[cfg(feature = "symphonia")]
     ResetRequired,
 
-    /// No streams were found by the decoder
-    #[cfg(feature = "symphonia")]
+    /// No streams were found by the decoder, or none with the track index given to
+    /// [`DecoderBuilder::with_track_index`].
     NoStreams,
 }
 
@@ -568,7 +1293,6 @@ impl fmt::Display for DecoderError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let text = match self {
             DecoderError::UnrecognizedFormat => "Unrecognized format",
-            #[cfg(feature = "symphonia")]
             DecoderError::IoError(msg) => &msg[..],
             #[cfg(feature = "symphonia")]
             DecoderError::DecodeError(msg) => msg,
@@ -576,7 +1300,6 @@ impl fmt::Display for DecoderError {
             DecoderError::LimitError(msg) => msg,
             #[cfg(feature = "symphonia")]
             DecoderError::ResetRequired => "Reset required",
-            #[cfg(feature = "symphonia")]
             DecoderError::NoStreams => "No streams",
         };
         write!(f, "{text}")
@@ -1,6 +1,9 @@
 use std::io::{Read, Seek, SeekFrom};
 use std::time::Duration;
 
+use super::{ProbeResult, SeekMode};
+use crate::common::{ChannelCount, SampleRate};
+use crate::math;
 use crate::source::SeekError;
 use crate::Source;
 
@@ -13,10 +16,14 @@ where
     R: Read + Seek,
 {
     // decoder: SeekDecoder<R>,
-    decoder: Decoder<R>,
+    // Only `None` while a seek rebuilds it.
+    decoder: Option<Decoder<R>>,
     // what minimp3 calls frames rodio calls spans
     current_span: minimp3::Frame,
     current_span_offset: usize,
+    // Position of the mp3 data in the reader, seeking decodes again from there.
+    start: u64,
+    seek_mode: SeekMode,
 }
 
 impl<R> Mp3Decoder<R>
@@ -27,6 +34,9 @@ where
         if !is_mp3(data.by_ref()) {
             return Err(data);
         }
+        let Ok(start) = data.stream_position() else {
+            return Err(data);
+        };
         // let mut decoder = SeekDecoder::new(data)
         let mut decoder = Decoder::new(data);
         // parameters are correct and minimp3 is used correctly
@@ -39,13 +49,75 @@ where
             .expect("data should not corrupt");
 
         Ok(Mp3Decoder {
-            decoder,
+            decoder: Some(decoder),
             current_span,
             current_span_offset: 0,
+            start,
+            seek_mode: SeekMode::default(),
+        })
+    }
+    /// Reads the format from the first frame of MP3 data, minimp3 can not read a
+    /// frame header on its own. Gives back the data if it is not MP3.
+    pub(crate) fn probe(data: R) -> Result<ProbeResult, R> {
+        let decoder = Self::new(data)?;
+        Ok(ProbeResult {
+            codec: "mp3",
+            channels: decoder.channels(),
+            sample_rate: decoder.sample_rate(),
+            total_duration: None,
+            bits_per_sample: None,
         })
     }
+
     pub fn into_inner(self) -> R {
-        self.decoder.into_inner()
+        self.decoder.expect("only taken while seeking").into_inner()
+    }
+
+    pub(crate) fn set_seek_mode(&mut self, seek_mode: SeekMode) {
+        self.seek_mode = seek_mode;
+    }
+
+    fn decoder(&mut self) -> &mut Decoder<R> {
+        self.decoder.as_mut().expect("only taken while seeking")
+    }
+
+    // minimp3 can not jump to a position, so this decodes from the start and
+    // discards spans up to the one that contains `pos`. Returns the position landed on.
+    pub(crate) fn seek_reported(&mut self, pos: Duration) -> Result<Duration, SeekError> {
+        let mut data = self
+            .decoder
+            .take()
+            .expect("only taken while seeking")
+            .into_inner();
+        let rewound = data.seek(SeekFrom::Start(self.start));
+        self.decoder = Some(Decoder::new(data));
+        rewound.map_err(SeekError::Minimp3Decoder)?;
+
+        let mut span_start = Duration::ZERO;
+        loop {
+            let Ok(span) = self.decoder().next_frame() else {
+                // Past the end, the next call to `next` ends the source.
+                self.current_span_offset = self.current_span.data.len();
+                return Ok(span_start);
+            };
+            let channels = span.channels.max(1);
+            let frames = (span.data.len() / channels) as u64;
+            let sample_rate = span.sample_rate.max(1) as SampleRate;
+            let span_end = span_start + math::frames_to_duration(frames, sample_rate);
+            self.current_span = span;
+            if span_end > pos {
+                let frame = match self.seek_mode {
+                    // Lands on the start of the span.
+                    SeekMode::Fastest => 0,
+                    SeekMode::Nearest => {
+                        math::duration_to_frames(pos - span_start, sample_rate).min(frames)
+                    }
+                };
+                self.current_span_offset = frame as usize * channels;
+                return Ok(span_start + math::frames_to_duration(frame, sample_rate));
+            }
+            span_start = span_end;
+        }
     }
 }
 
@@ -74,17 +146,9 @@ where
     }
 
     fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
-        // TODO waiting for PR in minimp3_fixed or minimp3
-
-        // let pos = (pos.as_secs_f32() * self.sample_rate() as f32) as u64;
-        // // do not trigger a sample_rate, channels and frame/span len update
-        // // as the seek only takes effect after the current frame/span is done
-        // self.decoder.seek_samples(pos)?;
-        // Ok(())
-
-        Err(SeekError::NotSupported {
-            underlying_source: std::any::type_name::<Self>(),
-        })
+        // TODO use a seek in minimp3_fixed or minimp3 once there is one, scanning
+        // decodes everything before `pos`.
+        self.seek_reported(pos).map(|_| ())
     }
 }
 
@@ -96,7 +160,7 @@ where
 
     fn next(&mut self) -> Option<i16> {
         if self.current_span_offset == self.current_span_len().unwrap() {
-            if let Ok(span) = self.decoder.next_frame() {
+            if let Ok(span) = self.decoder().next_frame() {
                 // if let Ok(span) = self.decoder.decode_frame() {
                 self.current_span = span;
                 self.current_span_offset = 0;
@@ -1,16 +1,29 @@
 use std::io::{Read, Result, Seek, SeekFrom};
+use std::sync::{Arc, Mutex};
 
 use symphonia::core::io::MediaSource;
 
 pub struct ReadSeekSource<T: Read + Seek + Send + Sync> {
-    inner: T,
+    inner: Arc<Mutex<T>>,
+    // Length of the data when the source was made, `None` if it could not be found.
+    byte_len: Option<u64>,
 }
 
 impl<T: Read + Seek + Send + Sync> ReadSeekSource<T> {
     /// Instantiates a new `ReadSeekSource<T>` by taking ownership and wrapping the provided
     /// `Read + Seek`er.
-    pub fn new(inner: T) -> Self {
-        ReadSeekSource { inner }
+    pub fn new(mut inner: T) -> Self {
+        let byte_len = stream_len(&mut inner);
+        ReadSeekSource {
+            inner: Arc::new(Mutex::new(inner)),
+            byte_len,
+        }
+    }
+
+    /// Returns a handle to the wrapped `Read + Seek`er. Symphonia does not give
+    /// back the media source once it owns it, this handle is used to recover it.
+    pub fn handle(&self) -> Arc<Mutex<T>> {
+        Arc::clone(&self.inner)
     }
 }
 
@@ -20,18 +33,28 @@ impl<T: Read + Seek + Send + Sync> MediaSource for ReadSeekSource<T> {
     }
 
     fn byte_len(&self) -> Option<u64> {
-        None
+        self.byte_len
     }
 }
 
+// Symphonia needs the length for coarse seeking in formats without an index, such as
+// MP3, which estimate the position from the bitrate. Found once by seeking to the end
+// and back.
+fn stream_len(inner: &mut impl Seek) -> Option<u64> {
+    let pos = inner.stream_position().ok()?;
+    let len = inner.seek(SeekFrom::End(0)).ok()?;
+    inner.seek(SeekFrom::Start(pos)).ok()?;
+    Some(len)
+}
+
 impl<T: Read + Seek + Send + Sync> Read for ReadSeekSource<T> {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
-        self.inner.read(buf)
+        self.inner.lock().unwrap().read(buf)
     }
 }
 
 impl<T: Read + Seek + Send + Sync> Seek for ReadSeekSource<T> {
     fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
-        self.inner.seek(pos)
+        self.inner.lock().unwrap().seek(pos)
     }
 }
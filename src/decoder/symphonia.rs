@@ -5,16 +5,16 @@ use symphonia::{
         audio::{AudioBufferRef, SampleBuffer, SignalSpec},
         codecs::{Decoder, DecoderOptions, CODEC_TYPE_NULL},
         errors::Error,
-        formats::{FormatOptions, FormatReader, SeekedTo},
+        formats::{FormatOptions, FormatReader, Packet, SeekedTo, Track},
         io::MediaSourceStream,
-        meta::MetadataOptions,
+        meta::{MetadataOptions, StandardTagKey},
         probe::Hint,
         units::{self, Time},
     },
     default::get_probe,
 };
 
-use super::DecoderError;
+use super::{DecoderError, ProbeResult};
 use crate::common::{ChannelCount, SampleRate};
 use crate::{source, Source};
 
@@ -27,9 +27,15 @@ pub(crate) struct SymphoniaDecoder {
     decoder: Box<dyn Decoder>,
     current_span_offset: usize,
     format: Box<dyn FormatReader>,
+    // Track that is decoded, packets of the other tracks are skipped.
+    track_id: u32,
     total_duration: Option<Time>,
+    total_frames: Option<u64>,
     buffer: SampleBuffer<i16>,
     spec: SignalSpec,
+    seek_mode: super::SeekMode,
+    // Packets skipped because they could not be decoded.
+    decode_errors: u64,
 }
 
 impl SymphoniaDecoder {
@@ -37,22 +43,57 @@ impl SymphoniaDecoder {
         mss: MediaSourceStream,
         extension: Option<&str>,
     ) -> Result<Self, DecoderError> {
-        match SymphoniaDecoder::init(mss, extension) {
-            Err(e) => match e {
-                Error::IoError(e) => Err(DecoderError::IoError(e.to_string())),
-                Error::DecodeError(e) => Err(DecoderError::DecodeError(e)),
-                Error::SeekError(_) => {
-                    unreachable!("Seek errors should not occur during initialization")
-                }
-                Error::Unsupported(_) => Err(DecoderError::UnrecognizedFormat),
-                Error::LimitError(e) => Err(DecoderError::LimitError(e)),
-                Error::ResetRequired => Err(DecoderError::ResetRequired),
-            },
+        SymphoniaDecoder::with_track(mss, extension, 0)
+    }
+
+    /// Decodes the audio track at `track_index` among the audio tracks of the file.
+    pub(crate) fn with_track(
+        mss: MediaSourceStream,
+        extension: Option<&str>,
+        track_index: usize,
+    ) -> Result<Self, DecoderError> {
+        match SymphoniaDecoder::init(mss, extension, track_index) {
+            Err(e) => Err(init_error(e)),
             Ok(Some(decoder)) => Ok(decoder),
             Ok(None) => Err(DecoderError::NoStreams),
         }
     }
 
+    /// Reads the format of the first supported track from its headers, without
+    /// decoding any packets.
+    pub(crate) fn probe(mss: MediaSourceStream) -> Result<ProbeResult, DecoderError> {
+        let format_opts = FormatOptions {
+            enable_gapless: true,
+            ..Default::default()
+        };
+        let probed = get_probe()
+            .format(&Hint::new(), mss, &format_opts, &Default::default())
+            .map_err(init_error)?;
+        let params = &audio_tracks(probed.format.tracks())
+            .next()
+            .ok_or(DecoderError::NoStreams)?
+            .codec_params;
+        let codec = symphonia::default::get_codecs()
+            .get_codec(params.codec)
+            .ok_or(DecoderError::UnrecognizedFormat)?;
+        let (Some(channels), Some(sample_rate)) = (params.channels, params.sample_rate) else {
+            return Err(DecoderError::DecodeError(
+                "the headers do not contain the channel count or sample rate",
+            ));
+        };
+        let total_duration = params
+            .time_base
+            .zip(params.n_frames)
+            .map(|(base, frames)| time_to_duration(base.calc_time(frames)));
+        Ok(ProbeResult {
+            codec: codec.short_name,
+            channels: channels.count() as ChannelCount,
+            sample_rate,
+            total_duration,
+            bits_per_sample: params.bits_per_sample,
+        })
+    }
+
     pub(crate) fn into_inner(self) -> MediaSourceStream {
         self.format.into_inner()
     }
@@ -60,6 +101,7 @@ impl SymphoniaDecoder {
     fn init(
         mss: MediaSourceStream,
         extension: Option<&str>,
+        track_index: usize,
     ) -> symphonia::core::errors::Result<Option<SymphoniaDecoder>> {
         let mut hint = Hint::new();
         if let Some(ext) = extension {
@@ -72,36 +114,29 @@ impl SymphoniaDecoder {
         let metadata_opts: MetadataOptions = Default::default();
         let mut probed = get_probe().format(&hint, mss, &format_opts, &metadata_opts)?;
 
-        let stream = match probed.format.default_track() {
-            Some(stream) => stream,
+        if probed.format.default_track().is_none() {
+            return Ok(None);
+        }
+
+        let track = match audio_tracks(probed.format.tracks()).nth(track_index) {
+            Some(track) => track,
+            None if track_index == 0 => {
+                return Err(symphonia::core::errors::Error::Unsupported(
+                    "No track with supported codec",
+                ))
+            }
             None => return Ok(None),
         };
-
-        // Select the first supported track
-        let track_id = probed
-            .format
-            .tracks()
-            .iter()
-            .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
-            .ok_or(symphonia::core::errors::Error::Unsupported(
-                "No track with supported codec",
-            ))?
-            .id;
-
-        let track = probed
-            .format
-            .tracks()
-            .iter()
-            .find(|track| track.id == track_id)
-            .unwrap();
+        let track_id = track.id;
 
         let mut decoder = symphonia::default::get_codecs()
             .make(&track.codec_params, &DecoderOptions::default())?;
-        let total_duration = stream
+        let total_duration = track
             .codec_params
             .time_base
-            .zip(stream.codec_params.n_frames)
+            .zip(track.codec_params.n_frames)
             .map(|(base, spans)| base.calc_time(spans));
+        let total_frames = track.codec_params.n_frames;
 
         let mut decode_errors: usize = 0;
         let decoded = loop {
@@ -137,9 +172,13 @@ impl SymphoniaDecoder {
             decoder,
             current_span_offset: 0,
             format: probed.format,
+            track_id,
             total_duration,
+            total_frames,
             buffer,
             spec,
+            seek_mode: super::SeekMode::default(),
+            decode_errors: decode_errors as u64,
         }))
     }
 
@@ -152,6 +191,26 @@ impl SymphoniaDecoder {
     }
 }
 
+// The tracks a decoder can play, in the order the file lists them.
+fn audio_tracks(tracks: &[Track]) -> impl Iterator<Item = &Track> {
+    tracks
+        .iter()
+        .filter(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+}
+
+fn init_error(e: Error) -> DecoderError {
+    match e {
+        Error::IoError(e) => DecoderError::IoError(e.to_string()),
+        Error::DecodeError(e) => DecoderError::DecodeError(e),
+        Error::SeekError(_) => {
+            unreachable!("Seek errors should not occur during initialization")
+        }
+        Error::Unsupported(_) => DecoderError::UnrecognizedFormat,
+        Error::LimitError(e) => DecoderError::LimitError(e),
+        Error::ResetRequired => DecoderError::ResetRequired,
+    }
+}
+
 impl Source for SymphoniaDecoder {
     #[inline]
     fn current_span_len(&self) -> Option<usize> {
@@ -174,6 +233,61 @@ impl Source for SymphoniaDecoder {
     }
 
     fn try_seek(&mut self, pos: Duration) -> Result<(), source::SeekError> {
+        self.seek_reported(pos).map(drop)
+    }
+}
+
+impl SymphoniaDecoder {
+    pub(crate) fn total_frames(&self) -> Option<u64> {
+        self.total_frames
+    }
+
+    pub(crate) fn chapters(&self) -> Vec<super::Chapter> {
+        let params = self.decoder.codec_params();
+        let Some(time_base) = params.time_base else {
+            return Vec::new();
+        };
+        self.format
+            .cues()
+            .iter()
+            // The lead-out of a cue sheet marks the end of the last chapter.
+            .filter(|cue| cue.index != 170 && cue.index != 255)
+            .map(|cue| super::Chapter {
+                start: time_to_duration(time_base.calc_time(cue.start_ts)),
+                title: cue
+                    .tags
+                    .iter()
+                    .find(|tag| tag.std_key == Some(StandardTagKey::TrackTitle))
+                    .map(|tag| tag.value.to_string()),
+            })
+            .collect()
+    }
+
+    pub(crate) fn track_count(&self) -> usize {
+        audio_tracks(self.format.tracks()).count()
+    }
+
+    // Reads the next packet of the decoded track.
+    fn next_packet(&mut self) -> symphonia::core::errors::Result<Packet> {
+        loop {
+            let packet = self.format.next_packet()?;
+            if packet.track_id() == self.track_id {
+                return Ok(packet);
+            }
+        }
+    }
+
+    pub(crate) fn decode_error_count(&self) -> u64 {
+        self.decode_errors
+    }
+
+    pub(crate) fn set_seek_mode(&mut self, seek_mode: super::SeekMode) {
+        self.seek_mode = seek_mode;
+    }
+
+    /// Seeks to `pos` and returns the position of the next sample, which can differ
+    /// from `pos` by the rounding to the time base of the track or near the end.
+    pub(crate) fn seek_reported(&mut self, pos: Duration) -> Result<Duration, source::SeekError> {
         use symphonia::core::formats::{SeekMode, SeekTo};
 
         let seek_beyond_end = self
@@ -190,21 +304,38 @@ impl Source for SymphoniaDecoder {
         // make sure the next sample is for the right channel
         let to_skip = self.current_span_offset % self.channels() as usize;
 
-        let seek_res = self
+        let mode = match self.seek_mode {
+            super::SeekMode::Fastest => SeekMode::Coarse,
+            super::SeekMode::Nearest => SeekMode::Accurate,
+        };
+        let mut seek_res = self
             .format
             .seek(
-                SeekMode::Accurate,
+                mode,
                 SeekTo::Time {
                     time,
-                    track_id: None,
+                    track_id: Some(self.track_id),
                 },
             )
             .map_err(SeekError::BaseSeek)?;
+        if self.seek_mode == super::SeekMode::Fastest {
+            // Play from wherever the format reader jumped to.
+            seek_res.required_ts = seek_res.actual_ts;
+        }
+        let landed = self
+            .format
+            .tracks()
+            .iter()
+            .find(|track| track.id == seek_res.track_id)
+            .and_then(|track| track.codec_params.time_base)
+            .map_or(pos, |base| {
+                time_to_duration(base.calc_time(seek_res.required_ts))
+            });
 
         self.refine_position(seek_res)?;
         self.current_span_offset += to_skip;
 
-        Ok(())
+        Ok(landed)
     }
 }
 
@@ -266,7 +397,7 @@ impl SymphoniaDecoder {
     fn refine_position(&mut self, seek_res: SeekedTo) -> Result<(), source::SeekError> {
         let mut samples_to_pass = seek_res.required_ts - seek_res.actual_ts;
         let packet = loop {
-            let candidate = self.format.next_packet().map_err(SeekError::Refining)?;
+            let candidate = self.next_packet().map_err(SeekError::Refining)?;
             if candidate.dur() > samples_to_pass {
                 break candidate;
             } else {
@@ -277,7 +408,8 @@ impl SymphoniaDecoder {
         let mut decoded = self.decoder.decode(&packet);
         for _ in 0..MAX_DECODE_RETRIES {
             if decoded.is_err() {
-                let packet = self.format.next_packet().map_err(SeekError::Retrying)?;
+                self.decode_errors += 1;
+                let packet = self.next_packet().map_err(SeekError::Retrying)?;
                 decoded = self.decoder.decode(&packet);
             }
         }
@@ -305,14 +437,7 @@ fn skip_back_a_tiny_bit(
 }
 
 fn time_to_duration(time: Time) -> Duration {
-    Duration::new(
-        time.seconds,
-        if time.frac > 0.0 {
-            (1f64 / time.frac) as u32
-        } else {
-            0
-        },
-    )
+    Duration::new(time.seconds, (time.frac * 1e9) as u32)
 }
 
 impl Iterator for SymphoniaDecoder {
@@ -321,11 +446,12 @@ impl Iterator for SymphoniaDecoder {
     #[inline]
     fn next(&mut self) -> Option<i16> {
         if self.current_span_offset >= self.buffer.len() {
-            let packet = self.format.next_packet().ok()?;
+            let packet = self.next_packet().ok()?;
             let mut decoded = self.decoder.decode(&packet);
             for _ in 0..MAX_DECODE_RETRIES {
                 if decoded.is_err() {
-                    let packet = self.format.next_packet().ok()?;
+                    self.decode_errors += 1;
+                    let packet = self.next_packet().ok()?;
                     decoded = self.decoder.decode(&packet);
                 }
             }
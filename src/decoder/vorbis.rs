@@ -4,6 +4,7 @@ use std::time::Duration;
 use crate::source::SeekError;
 use crate::Source;
 
+use super::ProbeResult;
 use crate::common::{ChannelCount, SampleRate};
 use lewton::inside_ogg::OggStreamReader;
 
@@ -30,6 +31,23 @@ where
         let stream_reader = OggStreamReader::new(data).unwrap();
         Ok(Self::from_stream_reader(stream_reader))
     }
+
+    /// Reads the format from the headers of ogg/vorbis data, without decoding any
+    /// audio. Gives back the data if it is not ogg/vorbis.
+    pub(crate) fn probe(mut data: R) -> Result<ProbeResult, R> {
+        if !is_vorbis(data.by_ref()) {
+            return Err(data);
+        }
+
+        let header = OggStreamReader::new(data).unwrap().ident_hdr;
+        Ok(ProbeResult {
+            codec: "vorbis",
+            channels: header.audio_channels as ChannelCount,
+            sample_rate: header.audio_sample_rate,
+            total_duration: None,
+            bits_per_sample: None,
+        })
+    }
     pub fn from_stream_reader(mut stream_reader: OggStreamReader<R>) -> Self {
         let mut data = match stream_reader.read_dec_packet_itl() {
             Ok(Some(d)) => d,
@@ -1,11 +1,13 @@
 use std::io::{Read, Seek, SeekFrom};
 use std::time::Duration;
 
+use crate::math;
 use crate::source::SeekError;
 use crate::Source;
 
+use super::ProbeResult;
 use crate::common::{ChannelCount, SampleRate};
-use hound::{SampleFormat, WavReader};
+use hound::{SampleFormat, WavReader, WavSpec};
 
 /// Decoder for the WAV format.
 pub struct WavDecoder<R>
@@ -36,18 +38,36 @@ where
             samples_read: 0,
         };
 
-        let sample_rate = spec.sample_rate;
-        let channels = spec.channels;
-        let total_duration =
-            Duration::from_micros((1_000_000 * len) / (sample_rate as u64 * channels as u64));
-
         Ok(WavDecoder {
             reader,
-            total_duration,
-            sample_rate: sample_rate as SampleRate,
-            channels: channels as ChannelCount,
+            total_duration: total_duration(spec, len),
+            sample_rate: spec.sample_rate as SampleRate,
+            channels: spec.channels as ChannelCount,
         })
     }
+
+    /// Reads the format from the header of WAV data, without reading any samples.
+    /// Gives back the data if it is not WAV.
+    pub(crate) fn probe(mut data: R) -> Result<ProbeResult, R> {
+        if !is_wave(data.by_ref()) {
+            return Err(data);
+        }
+
+        let reader = WavReader::new(data).unwrap();
+        let spec = reader.spec();
+        Ok(ProbeResult {
+            codec: "wav",
+            channels: spec.channels as ChannelCount,
+            sample_rate: spec.sample_rate as SampleRate,
+            total_duration: Some(total_duration(spec, reader.len() as u64)),
+            bits_per_sample: Some(spec.bits_per_sample as u32),
+        })
+    }
+    pub fn total_frames(&self) -> u64 {
+        self.reader.reader.duration() as u64
+    }
+
+    #[inline]
     pub fn into_inner(self) -> R {
         self.reader.reader.into_inner()
     }
@@ -134,9 +154,8 @@ where
     fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
         let file_len = self.reader.reader.duration();
 
-        let new_pos = pos.as_secs_f32() * self.sample_rate() as f32;
-        let new_pos = new_pos as u32;
-        let new_pos = new_pos.min(file_len); // saturate pos at the end of the source
+        let new_pos = math::duration_to_frames(pos, self.sample_rate());
+        let new_pos = new_pos.min(file_len as u64) as u32; // saturate pos at the end of the source
 
         // make sure the next sample is for the right channel
         let to_skip = self.reader.samples_read % self.channels() as u32;
@@ -174,6 +193,11 @@ where
 
 impl<R> ExactSizeIterator for WavDecoder<R> where R: Read + Seek {}
 
+// `len` is the number of samples of all channels.
+fn total_duration(spec: WavSpec, len: u64) -> Duration {
+    Duration::from_micros((1_000_000 * len) / (spec.sample_rate as u64 * spec.channels as u64))
+}
+
 /// Returns true if the stream contains WAV data, then resets it to where it was.
 fn is_wave<R>(mut data: R) -> bool
 where
@@ -10,8 +10,9 @@
 //! - Add the source to the output stream using [`OutputStream::mixer()`](OutputStream::mixer)
 //!   on the output stream handle.
 //!
-//! The output stream expects the sources to produce [`f32`]s. In case the output sample format
-//! is different use [`.convert_samples()`](Source::convert_samples) to adapt them.
+//! The output stream plays [`f32`]s. Sources producing another sample type, for example the
+//! `i16`s of a [`Decoder`], are converted when added with
+//! [`Mixer::add_converted`](mixer::Mixer::add_converted).
 //!
 //! Here is a complete example of how you would play an audio file:
 //!
@@ -30,7 +31,7 @@
 //! // Decode that sound file into a source
 //! let source = Decoder::new(file).unwrap();
 //! // Play the sound directly on the device
-//! stream_handle.mixer().add(source.convert_samples());
+//! stream_handle.mixer().add_converted(source);
 //!
 //! // The sound plays in a separate audio thread,
 //! // so we need to keep the main thread alive while it's playing.
@@ -138,6 +139,12 @@
 //! The "noise" feature adds support for white and pink noise sources. This feature requires the
 //! "rand" crate.
 //!
+//! ### Feature "fft"
+//!
+//! The "fft" feature adds `Source::power_spectrum` to analyse the frequency content of a
+//! sound, for example in tests, and `Source::pitch_shift` to change the pitch of a sound
+//! without changing its duration. This feature requires the "realfft" crate.
+//!
 //! ## How it works under the hood
 //!
 //! Rodio spawns a background thread that is dedicated to reading from the sources and sending
@@ -167,18 +174,21 @@ mod wav_output;
 
 pub mod buffer;
 pub mod decoder;
+pub mod math;
 pub mod mixer;
 pub mod queue;
 pub mod source;
 pub mod static_buffer;
 
-pub use crate::common::{ChannelCount, SampleRate};
-pub use crate::conversions::Sample;
+pub use crate::common::{BitDepth, BitDepthError, ChannelCount, SampleRate};
+pub use crate::conversions::{deinterleave, interleave, Sample};
 pub use crate::decoder::Decoder;
-pub use crate::sink::Sink;
+pub use crate::sink::{QueueFullError, Sink, SourceController};
 pub use crate::source::Source;
 pub use crate::spatial_sink::SpatialSink;
 #[cfg(feature = "playback")]
-pub use crate::stream::{play, OutputStream, OutputStreamBuilder, PlayError, StreamError};
+pub use crate::stream::{
+    play, OutputStream, OutputStreamBuilder, PlayError, StreamConfigInfo, StreamError,
+};
 #[cfg(feature = "wav")]
 pub use crate::wav_output::output_to_wav;
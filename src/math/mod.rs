@@ -0,0 +1,83 @@
+//! Conversions between durations, frames and samples.
+//!
+//! A frame holds one sample for every channel, so a stereo frame is two samples.
+
+use std::time::Duration;
+
+use crate::common::{ChannelCount, SampleRate};
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+/// Returns how long `frames` frames play at `sample_rate`, rounded to the nearest
+/// nanosecond.
+///
+/// # Panics
+///
+/// Panics if `sample_rate` is zero.
+pub fn frames_to_duration(frames: u64, sample_rate: SampleRate) -> Duration {
+    assert!(sample_rate > 0, "the sample rate must be positive");
+    let rate = sample_rate as u64;
+    let secs = frames / rate;
+    let rest = (frames % rate) as u128;
+    let nanos = (rest * NANOS_PER_SEC + rate as u128 / 2) / rate as u128;
+    Duration::from_secs(secs) + Duration::from_nanos(nanos as u64)
+}
+
+/// Returns the number of frames that play in `duration` at `sample_rate`, rounded to
+/// the nearest frame.
+pub fn duration_to_frames(duration: Duration, sample_rate: SampleRate) -> u64 {
+    let frames = (duration.as_nanos() * sample_rate as u128 + NANOS_PER_SEC / 2) / NANOS_PER_SEC;
+    frames.try_into().unwrap_or(u64::MAX)
+}
+
+/// Returns the number of complete frames in `samples` samples of `channels` channels.
+///
+/// # Panics
+///
+/// Panics if `channels` is zero.
+pub fn samples_to_frames(samples: usize, channels: ChannelCount) -> usize {
+    assert!(channels > 0, "a frame needs at least one channel");
+    samples / channels as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{duration_to_frames, frames_to_duration, samples_to_frames};
+
+    #[test]
+    fn frames_to_duration_exact_and_rounded() {
+        assert_eq!(frames_to_duration(48000, 48000), Duration::from_secs(1));
+        assert_eq!(
+            frames_to_duration(72000, 48000),
+            Duration::from_millis(1500)
+        );
+        assert_eq!(frames_to_duration(0, 44100), Duration::ZERO);
+        // One frame at 44.1 kHz is 22675.73 ns.
+        assert_eq!(frames_to_duration(1, 44100), Duration::from_nanos(22676));
+        assert_eq!(
+            frames_to_duration(u64::MAX, 1),
+            Duration::from_secs(u64::MAX)
+        );
+    }
+
+    #[test]
+    fn duration_to_frames_exact_and_rounded() {
+        assert_eq!(duration_to_frames(Duration::from_secs(2), 44100), 88200);
+        assert_eq!(duration_to_frames(Duration::from_millis(10), 48000), 480);
+        assert_eq!(duration_to_frames(Duration::from_nanos(22676), 44100), 1);
+        // 0.4 and 0.6 of a frame at 1 kHz.
+        assert_eq!(duration_to_frames(Duration::from_micros(400), 1000), 0);
+        assert_eq!(duration_to_frames(Duration::from_micros(600), 1000), 1);
+        assert_eq!(duration_to_frames(Duration::MAX, 192000), u64::MAX);
+    }
+
+    #[test]
+    fn samples_to_frames_drops_incomplete_frames() {
+        assert_eq!(samples_to_frames(10, 2), 5);
+        assert_eq!(samples_to_frames(11, 2), 5);
+        assert_eq!(samples_to_frames(12, 6), 2);
+        assert_eq!(samples_to_frames(0, 1), 0);
+    }
+}
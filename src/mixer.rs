@@ -1,9 +1,13 @@
 //! Mixer that plays multiple sounds at the same time.
 
+use crate::buffer::SamplesBuffer;
 use crate::common::{ChannelCount, SampleRate};
-use crate::source::{SeekError, Source, UniformSourceIterator};
+use crate::math;
+use crate::source::{ResampleConfig, SeekError, Source, UniformSourceIterator};
 use crate::Sample;
-use std::sync::atomic::{AtomicBool, Ordering};
+use dasp_sample::FromSample;
+use std::f32::consts::FRAC_PI_2;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
@@ -20,14 +24,21 @@ where
     let input = Arc::new(Mixer {
         has_pending: AtomicBool::new(false),
         pending_sources: Mutex::new(Vec::new()),
+        resample_config: Mutex::new(None),
+        voice_limit: Mutex::new(None),
         channels,
         sample_rate,
     });
 
+    let steal_fade_len =
+        math::duration_to_frames(STEAL_FADE_TIME, sample_rate).max(1) as usize * channels as usize;
     let output = MixerSource {
         current_sources: Vec::with_capacity(16),
+        stolen: Vec::new(),
+        steal_fade_len,
         input: input.clone(),
         sample_count: 0,
+        voices_started: 0,
         still_pending: vec![],
         still_current: vec![],
     };
@@ -35,10 +46,209 @@ where
     (input, output)
 }
 
+/// Which sound a [`Mixer`] stops to make room for a new one once it plays as many
+/// sounds as its voice limit allows, see [`Mixer::set_voice_limit`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StealPolicy {
+    /// Stops the sound that started first.
+    Oldest,
+    /// Stops the sound that has been the quietest over the last few milliseconds.
+    Quietest,
+    /// Stops the sound with the lowest priority given to
+    /// [`Mixer::add_with_priority`], the oldest of them if several share it. A new
+    /// sound with a lower priority than all playing ones is not played at all.
+    LowestPriority,
+}
+
+/// Mutes or solos a sound playing in a [`Mixer`], like the buttons of a channel on a
+/// mixing desk, see [`Mixer::add_with_handle`].
+#[derive(Clone, Debug)]
+pub struct MixerInputHandle {
+    switches: Arc<InputSwitches>,
+}
+
+#[derive(Debug, Default)]
+struct InputSwitches {
+    muted: AtomicBool,
+    soloed: AtomicBool,
+}
+
+impl MixerInputHandle {
+    /// Silences the sound while `muted` is `true`. It keeps playing in the background
+    /// and is heard again from where it got to when unmuted.
+    #[inline]
+    pub fn mute(&self, muted: bool) {
+        self.switches.muted.store(muted, Ordering::Relaxed);
+    }
+
+    /// Returns whether the sound is muted.
+    #[inline]
+    pub fn is_muted(&self) -> bool {
+        self.switches.muted.load(Ordering::Relaxed)
+    }
+
+    /// Solos the sound while `soloed` is `true`. As long as any sound of the mixer is
+    /// soloed only the soloed ones are heard. A muted sound stays silent when soloed.
+    #[inline]
+    pub fn solo(&self, soloed: bool) {
+        self.switches.soloed.store(soloed, Ordering::Relaxed);
+    }
+
+    /// Returns whether the sound is soloed.
+    #[inline]
+    pub fn is_soloed(&self) -> bool {
+        self.switches.soloed.load(Ordering::Relaxed)
+    }
+}
+
+/// One of the two input groups of a [`Crossfader`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Deck {
+    /// The group heard alone at position `0.0`.
+    A,
+    /// The group heard alone at position `1.0`.
+    B,
+}
+
+/// Fades between two groups of sounds playing in a [`Mixer`], like the crossfader of
+/// a DJ mixer.
+///
+/// Sounds added to the same deck with [`Mixer::add_to_deck`] are summed, then the two
+/// decks are crossfaded with equal power so the loudness stays about the same while
+/// moving between two unrelated tracks. One crossfader can fade sounds of several
+/// mixers.
+#[derive(Clone, Debug)]
+pub struct Crossfader {
+    // Bits of the `f32` position.
+    position: Arc<AtomicU32>,
+}
+
+impl Crossfader {
+    /// Creates a crossfader centered between its two decks. Sounds are added to one
+    /// of them with [`Mixer::add_to_deck`].
+    pub fn new() -> Crossfader {
+        Crossfader {
+            position: Arc::new(AtomicU32::new(0.5f32.to_bits())),
+        }
+    }
+
+    /// Moves the crossfader, `0.0` plays only deck A, `1.0` only deck B and `0.5` both
+    /// at equal power. Values outside of that range are clamped.
+    ///
+    /// The sounds glide to the new gains over a few milliseconds, so moving the
+    /// crossfader in steps does not make them crackle.
+    #[inline]
+    pub fn set_position(&self, position: f32) {
+        let position = position.clamp(0.0, 1.0);
+        self.position.store(position.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Returns the position of the crossfader, starting at `0.5`.
+    #[inline]
+    pub fn position(&self) -> f32 {
+        f32::from_bits(self.position.load(Ordering::Relaxed))
+    }
+
+    /// Returns the gain the sounds of `deck` get at the current position, once they
+    /// finished gliding to it.
+    #[inline]
+    pub fn gain(&self, deck: Deck) -> f32 {
+        deck_gain(deck, self.position())
+    }
+}
+
+impl Default for Crossfader {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Equal power gain of `deck` with the crossfader at `position`.
+fn deck_gain(deck: Deck, position: f32) -> f32 {
+    let angle = position * FRAC_PI_2;
+    match deck {
+        Deck::A => angle.cos(),
+        Deck::B => angle.sin(),
+    }
+}
+
+// Time for the gain of a deck to glide across the whole range of the crossfader.
+const DECK_GLIDE_TIME: f32 = 0.01;
+
+// Follows a crossfader for a sound on one of its decks.
+struct DeckInput {
+    crossfader: Crossfader,
+    deck: Deck,
+    // Position the gain is gliding from towards the one of the crossfader.
+    position: f32,
+    gain: f32,
+    // Furthest the position moves in one frame.
+    step: f32,
+}
+
+impl DeckInput {
+    fn new(crossfader: &Crossfader, deck: Deck, sample_rate: SampleRate) -> DeckInput {
+        let position = crossfader.position();
+        DeckInput {
+            crossfader: crossfader.clone(),
+            deck,
+            position,
+            gain: deck_gain(deck, position),
+            step: 1.0 / (DECK_GLIDE_TIME * sample_rate as f32),
+        }
+    }
+
+    // Moves the gain one frame closer to the position of the crossfader.
+    fn glide(&mut self) {
+        let target = self.crossfader.position();
+        if self.position != target {
+            self.position += (target - self.position).clamp(-self.step, self.step);
+            self.gain = deck_gain(self.deck, self.position);
+        }
+    }
+}
+
+// A sound playing in the mixer.
+struct Voice<S> {
+    source: Box<dyn Source<Item = S> + Send>,
+    priority: i32,
+    // Counts up from the first voice the mixer started.
+    started: u64,
+    // Smoothed absolute value of recent samples.
+    level: f32,
+    // Set for sounds added with a handle.
+    switches: Option<Arc<InputSwitches>>,
+    // Set for sounds added to a deck of a crossfader.
+    deck: Option<DeckInput>,
+    // Samples left before a stolen voice has faded out.
+    fade_left: usize,
+}
+
+impl<S> Voice<S> {
+    fn is_soloed(&self) -> bool {
+        self.switches
+            .as_ref()
+            .is_some_and(|switches| switches.soloed.load(Ordering::Relaxed))
+    }
+
+    // Whether the voice is heard while `solo` tells if any voice is soloed.
+    fn is_audible(&self, solo: bool) -> bool {
+        match &self.switches {
+            Some(switches) => {
+                !switches.muted.load(Ordering::Relaxed) && (!solo || self.is_soloed())
+            }
+            None => !solo,
+        }
+    }
+}
+
 /// The input of the mixer.
 pub struct Mixer<S> {
     has_pending: AtomicBool,
-    pending_sources: Mutex<Vec<Box<dyn Source<Item = S> + Send>>>,
+    pending_sources: Mutex<Vec<Voice<S>>>,
+    resample_config: Mutex<Option<ResampleConfig>>,
+    voice_limit: Mutex<Option<(usize, StealPolicy)>>,
     channels: ChannelCount,
     sample_rate: SampleRate,
 }
@@ -47,25 +257,169 @@ impl<S> Mixer<S>
 where
     S: Sample + Send + 'static,
 {
+    /// Returns the number of channels the mixer outputs.
+    #[inline]
+    pub fn channels(&self) -> ChannelCount {
+        self.channels
+    }
+
+    /// Returns the sample rate the mixer outputs.
+    #[inline]
+    pub fn sample_rate(&self) -> SampleRate {
+        self.sample_rate
+    }
+
+    /// Sets how sources added from now on are converted to the sample rate of the mixer.
+    ///
+    /// By default the mixer interpolates linearly between samples. That is cheap but
+    /// adds audible aliasing, especially when lowering the sample rate of bright
+    /// material. A sinc based config such as [`ResampleConfig::accurate`] removes
+    /// most of it at the cost of noticeably more CPU time per source that has to be
+    /// converted. Sources that already have the sample rate of the mixer are not
+    /// affected.
+    pub fn set_resample_config(&self, config: ResampleConfig) {
+        *self.resample_config.lock().unwrap() = Some(config);
+    }
+
+    /// Limits how many sounds the mixer plays at the same time.
+    ///
+    /// Adding a sound while `limit` sounds are playing stops one of them, chosen by
+    /// `policy`. It fades out over 5 ms instead of being cut off with a click. Games use this to keep a burst of sound effects from overloading the
+    /// mix. Sounds that already play beyond a new, lower limit are stopped the next
+    /// time a sound is added. A [`Sink`](crate::Sink) is a single sound to the mixer,
+    /// stopping it stops everything queued in the sink.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `limit` is zero.
+    pub fn set_voice_limit(&self, limit: usize, policy: StealPolicy) {
+        assert!(
+            limit > 0,
+            "the mixer has to be able to play at least one sound"
+        );
+        *self.voice_limit.lock().unwrap() = Some((limit, policy));
+    }
+
+    /// Lets the mixer play any number of sounds at the same time again.
+    pub fn clear_voice_limit(&self) {
+        *self.voice_limit.lock().unwrap() = None;
+    }
+
     /// Adds a new source to mix to the existing ones.
+    ///
+    /// The source is converted to the channel count and sample rate of the mixer. A
+    /// mono source plays equally on the first two channels, extra channels of a source
+    /// are dropped.
     #[inline]
     pub fn add<T>(&self, source: T)
     where
         T: Source<Item = S> + Send + 'static,
     {
-        let uniform_source = UniformSourceIterator::new(source, self.channels, self.sample_rate);
-        self.pending_sources
-            .lock()
-            .unwrap()
-            .push(Box::new(uniform_source) as Box<_>);
+        self.add_with_priority(source, 0);
+    }
+
+    /// Adds a new source like [`add`](Mixer::add) that produces another sample type
+    /// than the mixer, so for example the `i16`s of a decoder play on an `f32` mixer
+    /// without calling [`convert_samples`](Source::convert_samples) first.
+    #[inline]
+    pub fn add_converted<T>(&self, source: T)
+    where
+        T: Source + Send + 'static,
+        T::Item: Sample + Send,
+        S: FromSample<T::Item>,
+    {
+        self.add(source.convert_samples::<S>());
+    }
+
+    /// Adds a new source like [`add`](Mixer::add) with a priority for
+    /// [`StealPolicy::LowestPriority`]. Sources added with `add` have a priority of
+    /// `0`, higher values are stopped later.
+    pub fn add_with_priority<T>(&self, source: T, priority: i32)
+    where
+        T: Source<Item = S> + Send + 'static,
+    {
+        self.push_voice(source, priority, None, None);
+    }
+
+    /// Adds a new source like [`add`](Mixer::add) and returns a handle that mutes or
+    /// solos it while it plays.
+    pub fn add_with_handle<T>(&self, source: T) -> MixerInputHandle
+    where
+        T: Source<Item = S> + Send + 'static,
+    {
+        let switches = Arc::new(InputSwitches::default());
+        self.push_voice(source, 0, Some(switches.clone()), None);
+        MixerInputHandle { switches }
+    }
+
+    /// Adds a new source like [`add`](Mixer::add) to one deck of a crossfader, its
+    /// volume follows the position of the crossfader from then on.
+    pub fn add_to_deck<T>(&self, source: T, crossfader: &Crossfader, deck: Deck)
+    where
+        T: Source<Item = S> + Send + 'static,
+    {
+        let deck = DeckInput::new(crossfader, deck, self.sample_rate);
+        self.push_voice(source, 0, None, Some(deck));
+    }
+
+    fn push_voice<T>(
+        &self,
+        source: T,
+        priority: i32,
+        switches: Option<Arc<InputSwitches>>,
+        deck: Option<DeckInput>,
+    ) where
+        T: Source<Item = S> + Send + 'static,
+    {
+        let resample_config = *self.resample_config.lock().unwrap();
+        let uniform_source = match resample_config {
+            Some(config) => Box::new(UniformSourceIterator::new(
+                source.resample(self.sample_rate, config),
+                self.channels,
+                self.sample_rate,
+            )) as Box<dyn Source<Item = S> + Send>,
+            None => Box::new(UniformSourceIterator::new(
+                source,
+                self.channels,
+                self.sample_rate,
+            )),
+        };
+        self.pending_sources.lock().unwrap().push(Voice {
+            source: uniform_source,
+            priority,
+            started: 0,
+            level: 0.0,
+            fade_left: 0,
+            switches,
+            deck,
+        });
         self.has_pending.store(true, Ordering::SeqCst); // TODO: can we relax this ordering?
     }
+
+    /// Adds a new source that fades in from silence over `duration`.
+    ///
+    /// A sound that does not start at zero clicks when it is added abruptly. Fading in
+    /// over a few milliseconds avoids that, which matters when many short sounds are
+    /// triggered, for example in a game.
+    #[inline]
+    pub fn add_with_fade_in<T>(&self, source: T, duration: Duration)
+    where
+        T: Source<Item = S> + Send + 'static,
+    {
+        self.add(source.fade_in(duration));
+    }
 }
 
 /// The output of the mixer. Implements `Source`.
 pub struct MixerSource<S> {
     // The current iterator that produces samples.
-    current_sources: Vec<Box<dyn Source<Item = S> + Send>>,
+    current_sources: Vec<Voice<S>>,
+
+    // Voices stopped to make room for new ones, fading out.
+    stolen: Vec<Voice<S>>,
+
+    // Samples a stolen voice takes to fade out.
+    steal_fade_len: usize,
 
     // The pending sounds.
     input: Arc<Mixer<S>>,
@@ -73,11 +427,14 @@ pub struct MixerSource<S> {
     // The number of samples produced so far.
     sample_count: usize,
 
+    // The number of voices started so far.
+    voices_started: u64,
+
     // A temporary vec used in start_pending_sources.
-    still_pending: Vec<Box<dyn Source<Item = S> + Send>>,
+    still_pending: Vec<Voice<S>>,
 
     // A temporary vec used in sum_current_sources.
-    still_current: Vec<Box<dyn Source<Item = S> + Send>>,
+    still_current: Vec<Voice<S>>,
 }
 
 impl<S> Source for MixerSource<S>
@@ -104,6 +461,15 @@ where
         None
     }
 
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.current_sources
+            .iter()
+            .map(|voice| voice.source.latency())
+            .max()
+            .unwrap_or_default()
+    }
+
     #[inline]
     fn try_seek(&mut self, _: Duration) -> Result<(), SeekError> {
         Err(SeekError::NotSupported {
@@ -174,21 +540,50 @@ impl<S> MixerSource<S>
 where
     S: Sample + Send + 'static,
 {
+    /// Mixes the next `frames` frames on the calling thread and returns them.
+    ///
+    /// This renders a mix as fast as the sources can produce samples, for example to
+    /// export it to a file, instead of at the pace of an output device. Don't use it
+    /// on a mixer that is also playing. Silence fills the rest once all sources ended,
+    /// so the buffer always holds exactly `frames` frames.
+    pub fn render(&mut self, frames: usize) -> SamplesBuffer<S> {
+        let channels = self.input.channels;
+        let samples = (0..frames * channels as usize)
+            .map(|_| self.next().unwrap_or_else(S::zero_value))
+            .collect::<Vec<_>>();
+        SamplesBuffer::new(channels, self.input.sample_rate, samples)
+    }
+
     // Samples from the #next() function are interlaced for each of the channels.
     // We need to ensure we start playing sources so that their samples are
     // in-step with the modulo of the samples produced so far. Otherwise, the
     // sound will play on the wrong channels, e.g. left / right will be reversed.
     fn start_pending_sources(&mut self) {
         let mut pending = self.input.pending_sources.lock().unwrap(); // TODO: relax ordering?
+        let voice_limit = *self.input.voice_limit.lock().unwrap();
 
-        for source in pending.drain(..) {
-            let in_step = self.sample_count % source.channels() as usize == 0;
+        for mut voice in pending.drain(..) {
+            let in_step = self
+                .sample_count
+                .is_multiple_of(voice.source.channels() as usize);
 
-            if in_step {
-                self.current_sources.push(source);
-            } else {
-                self.still_pending.push(source);
+            if !in_step {
+                self.still_pending.push(voice);
+                continue;
+            }
+            voice.started = self.voices_started;
+            self.voices_started += 1;
+            if let Some((limit, policy)) = voice_limit {
+                let room = make_room(&mut self.current_sources, &voice, limit, policy);
+                for mut stolen in room.stolen {
+                    stolen.fade_left = self.steal_fade_len;
+                    self.stolen.push(stolen);
+                }
+                if !room.fits {
+                    continue;
+                }
             }
+            self.current_sources.push(voice);
         }
         std::mem::swap(&mut self.still_pending, &mut pending);
 
@@ -198,24 +593,107 @@ where
 
     fn sum_current_sources(&mut self) -> S {
         let mut sum = S::zero_value();
+        let solo = self.current_sources.iter().any(Voice::is_soloed);
+        // `sample_count` was already counted up for the sample being mixed.
+        let frame_start = (self.sample_count - 1).is_multiple_of(self.input.channels as usize);
 
-        for mut source in self.current_sources.drain(..) {
-            if let Some(value) = source.next() {
-                sum = sum.saturating_add(value);
-                self.still_current.push(source);
+        for mut voice in self.current_sources.drain(..) {
+            if let Some(value) = voice.source.next() {
+                if let Some(deck) = &mut voice.deck {
+                    if frame_start {
+                        deck.glide();
+                    }
+                }
+                if voice.is_audible(solo) {
+                    sum = match &voice.deck {
+                        Some(deck) => sum.saturating_add(value.amplify(deck.gain)),
+                        None => sum.saturating_add(value),
+                    };
+                }
+                voice.level += (value.to_f32().abs() - voice.level) * LEVEL_SMOOTHING;
+                self.still_current.push(voice);
             }
         }
         std::mem::swap(&mut self.still_current, &mut self.current_sources);
 
+        // Stopping a voice mid-waveform would click.
+        let fade_len = self.steal_fade_len as f32;
+        self.stolen.retain_mut(|voice| {
+            if voice.fade_left == 0 {
+                return false;
+            }
+            let Some(value) = voice.source.next() else {
+                return false;
+            };
+            voice.fade_left -= 1;
+            if voice.is_audible(solo) {
+                let mut gain = voice.fade_left as f32 / fade_len;
+                if let Some(deck) = &voice.deck {
+                    gain *= deck.gain;
+                }
+                sum = sum.saturating_add(value.amplify(gain));
+            }
+            true
+        });
+
         sum
     }
 }
 
+// Weight of every new sample in the level of a voice. At 48 kHz the level follows
+// changes within about 20 ms.
+const LEVEL_SMOOTHING: f32 = 0.001;
+
+// Time a voice stopped to make room for a new one takes to fade out.
+const STEAL_FADE_TIME: Duration = Duration::from_millis(5);
+
+// Voices taken out by `make_room`.
+struct Room<S> {
+    stolen: Vec<Voice<S>>,
+    // `false` if the new voice should not be played instead.
+    fits: bool,
+}
+
+// Takes out voices until fewer than `limit` play.
+fn make_room<S>(
+    voices: &mut Vec<Voice<S>>,
+    new: &Voice<S>,
+    limit: usize,
+    policy: StealPolicy,
+) -> Room<S> {
+    let mut stolen = Vec::new();
+    while voices.len() >= limit {
+        let victim = match policy {
+            StealPolicy::Oldest => voices.iter().enumerate().min_by_key(|(_, v)| v.started),
+            StealPolicy::Quietest => voices
+                .iter()
+                .enumerate()
+                .min_by(|(_, a), (_, b)| a.level.total_cmp(&b.level)),
+            StealPolicy::LowestPriority => voices
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, v)| (v.priority, v.started)),
+        };
+        let Some((index, voice)) = victim else {
+            break;
+        };
+        if policy == StealPolicy::LowestPriority && new.priority < voice.priority {
+            return Room {
+                stolen,
+                fits: false,
+            };
+        }
+        stolen.push(voices.remove(index));
+    }
+    Room { stolen, fits: true }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::buffer::SamplesBuffer;
-    use crate::mixer;
-    use crate::source::Source;
+    use crate::mixer::{self, deck_gain, Crossfader, Deck};
+    use crate::source::{ResampleConfig, SineWave, Source};
+    use std::time::Duration;
 
     #[test]
     fn basic() {
@@ -253,6 +731,177 @@ mod tests {
         assert_eq!(rx.next(), None);
     }
 
+    #[test]
+    fn add_with_fade_in_ramps_from_zero() {
+        let (tx, rx) = mixer::mixer::<f32>(1, 1000);
+        tx.add_with_fade_in(
+            SamplesBuffer::new(1, 1000, vec![1.0f32; 20]),
+            Duration::from_millis(10),
+        );
+
+        let samples: Vec<f32> = rx.collect();
+        assert_eq!(samples[0], 0.0);
+        assert!(samples[..10].windows(2).all(|w| w[1] > w[0]));
+        assert!(samples[..10].iter().all(|&s| s < 1.0));
+        assert_eq!(samples[10..], [1.0; 10]);
+    }
+
+    #[test]
+    fn render_sums_sources() {
+        let (tx, mut rx) = mixer::mixer::<f32>(2, 1000);
+        let a: Vec<f32> = (0..3000).map(|n| (n as f32 * 0.01).sin()).collect();
+        let b: Vec<f32> = (0..3000).map(|n| (n as f32 * 0.03).cos() / 2.0).collect();
+        tx.add(SamplesBuffer::new(2, 1000, a.clone()));
+        tx.add(SamplesBuffer::new(2, 1000, b.clone()));
+
+        let rendered = rx.render(1000);
+        assert_eq!(rendered.channels(), 2);
+        assert_eq!(rendered.sample_rate(), 1000);
+        let sum: Vec<f32> = a.iter().zip(&b).map(|(a, b)| a + b).collect();
+        assert_eq!(rendered.collect::<Vec<f32>>(), sum[..2000]);
+
+        // Pads with silence after the sources end.
+        let rest: Vec<f32> = rx.render(1000).collect();
+        assert_eq!(rest[..1000], sum[2000..]);
+        assert!(rest[1000..].iter().all(|&s| s == 0.0));
+    }
+
+    #[test]
+    fn mono_sine_in_stereo_mixer() {
+        let (tx, rx) = mixer::mixer::<f32>(2, 48000);
+        tx.add(SineWave::new(440.0).take_duration(Duration::from_millis(10)));
+
+        let samples: Vec<f32> = rx.collect();
+        assert_eq!(samples.len(), 960);
+        assert!(samples.iter().any(|s| s.abs() > 0.5));
+        for frame in samples.chunks(2) {
+            assert_eq!(frame[0], frame[1]);
+        }
+    }
+
+    #[test]
+    fn voice_limit_steals() {
+        let constant = |value: f32| SamplesBuffer::new(1, 48000, vec![value; 1000]);
+        let mix_after_third = |policy, priorities: [i32; 3]| {
+            let (tx, mut rx) = mixer::mixer::<f32>(1, 48000);
+            tx.set_voice_limit(2, policy);
+            tx.add_with_priority(constant(0.5), priorities[0]);
+            tx.add_with_priority(constant(0.1), priorities[1]);
+            rx.by_ref().take(100).for_each(drop);
+            tx.add_with_priority(constant(0.3), priorities[2]);
+            // Past the fade out of the stolen voice, 5 ms at 48 kHz.
+            rx.by_ref().take(240).for_each(drop);
+            rx.next().unwrap()
+        };
+
+        assert_eq!(
+            mix_after_third(mixer::StealPolicy::Oldest, [0; 3]),
+            0.1 + 0.3
+        );
+        assert_eq!(
+            mix_after_third(mixer::StealPolicy::Quietest, [0; 3]),
+            0.5 + 0.3
+        );
+        let lowest = mixer::StealPolicy::LowestPriority;
+        assert_eq!(mix_after_third(lowest, [2, 1, 3]), 0.5 + 0.3);
+        // A new sound that matters less than everything playing is dropped.
+        assert_eq!(mix_after_third(lowest, [2, 1, 0]), 0.5 + 0.1);
+    }
+
+    #[test]
+    fn stolen_voice_fades_out() {
+        let (tx, mut rx) = mixer::mixer::<f32>(1, 1000);
+        tx.set_voice_limit(1, mixer::StealPolicy::Oldest);
+        tx.add(SamplesBuffer::new(1, 1000, vec![1.0; 100]));
+        assert_eq!(rx.next(), Some(1.0));
+
+        // 5 ms are 5 samples at 1 kHz.
+        tx.add(SamplesBuffer::new(1, 1000, vec![0.0; 100]));
+        let mix: Vec<f32> = rx.by_ref().take(6).collect();
+        assert_eq!(mix, [0.8, 0.6, 0.4, 0.2, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn solo_and_mute() {
+        let (tx, mut rx) = mixer::mixer::<f32>(1, 1000);
+        let handles: Vec<_> = [1.0f32, 2.0, 4.0]
+            .into_iter()
+            .map(|value| tx.add_with_handle(SamplesBuffer::new(1, 1000, vec![value; 100])))
+            .collect();
+        assert_eq!(rx.next(), Some(7.0));
+
+        handles[1].solo(true);
+        assert!(handles[1].is_soloed());
+        assert_eq!(rx.next(), Some(2.0));
+        handles[2].solo(true);
+        assert_eq!(rx.next(), Some(6.0));
+
+        handles[1].solo(false);
+        handles[2].solo(false);
+        assert_eq!(rx.next(), Some(7.0));
+
+        handles[0].mute(true);
+        assert_eq!(rx.next(), Some(6.0));
+        // Muted sounds keep playing in the background.
+        handles[0].mute(false);
+        assert_eq!(rx.by_ref().count(), 95);
+    }
+
+    #[test]
+    fn crossfader_center_plays_both_decks_at_equal_power() {
+        // Amplitude of a tone in the mix, both tones have whole periods in a second.
+        let amplitude = |mix: &[f32], freq: f32| {
+            let step = 2.0 * std::f32::consts::PI * freq / 48000.0;
+            let (sin, cos) = mix.iter().enumerate().fold((0.0, 0.0), |(s, c), (i, x)| {
+                let phase = step * i as f32;
+                (s + x * phase.sin(), c + x * phase.cos())
+            });
+            2.0 * (sin * sin + cos * cos).sqrt() / mix.len() as f32
+        };
+
+        let (tx, mut rx) = mixer::mixer::<f32>(1, 48000);
+        let crossfader = Crossfader::new();
+        tx.add_to_deck(SineWave::new(440.0).amplify(0.5), &crossfader, Deck::A);
+        tx.add_to_deck(SineWave::new(1000.0).amplify(0.5), &crossfader, Deck::B);
+
+        crossfader.set_position(0.5);
+        let mix: Vec<f32> = rx.by_ref().take(48000).collect();
+        let a = amplitude(&mix, 440.0);
+        let b = amplitude(&mix, 1000.0);
+        assert!((a - b).abs() < 1e-3, "deck A at {a}, deck B at {b}");
+        // Equal power: the squared gains add up to one.
+        assert!((a * a + b * b - 0.25).abs() < 1e-3);
+
+        crossfader.set_position(0.0);
+        // Past the glide to the new position.
+        rx.by_ref().take(480).for_each(drop);
+        let mix: Vec<f32> = rx.by_ref().take(48000).collect();
+        assert!((amplitude(&mix, 440.0) - 0.5).abs() < 1e-3);
+        assert!(amplitude(&mix, 1000.0) < 1e-3);
+    }
+
+    #[test]
+    fn crossfader_glides_to_new_position() {
+        let (tx, mut rx) = mixer::mixer::<f32>(1, 1000);
+        let crossfader = Crossfader::new();
+        crossfader.set_position(0.0);
+        tx.add_to_deck(
+            SamplesBuffer::new(1, 1000, vec![1.0; 100]),
+            &crossfader,
+            Deck::A,
+        );
+        assert_eq!(rx.next(), Some(1.0));
+
+        // The whole range takes 10 frames at 1 kHz, instead of one step to silence.
+        crossfader.set_position(1.0);
+        let glide: Vec<f32> = rx.by_ref().take(10).collect();
+        for (n, gain) in glide.iter().enumerate() {
+            let expected = deck_gain(Deck::A, (n + 1) as f32 / 10.0);
+            assert!((gain - expected).abs() < 1e-5, "frame {n} at {gain}");
+        }
+        assert!(rx.next().unwrap().abs() < 1e-6);
+    }
+
     #[test]
     fn rate_conv() {
         let (tx, mut rx) = mixer::mixer(1, 96000);
@@ -272,6 +921,122 @@ mod tests {
         assert_eq!(rx.next(), None);
     }
 
+    #[test]
+    fn resample_to_mixer_rate() {
+        let (tx, mut rx) = mixer::mixer(1, 48000);
+
+        let source = SamplesBuffer::new(1, 16000, vec![0.5f32; 1600])
+            .resample_to(&tx, ResampleConfig::balanced());
+        assert_eq!(source.sample_rate(), tx.sample_rate());
+        tx.add(source);
+
+        assert_eq!(rx.by_ref().count(), 4800);
+    }
+
+    #[test]
+    fn resample_config_reduces_aliasing() {
+        // A 15 kHz tone is above the Nyquist frequency of the mixer, ideally
+        // nothing of it is left after conversion.
+        let tone = || {
+            let data = (0..48000)
+                .map(|i| (2.0 * std::f32::consts::PI * 15000.0 * i as f32 / 48000.0).sin())
+                .collect::<Vec<f32>>();
+            SamplesBuffer::new(1, 48000, data)
+        };
+        let rms = |samples: Vec<f32>| {
+            let samples = &samples[1000..samples.len() - 1000];
+            (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+        };
+
+        let (tx, rx) = mixer::mixer(1, 16000);
+        tx.add(tone());
+        let default_rms = rms(rx.collect());
+
+        let (tx, rx) = mixer::mixer(1, 16000);
+        tx.set_resample_config(ResampleConfig::accurate());
+        tx.add(tone());
+        let accurate_rms = rms(rx.collect());
+
+        assert!(
+            accurate_rms < default_rms / 10.0,
+            "{accurate_rms} not much lower than {default_rms}"
+        );
+    }
+
+    // A mono span followed by a stereo one with opposite channels. Unlike a queue,
+    // which reports the format of the next sound late, it switches the format right
+    // at the span boundary.
+    struct MonoThenStereo {
+        pos: usize,
+    }
+
+    impl MonoThenStereo {
+        const MONO: usize = 2400;
+        const STEREO: usize = 4800;
+    }
+
+    impl Iterator for MonoThenStereo {
+        type Item = f32;
+
+        fn next(&mut self) -> Option<f32> {
+            let pos = self.pos;
+            self.pos += 1;
+            match pos {
+                _ if pos < Self::MONO => Some(0.5),
+                _ if pos < Self::MONO + Self::STEREO => {
+                    Some(if pos.is_multiple_of(2) { 0.25 } else { -0.25 })
+                }
+                _ => None,
+            }
+        }
+    }
+
+    impl Source for MonoThenStereo {
+        fn current_span_len(&self) -> Option<usize> {
+            if self.pos < Self::MONO {
+                Some(Self::MONO - self.pos)
+            } else {
+                Some((Self::MONO + Self::STEREO).saturating_sub(self.pos))
+            }
+        }
+
+        fn channels(&self) -> crate::ChannelCount {
+            if self.pos < Self::MONO {
+                1
+            } else {
+                2
+            }
+        }
+
+        fn sample_rate(&self) -> crate::SampleRate {
+            24000
+        }
+
+        fn total_duration(&self) -> Option<Duration> {
+            None
+        }
+    }
+
+    #[test]
+    fn resample_config_keeps_channels_of_mono_then_stereo() {
+        let (tx, rx) = mixer::mixer::<f32>(2, 48000);
+        tx.set_resample_config(ResampleConfig::accurate());
+        tx.add(MonoThenStereo { pos: 0 });
+        let frames: Vec<f32> = rx.collect();
+        let frames: Vec<&[f32]> = frames.chunks(2).collect();
+        assert_eq!(frames.len(), 9600);
+
+        // Away from the edges where the filter rings.
+        for frame in &frames[500..4300] {
+            assert!((frame[0] - 0.5).abs() < 0.01, "{frame:?}");
+            assert!((frame[1] - 0.5).abs() < 0.01, "{frame:?}");
+        }
+        for frame in &frames[5300..9100] {
+            assert!((frame[0] - 0.25).abs() < 0.01, "{frame:?}");
+            assert!((frame[1] + 0.25).abs() < 0.01, "{frame:?}");
+        }
+    }
+
     #[test]
     fn start_afterwards() {
         let (tx, mut rx) = mixer::mixer(1, 48000);
@@ -31,6 +31,7 @@ where
     let input = Arc::new(SourcesQueueInput {
         next_sounds: Mutex::new(Vec::new()),
         keep_alive_if_empty: AtomicBool::new(keep_alive_if_empty),
+        refill: Mutex::new(None),
     });
 
     let output = SourcesQueueOutput {
@@ -46,6 +47,7 @@ where
 
 type Sound<S> = Box<dyn Source<Item = S> + Send>;
 type SignalDone = Option<Sender<()>>;
+type Refill<S> = Box<dyn FnMut() -> Vec<Sound<S>> + Send>;
 
 /// The input of the queue.
 pub struct SourcesQueueInput<S> {
@@ -53,6 +55,9 @@ pub struct SourcesQueueInput<S> {
 
     // See constructor.
     keep_alive_if_empty: AtomicBool,
+
+    // Called for new sounds once the queue runs empty.
+    refill: Mutex<Option<Refill<S>>>,
 }
 
 impl<S> SourcesQueueInput<S>
@@ -97,6 +102,85 @@ where
             .store(keep_alive_if_empty, Ordering::Release);
     }
 
+    /// Replaces all the sounds waiting in the queue by `sources` at once, so the sound
+    /// that is playing is followed by the new ones without a gap. Returns the number
+    /// of sounds removed.
+    ///
+    /// The `Receiver` is signalled when the last of the new sounds has finished playing.
+    pub(crate) fn replace_with_signal<T>(&self, sources: Vec<T>) -> (usize, Option<Receiver<()>>)
+    where
+        T: Source<Item = S> + Send + 'static,
+    {
+        let count = sources.len();
+        let mut last_signal = None;
+        let mut sounds = Vec::with_capacity(count);
+        for (index, source) in sources.into_iter().enumerate() {
+            let signal = if index + 1 == count {
+                let (tx, rx) = channel();
+                last_signal = Some(rx);
+                Some(tx)
+            } else {
+                None
+            };
+            sounds.push((Box::new(source) as Box<_>, signal));
+        }
+
+        let removed = std::mem::replace(&mut *self.next_sounds.lock().unwrap(), sounds);
+        (removed.len(), last_signal)
+    }
+
+    /// Adds a new source to the front of the queue, so it plays right after the sound
+    /// that is playing now.
+    ///
+    /// Returns a `Receiver` that is signalled when the sound has finished playing, but
+    /// only if no other sound was waiting. Otherwise the new sound is not the last one.
+    pub(crate) fn prepend_with_signal<T>(&self, source: T) -> Option<Receiver<()>>
+    where
+        T: Source<Item = S> + Send + 'static,
+    {
+        let mut sounds = self.next_sounds.lock().unwrap();
+        if sounds.is_empty() {
+            let (tx, rx) = channel();
+            sounds.push((Box::new(source) as Box<_>, Some(tx)));
+            Some(rx)
+        } else {
+            sounds.insert(0, (Box::new(source) as Box<_>, None));
+            None
+        }
+    }
+
+    /// Sets a function that is called for new sounds whenever the queue runs empty,
+    /// `None` removes it.
+    pub(crate) fn set_refill(&self, refill: Option<Refill<S>>) {
+        *self.refill.lock().unwrap() = refill;
+    }
+
+    /// Returns the number of sounds waiting to be played.
+    pub(crate) fn waiting_len(&self) -> usize {
+        self.next_sounds.lock().unwrap().len()
+    }
+
+    /// Returns the total duration of every sound waiting to be played, in order.
+    pub(crate) fn waiting_durations(&self) -> Vec<Option<Duration>> {
+        let sounds = self.next_sounds.lock().unwrap();
+        sounds
+            .iter()
+            .map(|(sound, _)| sound.total_duration())
+            .collect()
+    }
+
+    /// Seeks the sound waiting at `index`, zero being the next sound to play. Returns
+    /// `None` if fewer sounds are waiting.
+    pub(crate) fn seek_waiting(
+        &self,
+        index: usize,
+        pos: Duration,
+    ) -> Option<Result<(), SeekError>> {
+        let mut sounds = self.next_sounds.lock().unwrap();
+        let (sound, _) = sounds.get_mut(index)?;
+        Some(sound.try_seek(pos))
+    }
+
     /// Removes all the sounds from the queue. Returns the number of sounds cleared.
     pub fn clear(&self) -> usize {
         let mut sounds = self.next_sounds.lock().unwrap();
@@ -174,6 +258,11 @@ where
         None
     }
 
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.current.latency()
+    }
+
     /// Only seeks within the current source.
     // We can not go back to previous sources. We could implement seek such
     // that it advances the queue if the position is beyond the current song.
@@ -233,6 +322,12 @@ where
         let (next, signal_after_end) = {
             let mut next = self.input.next_sounds.lock().unwrap();
 
+            if next.is_empty() {
+                if let Some(refill) = self.input.refill.lock().unwrap().as_mut() {
+                    next.extend(refill().into_iter().map(|sound| (sound, None)));
+                }
+            }
+
             if next.len() == 0 {
                 let silence = Box::new(Zero::<S>::new_samples(1, 44100, THRESHOLD)) as Box<_>;
                 if self.input.keep_alive_if_empty.load(Ordering::Acquire) {
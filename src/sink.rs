@@ -1,6 +1,8 @@
-use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
-use std::sync::{Arc, Mutex};
+use std::any::Any;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
 use std::time::Duration;
+use std::{error, fmt, thread};
 
 #[cfg(feature = "crossbeam-channel")]
 use crossbeam_channel::{Receiver, Sender};
@@ -8,8 +10,10 @@ use dasp_sample::FromSample;
 #[cfg(not(feature = "crossbeam-channel"))]
 use std::sync::mpsc::{Receiver, Sender};
 
+use crate::common::{ChannelCount, SampleRate};
+use crate::math;
 use crate::mixer::Mixer;
-use crate::source::SeekError;
+use crate::source::{Buffered, SeekError};
 use crate::{queue, source::Done, Sample, Source};
 
 /// Handle to a device that outputs sounds.
@@ -22,10 +26,106 @@ pub struct Sink {
 
     controls: Arc<Controls>,
     sound_count: Arc<AtomicUsize>,
+    max_queue_len: Option<usize>,
+    looper: Arc<Looper>,
 
     detached: bool,
 }
 
+type LoopedSound = Buffered<Box<dyn Source<Item = f32> + Send>>;
+type QueuedSound = Box<dyn Source<Item = f32> + Send>;
+
+// Repeats the sounds of a looping sink.
+struct Looper {
+    // Copies of the sounds appended while looping, `None` if the sink does not loop.
+    playlist: Mutex<Option<Vec<LoopedSound>>>,
+    // The next time around, built ahead so the audio thread only has to take it.
+    next_cycle: Mutex<Option<Vec<QueuedSound>>>,
+    controls: Arc<Controls>,
+    sound_count: Arc<AtomicUsize>,
+}
+
+impl Looper {
+    // Adds the controls of the sink to the sounds of the playlist. They are only
+    // counted once they are queued.
+    fn build_cycle(&self, playlist: &[LoopedSound]) -> Vec<QueuedSound> {
+        let cycle: Vec<QueuedSound> = playlist
+            .iter()
+            .map(|sound| {
+                Box::new(wrap_source(
+                    &self.controls,
+                    &self.sound_count,
+                    sound.clone(),
+                    None,
+                    None,
+                    None,
+                )) as Box<_>
+            })
+            .collect();
+        self.sound_count.fetch_sub(cycle.len(), Ordering::Relaxed);
+        cycle
+    }
+
+    // Builds the next time around from the playlist as it is now.
+    fn prepare(&self) {
+        let playlist = self.playlist.lock().unwrap();
+        let cycle = playlist.as_deref().map(|sounds| self.build_cycle(sounds));
+        *self.next_cycle.lock().unwrap() = cycle;
+    }
+
+    // Empties the playlist, looping goes on with the sounds appended afterwards.
+    fn clear(&self) {
+        if let Some(playlist) = self.playlist.lock().unwrap().as_mut() {
+            playlist.clear();
+        }
+        self.prepare();
+    }
+
+    // Called on the audio thread once the queue runs empty. Takes the time around
+    // that was built ahead, it is only built here if that did not happen in time.
+    fn take_cycle(&self) -> Vec<QueuedSound> {
+        if self.controls.stopped.load(Ordering::SeqCst) {
+            return Vec::new();
+        }
+        let prepared = match self.next_cycle.try_lock() {
+            Ok(mut next_cycle) => next_cycle.take(),
+            Err(_) => None,
+        };
+        match prepared {
+            Some(cycle) => {
+                self.sound_count.fetch_add(cycle.len(), Ordering::Relaxed);
+                cycle
+            }
+            None => {
+                let playlist = self.playlist.lock().unwrap();
+                let cycle = playlist.as_deref().map(|sounds| self.build_cycle(sounds));
+                let cycle = cycle.unwrap_or_default();
+                self.sound_count.fetch_add(cycle.len(), Ordering::Relaxed);
+                cycle
+            }
+        }
+    }
+}
+
+/// Error returned by [`Sink::try_append`] when the queue is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueueFullError {
+    /// The maximum number of sounds the sink queues.
+    pub max_len: usize,
+}
+
+impl fmt::Display for QueueFullError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the sink already holds its maximum of {} sounds",
+            self.max_len
+        )
+    }
+}
+
+impl error::Error for QueueFullError {}
+
 struct SeekOrder {
     pos: Duration,
     feedback: Sender<Result<(), SeekError>>,
@@ -57,6 +157,82 @@ impl SeekOrder {
     }
 }
 
+/// Controls a single sound queued with [`Sink::append_controlled`], independently of
+/// the other sounds of the sink.
+#[derive(Clone)]
+pub struct SourceController {
+    controls: Arc<SourceControls>,
+}
+
+#[derive(Default)]
+struct SourceControls {
+    stopped: AtomicBool,
+    started: AtomicBool,
+    finished: AtomicBool,
+    seek: Mutex<Option<SeekOrder>>,
+    position: Mutex<Duration>,
+}
+
+// Marks the sound of a `SourceController` as finished once it is dropped.
+struct SourceControlsGuard(Arc<SourceControls>);
+
+impl Drop for SourceControlsGuard {
+    fn drop(&mut self) {
+        self.0.finished.store(true, Ordering::SeqCst);
+        // Wakes up a `try_seek` that waits for the sound.
+        self.0.seek.lock().unwrap().take();
+    }
+}
+
+impl SourceController {
+    /// Stops the sound. If it is playing the sink moves on to the next sound, if it
+    /// is waiting in the queue it is skipped once its turn comes.
+    pub fn stop(&self) {
+        self.controls.stopped.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns `true` once the sound ended, was stopped or was cleared from the sink.
+    pub fn is_finished(&self) -> bool {
+        self.controls.finished.load(Ordering::SeqCst)
+    }
+
+    /// Returns the position of the sound, zero while it waits in the queue. Like
+    /// [`Sink::get_pos`] this takes the speed of the sink into account.
+    pub fn get_pos(&self) -> Duration {
+        *self.controls.position.lock().unwrap()
+    }
+
+    /// Seeks the sound to `pos`, see [`Sink::try_seek`].
+    ///
+    /// A sound that waits in the queue starts at `pos` once it plays, errors are not
+    /// reported for it. Nothing happens once the sound has finished.
+    ///
+    /// # Errors
+    /// Returns the error of the sound if it is playing and can not seek.
+    pub fn try_seek(&self, pos: Duration) -> Result<(), SeekError> {
+        if self.is_finished() {
+            return Ok(());
+        }
+        let (order, feedback) = SeekOrder::new(pos);
+        *self.controls.seek.lock().unwrap() = Some(order);
+        if !self.controls.started.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        match feedback.recv() {
+            Ok(seek_res) => {
+                *self.controls.position.lock().unwrap() = pos;
+                seek_res
+            }
+            // The sound finished, or another seek replaced this one.
+            Err(_) => Ok(()),
+        }
+    }
+}
+
+/// Callback invoked with the tag of a source when it starts playing.
+type SourceStartCallback = Box<dyn FnMut(&(dyn Any + Send)) + Send>;
+
 struct Controls {
     pause: AtomicBool,
     volume: Mutex<f32>,
@@ -65,8 +241,40 @@ struct Controls {
     to_clear: Mutex<u32>,
     seek: Mutex<Option<SeekOrder>>,
     position: Mutex<Duration>,
+    // Total duration of the sound being played, if known.
+    duration: Mutex<Option<Duration>>,
+    on_source_start: Mutex<Option<SourceStartCallback>>,
+    peaks: Mutex<Vec<f32>>,
+    stop_fade: Mutex<Option<StopFade>>,
+    // Number of fades started, to tell them apart.
+    fades_started: AtomicU64,
+    // Held while checking for room in the queue, see `Sink::wait_for_space`.
+    space: Mutex<()>,
+    space_freed: Condvar,
+}
+
+impl Controls {
+    // Wakes up the appends that wait for room in the queue. Taking the lock first
+    // makes sure a waiter either sees the new count or gets woken up.
+    fn notify_space_freed(&self) {
+        drop(self.space.lock().unwrap());
+        self.space_freed.notify_all();
+    }
+}
+
+/// Fade out started by [`Sink::stop_with_fade`].
+struct StopFade {
+    id: u64,
+    duration: Duration,
+    left: Duration,
+    // Set once a sound was appended during the fade. The faded sounds are then
+    // skipped instead of stopping the sink, so the new sound plays.
+    appended: bool,
 }
 
+/// How often the sources of a sink pick up changes to its controls.
+const CONTROLS_PERIOD: Duration = Duration::from_millis(5);
+
 impl Sink {
     /// Builds a new `Sink`, beginning playback on a stream.
     #[inline]
@@ -80,26 +288,61 @@ impl Sink {
     #[inline]
     pub fn new() -> (Sink, queue::SourcesQueueOutput<f32>) {
         let (queue_tx, queue_rx) = queue::queue(true);
+        let controls = Arc::new(Controls {
+            pause: AtomicBool::new(false),
+            volume: Mutex::new(1.0),
+            stopped: AtomicBool::new(false),
+            speed: Mutex::new(1.0),
+            to_clear: Mutex::new(0),
+            seek: Mutex::new(None),
+            position: Mutex::new(Duration::ZERO),
+            duration: Mutex::new(None),
+            on_source_start: Mutex::new(None),
+            peaks: Mutex::new(Vec::new()),
+            stop_fade: Mutex::new(None),
+            fades_started: AtomicU64::new(0),
+            space: Mutex::new(()),
+            space_freed: Condvar::new(),
+        });
+        let sound_count = Arc::new(AtomicUsize::new(0));
 
         let sink = Sink {
             queue_tx,
             sleep_until_end: Mutex::new(None),
-            controls: Arc::new(Controls {
-                pause: AtomicBool::new(false),
-                volume: Mutex::new(1.0),
-                stopped: AtomicBool::new(false),
-                speed: Mutex::new(1.0),
-                to_clear: Mutex::new(0),
-                seek: Mutex::new(None),
-                position: Mutex::new(Duration::ZERO),
+            looper: Arc::new(Looper {
+                playlist: Mutex::new(None),
+                next_cycle: Mutex::new(None),
+                controls: controls.clone(),
+                sound_count: sound_count.clone(),
             }),
-            sound_count: Arc::new(AtomicUsize::new(0)),
+            controls,
+            sound_count,
+            max_queue_len: None,
             detached: false,
         };
         (sink, queue_rx)
     }
 
+    /// Limits the number of sounds in the queue, including the one playing, to
+    /// `max_len`.
+    ///
+    /// Once the queue is full [`Sink::append`] blocks until a sound ends and
+    /// [`Sink::try_append`] returns an error. This keeps a producer that decodes
+    /// ahead from queueing without bound.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_len` is zero.
+    pub fn with_max_queue_len(mut self, max_len: usize) -> Sink {
+        assert!(max_len > 0, "the queue must hold at least one sound");
+        self.max_queue_len = Some(max_len);
+        self
+    }
+
     /// Appends a sound to the queue of sounds to play.
+    ///
+    /// If the queue is full, see [`Sink::with_max_queue_len`], this waits until a
+    /// sound ends. That never happens while the sink is paused.
     #[inline]
     pub fn append<S>(&self, source: S)
     where
@@ -107,59 +350,294 @@ impl Sink {
         f32: FromSample<S::Item>,
         S::Item: Sample + Send,
     {
+        self.wait_for_space();
+        self.append_tagged(source, None, None);
+    }
+
+    /// Appends a sound to the queue of sounds to play, or returns an error if the
+    /// queue is full. See [`Sink::with_max_queue_len`].
+    #[inline]
+    pub fn try_append<S>(&self, source: S) -> Result<(), QueueFullError>
+    where
+        S: Source + Send + 'static,
+        f32: FromSample<S::Item>,
+        S::Item: Sample + Send,
+    {
+        if let Some(max_len) = self.max_queue_len {
+            if self.len() >= max_len {
+                return Err(QueueFullError { max_len });
+            }
+        }
+        self.append_tagged(source, None, None);
+        Ok(())
+    }
+
+    /// Appends a sound to the queue of sounds to play together with a tag, for
+    /// example a track title or ID.
+    ///
+    /// The tag is passed to the callback set with [`Sink::on_source_start`]
+    /// once the sound starts playing.
+    #[inline]
+    pub fn append_with_tag<S, T>(&self, source: S, tag: T)
+    where
+        S: Source + Send + 'static,
+        f32: FromSample<S::Item>,
+        S::Item: Sample + Send,
+        T: Send + 'static,
+    {
+        self.wait_for_space();
+        self.append_tagged(source, Some(Box::new(tag)), None);
+    }
+
+    /// Appends a sound to the queue like [`Sink::append`] and returns a controller
+    /// that stops or seeks just this sound, or tells its position.
+    ///
+    /// When the sink loops, see [`Sink::set_loop`], the controller only controls the
+    /// first time the sound is played.
+    pub fn append_controlled<S>(&self, source: S) -> SourceController
+    where
+        S: Source + Send + 'static,
+        f32: FromSample<S::Item>,
+        S::Item: Sample + Send,
+    {
+        let controls = Arc::new(SourceControls::default());
+        self.wait_for_space();
+        self.append_tagged(source, None, Some(controls.clone()));
+        SourceController { controls }
+    }
+
+    /// Queues a sound to play right after the current one, ahead of the other sounds
+    /// waiting in the queue.
+    ///
+    /// This is the "Play Next" of a music player. If nothing is playing the sound is
+    /// played first. Like [`Sink::append`] this waits while the queue is full.
+    pub fn play_next<S>(&self, source: S)
+    where
+        S: Source + Send + 'static,
+        f32: FromSample<S::Item>,
+        S::Item: Sample + Send,
+    {
+        self.wait_for_space();
+        self.resume_if_stopped();
+        let fade_id = self.exempt_from_fade();
+        let source = wrap_source(
+            &self.controls,
+            &self.sound_count,
+            source,
+            None,
+            None,
+            fade_id,
+        );
+        if let Some(signal) = self.queue_tx.prepend_with_signal(source) {
+            *self.sleep_until_end.lock().unwrap() = Some(signal);
+        }
+    }
+
+    /// Sets a callback that is called with the tag of a sound appended with
+    /// [`Sink::append_with_tag`] when that sound starts playing. Replaces any
+    /// previously set callback.
+    ///
+    /// The callback runs on the audio thread, keep it short. Use `downcast_ref`
+    /// to get the tag back.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rodio::Sink;
+    ///
+    /// let (sink, _output) = Sink::new();
+    /// sink.on_source_start(|tag| {
+    ///     if let Some(title) = tag.downcast_ref::<String>() {
+    ///         println!("Now playing: {title}");
+    ///     }
+    /// });
+    /// ```
+    pub fn on_source_start<F>(&self, callback: F)
+    where
+        F: FnMut(&(dyn Any + Send)) + Send + 'static,
+    {
+        *self.controls.on_source_start.lock().unwrap() = Some(Box::new(callback));
+    }
+
+    /// Replaces the sounds waiting to be played by `sources`, in one step.
+    ///
+    /// The sound that is currently playing is not interrupted, the new sounds play
+    /// after it without a gap. This is useful for reordering or editing a playlist,
+    /// clearing and appending again could let the queue run empty in between.
+    ///
+    /// If the queue is limited, see [`Sink::with_max_queue_len`], only the sounds
+    /// that fit are queued and the rest is left out.
+    pub fn replace_queue<S, I>(&self, sources: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Source + Send + 'static,
+        f32: FromSample<S::Item>,
+        S::Item: Sample + Send,
+    {
+        self.resume_if_stopped();
+
+        let fade_id = self.exempt_from_fade();
+        // The sounds that play now keep their room.
+        let room = self.max_queue_len.map_or(usize::MAX, |max_len| {
+            let playing = self.len().saturating_sub(self.queue_tx.waiting_len());
+            max_len.saturating_sub(playing)
+        });
+        let sources = sources
+            .into_iter()
+            .take(room)
+            .map(|source| {
+                wrap_source(
+                    &self.controls,
+                    &self.sound_count,
+                    source,
+                    None,
+                    None,
+                    fade_id,
+                )
+            })
+            .collect();
+        let (removed, signal) = self.queue_tx.replace_with_signal(sources);
+        self.sound_count.fetch_sub(removed, Ordering::Relaxed);
+        self.controls.notify_space_freed();
+        if let Some(signal) = signal {
+            *self.sleep_until_end.lock().unwrap() = Some(signal);
+        }
+    }
+
+    /// Makes the sink start over at the first sound after it played the last one.
+    ///
+    /// Only the sounds appended with [`Sink::append`], [`Sink::try_append`] or
+    /// [`Sink::append_with_tag`] while looping is enabled are repeated, they are kept in
+    /// memory for that. Tags are only passed on the first time around. Stopping or
+    /// clearing the sink ends the loop, disabling looping lets the sink finish after
+    /// the sounds that are queued.
+    ///
+    /// Each time around is prepared ahead on a thread of its own, so the audio thread
+    /// does not have to lock or allocate when the sink starts over.
+    pub fn set_loop(&self, loop_queue: bool) {
+        if !loop_queue {
+            *self.looper.playlist.lock().unwrap() = None;
+            self.queue_tx.set_refill(None);
+            self.looper.prepare();
+            return;
+        }
+        {
+            let mut playlist = self.looper.playlist.lock().unwrap();
+            if playlist.is_some() {
+                return;
+            }
+            *playlist = Some(Vec::new());
+        }
+        self.looper.prepare();
+
+        // Builds the next time around on its own thread whenever the audio thread took
+        // one. It ends once looping is disabled and the sender is dropped. Where no
+        // thread can be spawned the audio thread builds it.
+        #[cfg(not(feature = "crossbeam-channel"))]
+        let (wake_tx, wake_rx) = std::sync::mpsc::sync_channel::<()>(1);
+        #[cfg(feature = "crossbeam-channel")]
+        let (wake_tx, wake_rx) = crossbeam_channel::bounded::<()>(1);
+        let looper = self.looper.clone();
+        let _ = thread::Builder::new()
+            .name("rodio sink loop".to_owned())
+            .spawn(move || {
+                while wake_rx.recv().is_ok() {
+                    looper.prepare();
+                }
+            });
+
+        let looper = self.looper.clone();
+        self.queue_tx.set_refill(Some(Box::new(move || {
+            let cycle = looper.take_cycle();
+            let _ = wake_tx.try_send(());
+            cycle
+        })));
+    }
+
+    fn append_tagged<S>(
+        &self,
+        source: S,
+        tag: Option<Box<dyn Any + Send>>,
+        source_controls: Option<Arc<SourceControls>>,
+    ) where
+        S: Source + Send + 'static,
+        f32: FromSample<S::Item>,
+        S::Item: Sample + Send,
+    {
+        self.resume_if_stopped();
+        let fade_id = self.exempt_from_fade();
+        let looping = self.looper.playlist.lock().unwrap().is_some();
+        let signal = if looping {
+            let source: Box<dyn Source<Item = f32> + Send> = Box::new(source.convert_samples());
+            let source = source.buffered();
+            // A sound without samples would make the queue refill forever.
+            if source.clone().next().is_some() {
+                if let Some(playlist) = self.looper.playlist.lock().unwrap().as_mut() {
+                    playlist.push(source.clone());
+                }
+                self.looper.prepare();
+            }
+            let source = wrap_source::<LoopedSound>(
+                &self.controls,
+                &self.sound_count,
+                source,
+                tag,
+                source_controls,
+                fade_id,
+            );
+            self.queue_tx.append_with_signal(source)
+        } else {
+            let source = wrap_source(
+                &self.controls,
+                &self.sound_count,
+                source,
+                tag,
+                source_controls,
+                fade_id,
+            );
+            self.queue_tx.append_with_signal(source)
+        };
+        *self.sleep_until_end.lock().unwrap() = Some(signal);
+    }
+
+    // A sound added during a fade out is not faded, it plays once the sounds that are
+    // fading out are gone. Returns the fade the sound is exempt from.
+    fn exempt_from_fade(&self) -> Option<u64> {
+        let mut stop_fade = self.controls.stop_fade.lock().unwrap();
+        let fade = stop_fade.as_mut()?;
+        fade.appended = true;
+        Some(fade.id)
+    }
+
+    fn wait_for_space(&self) {
+        if let Some(max_len) = self.max_queue_len {
+            let mut space = self.controls.space.lock().unwrap();
+            while self.len() >= max_len {
+                space = self.controls.space_freed.wait(space).unwrap();
+            }
+        }
+    }
+
+    fn resume_if_stopped(&self) {
         // Wait for the queue to flush then resume stopped playback
         if self.controls.stopped.load(Ordering::SeqCst) {
             if self.sound_count.load(Ordering::SeqCst) > 0 {
                 self.sleep_until_end();
             }
+            // Stopping ends the loop.
+            self.looper.clear();
             self.controls.stopped.store(false, Ordering::SeqCst);
+            *self.controls.stop_fade.lock().unwrap() = None;
         }
+    }
 
-        let controls = self.controls.clone();
-
-        let start_played = AtomicBool::new(false);
-
-        let source = source
-            .speed(1.0)
-            // must be placed before pausable but after speed & delay
-            .track_position()
-            .pausable(false)
-            .amplify(1.0)
-            .skippable()
-            .stoppable()
-            // if you change the duration update the docs for try_seek!
-            .periodic_access(Duration::from_millis(5), move |src| {
-                if controls.stopped.load(Ordering::SeqCst) {
-                    src.stop();
-                    *controls.position.lock().unwrap() = Duration::ZERO;
-                }
-                {
-                    let mut to_clear = controls.to_clear.lock().unwrap();
-                    if *to_clear > 0 {
-                        src.inner_mut().skip();
-                        *to_clear -= 1;
-                        *controls.position.lock().unwrap() = Duration::ZERO;
-                    } else {
-                        *controls.position.lock().unwrap() = src.inner().inner().inner().inner().get_pos();
-                    }
-                }
-                let amp = src.inner_mut().inner_mut();
-                amp.set_factor(*controls.volume.lock().unwrap());
-                amp.inner_mut()
-                    .set_paused(controls.pause.load(Ordering::SeqCst));
-                amp.inner_mut()
-                    .inner_mut()
-                    .inner_mut()
-                    .set_factor(*controls.speed.lock().unwrap());
-                if let Some(seek) = controls.seek.lock().unwrap().take() {
-                    seek.attempt(amp)
-                }
-                start_played.store(true, Ordering::SeqCst);
-            })
-            .convert_samples();
-        self.sound_count.fetch_add(1, Ordering::Relaxed);
-        let source = Done::new(source, self.sound_count.clone());
-        *self.sleep_until_end.lock().unwrap() = Some(self.queue_tx.append_with_signal(source));
+    /// Returns the peak amplitude of each channel over the last 50 milliseconds
+    /// played, after volume and pausing are applied.
+    ///
+    /// This is the level the sink feeds into the mixer, useful for level meters in a
+    /// mixer UI. It is empty before anything played and zero while paused.
+    pub fn current_peak(&self) -> Vec<f32> {
+        self.controls.peaks.lock().unwrap().clone()
     }
 
     /// Gets the volume of the sound.
@@ -267,6 +745,25 @@ impl Sink {
         }
     }
 
+    /// Seeks the sound at `index` in the queue to `pos`, the sound that is playing has
+    /// index zero.
+    ///
+    /// A sound that is waiting in the queue starts at `pos` once it plays. See
+    /// [`Sink::try_seek`] for seeking the sound that is playing.
+    ///
+    /// # Errors
+    /// Returns [`SeekError::NoSuchSource`] if the queue holds no sound at `index`, or
+    /// the error of the sound if it can not seek.
+    pub fn seek_source(&self, index: usize, pos: Duration) -> Result<(), SeekError> {
+        let playing = self.len().saturating_sub(self.queue_tx.waiting_len());
+        if index < playing {
+            return self.try_seek(pos);
+        }
+        self.queue_tx
+            .seek_waiting(index - playing, pos)
+            .unwrap_or(Err(SeekError::NoSuchSource { index }))
+    }
+
     /// Pauses playback of this sink.
     ///
     /// No effect if already paused.
@@ -288,8 +785,10 @@ impl Sink {
     ///
     /// See `pause()` for information about pausing a `Sink`.
     pub fn clear(&self) {
+        self.looper.clear();
         let len = self.sound_count.load(Ordering::SeqCst) as u32;
         *self.controls.to_clear.lock().unwrap() = len;
+        *self.controls.stop_fade.lock().unwrap() = None;
         self.sleep_until_end();
         self.pause();
     }
@@ -310,9 +809,32 @@ impl Sink {
     /// Stops the sink by emptying the queue.
     #[inline]
     pub fn stop(&self) {
+        *self.controls.stop_fade.lock().unwrap() = None;
         self.controls.stopped.store(true, Ordering::SeqCst);
     }
 
+    /// Fades the sound out over `duration`, then stops the sink like [`Sink::stop`].
+    ///
+    /// Stopping in the middle of a sound causes an audible click, fading out avoids
+    /// it. Has no effect while the sink is already fading out. A sound appended
+    /// during the fade is not faded: the sounds that were queued before it fade out
+    /// and are skipped, then it plays.
+    pub fn stop_with_fade(&self, duration: Duration) {
+        if duration.is_zero() || self.empty() {
+            self.stop();
+            return;
+        }
+        let mut stop_fade = self.controls.stop_fade.lock().unwrap();
+        if stop_fade.is_none() {
+            *stop_fade = Some(StopFade {
+                id: self.controls.fades_started.fetch_add(1, Ordering::Relaxed),
+                duration,
+                left: duration,
+                appended: false,
+            });
+        }
+    }
+
     /// Destroys the sink without stopping the sounds that are still playing.
     #[inline]
     pub fn detach(mut self) {
@@ -351,6 +873,44 @@ impl Sink {
     pub fn get_pos(&self) -> Duration {
         *self.controls.position.lock().unwrap()
     }
+
+    /// Returns how far the sound being played has progressed, from `0.0` at its
+    /// start to `1.0` at its end.
+    ///
+    /// Returns `None` if the total duration of the sound is unknown, for example
+    /// for an infinite source or a decoder that can not tell the length of its file.
+    pub fn progress(&self) -> Option<f32> {
+        let duration = (*self.controls.duration.lock().unwrap())?;
+        if duration.is_zero() {
+            return Some(1.0);
+        }
+        let progress = self.get_pos().as_secs_f64() / duration.as_secs_f64();
+        Some(progress.min(1.0) as f32)
+    }
+
+    /// Returns the position and the total duration of every sound in the queue,
+    /// starting with the one being played, for example to save the session and
+    /// restore it after a restart.
+    ///
+    /// The list holds one entry per sound, see [`Sink::len`]. The sound being played
+    /// reports [`Sink::get_pos`], the sounds waiting after it report zero, also when
+    /// [`Sink::seek_source`] moved their start. The duration is `None` for sounds that
+    /// do not know how long they are. Keep track of which sound is which, for example
+    /// with [`Sink::append_with_tag`], to restore them later.
+    pub fn queue_positions(&self) -> Vec<(Duration, Option<Duration>)> {
+        let waiting = self.queue_tx.waiting_durations();
+        let playing = self.len().saturating_sub(waiting.len());
+        let mut positions = Vec::with_capacity(playing + waiting.len());
+        if playing > 0 {
+            positions.push((self.get_pos(), *self.controls.duration.lock().unwrap()));
+        }
+        positions.extend(
+            waiting
+                .into_iter()
+                .map(|duration| (Duration::ZERO, duration)),
+        );
+        positions
+    }
 }
 
 impl Drop for Sink {
@@ -364,12 +924,263 @@ impl Drop for Sink {
     }
 }
 
+// Adds the controls of the sink to a source that is about to be queued. `exempt_fade`
+// is the fade out that was going on when the source was appended, it does not apply
+// to the source.
+fn wrap_source<S>(
+    controls: &Arc<Controls>,
+    sound_count: &Arc<AtomicUsize>,
+    source: S,
+    mut tag: Option<Box<dyn Any + Send>>,
+    source_controls: Option<Arc<SourceControls>>,
+    exempt_fade: Option<u64>,
+) -> impl Source<Item = f32> + Send + 'static
+where
+    S: Source + Send + 'static,
+    f32: FromSample<S::Item>,
+    S::Item: Sample + Send,
+{
+    let meter_controls = controls.clone();
+    let controls = controls.clone();
+
+    let start_played = AtomicBool::new(false);
+    // Fade out the gain ramp is running for.
+    let mut fading = None;
+    let source_controls = source_controls.map(SourceControlsGuard);
+
+    let source = source
+        .speed(1.0)
+        // must be placed before pausable but after speed & delay
+        .track_position()
+        .pausable(false)
+        .amplify(1.0)
+        .linear_gain_ramp(Duration::from_nanos(1), 1.0, 1.0, true)
+        .skippable()
+        .stoppable()
+        // if you change the duration update the docs for try_seek!
+        .periodic_access(CONTROLS_PERIOD, move |src| {
+            {
+                let mut stop_fade = controls.stop_fade.lock().unwrap();
+                match stop_fade.as_mut() {
+                    // The sounds that were fading out are gone.
+                    Some(fade) if Some(fade.id) == exempt_fade => *stop_fade = None,
+                    Some(fade) => {
+                        if fading != Some(fade.id) {
+                            // The fade is shared, a sound that starts during it
+                            // continues where the previous one left off.
+                            let gain = fade.left.as_secs_f32() / fade.duration.as_secs_f32();
+                            src.inner_mut().inner_mut().restart(fade.left, gain, 0.0);
+                            fading = Some(fade.id);
+                        } else {
+                            fade.left = fade.left.saturating_sub(CONTROLS_PERIOD);
+                        }
+                        if fade.left.is_zero() {
+                            if fade.appended {
+                                src.inner_mut().skip();
+                            } else {
+                                controls.stopped.store(true, Ordering::SeqCst);
+                            }
+                        }
+                    }
+                    None => {
+                        if fading.take().is_some() {
+                            src.inner_mut().inner_mut().restart(CONTROLS_PERIOD, 1.0, 1.0);
+                        }
+                    }
+                }
+            }
+            if controls.stopped.load(Ordering::SeqCst) {
+                src.stop();
+                *controls.position.lock().unwrap() = Duration::ZERO;
+                *controls.duration.lock().unwrap() = None;
+            }
+            {
+                let mut to_clear = controls.to_clear.lock().unwrap();
+                if *to_clear > 0 {
+                    src.inner_mut().skip();
+                    *to_clear -= 1;
+                    *controls.position.lock().unwrap() = Duration::ZERO;
+                    *controls.duration.lock().unwrap() = None;
+                } else {
+                    let position = src.inner().inner().inner().inner().inner();
+                    *controls.position.lock().unwrap() = position.get_pos();
+                    *controls.duration.lock().unwrap() = position.total_duration();
+                }
+            }
+            let amp = src.inner_mut().inner_mut().inner_mut();
+            amp.set_factor(*controls.volume.lock().unwrap());
+            amp.inner_mut()
+                .set_paused(controls.pause.load(Ordering::SeqCst));
+            amp.inner_mut()
+                .inner_mut()
+                .inner_mut()
+                .set_factor(*controls.speed.lock().unwrap());
+            if let Some(seek) = controls.seek.lock().unwrap().take() {
+                seek.attempt(amp)
+            }
+            if let Some(SourceControlsGuard(source_controls)) = &source_controls {
+                source_controls.started.store(true, Ordering::SeqCst);
+                if let Some(seek) = source_controls.seek.lock().unwrap().take() {
+                    seek.attempt(amp)
+                }
+                if source_controls.stopped.load(Ordering::SeqCst) {
+                    src.inner_mut().skip();
+                } else {
+                    *source_controls.position.lock().unwrap() =
+                        src.inner().inner().inner().inner().inner().get_pos();
+                }
+            }
+            if let Some(tag) = tag.take() {
+                if let Some(callback) = controls.on_source_start.lock().unwrap().as_mut() {
+                    callback(tag.as_ref());
+                }
+            }
+            start_played.store(true, Ordering::SeqCst);
+        })
+        .convert_samples();
+    let source = PeakMeter::new(source, meter_controls);
+    sound_count.fetch_add(1, Ordering::Relaxed);
+    Done::new(source, sound_count.clone())
+}
+
+// Tracks the peak of each channel and publishes them to the controls of the sink
+// every `METER_WINDOW`.
+struct PeakMeter<I> {
+    input: I,
+    controls: Arc<Controls>,
+    peaks: Vec<f32>,
+    // Samples left before the peaks are published.
+    window_left: usize,
+    // Samples left in the current span of the input.
+    span_left: Option<usize>,
+    channel: usize,
+    published: bool,
+}
+
+const METER_WINDOW: Duration = Duration::from_millis(50);
+
+impl<I> PeakMeter<I>
+where
+    I: Source<Item = f32>,
+{
+    fn new(input: I, controls: Arc<Controls>) -> Self {
+        let channels = input.channels() as usize;
+        let mut meter = PeakMeter {
+            span_left: input.current_span_len(),
+            input,
+            controls,
+            peaks: Vec::new(),
+            window_left: 0,
+            channel: 0,
+            published: false,
+        };
+        meter.start_window(channels);
+        meter
+    }
+
+    // The channel count only changes at span boundaries, see `next`.
+    fn start_window(&mut self, channels: usize) {
+        let frames = math::duration_to_frames(METER_WINDOW, self.input.sample_rate()) as usize;
+        self.peaks.clear();
+        self.peaks.resize(channels, 0.0);
+        self.window_left = frames.max(1) * channels;
+        self.channel = 0;
+    }
+}
+
+impl<I> Iterator for PeakMeter<I>
+where
+    I: Source<Item = f32>,
+{
+    type Item = f32;
+
+    #[inline]
+    fn next(&mut self) -> Option<f32> {
+        if self.span_left == Some(0) {
+            let channels = self.input.channels() as usize;
+            if channels != self.peaks.len() {
+                self.start_window(channels);
+            }
+            self.span_left = self.input.current_span_len();
+        }
+        let sample = self.input.next()?;
+        self.span_left = self.span_left.map(|left| left.saturating_sub(1));
+        if let Some(peak) = self.peaks.get_mut(self.channel) {
+            *peak = peak.max(sample.abs());
+        }
+        self.channel = (self.channel + 1) % self.peaks.len().max(1);
+        self.window_left -= 1;
+        if self.window_left == 0 {
+            self.controls.peaks.lock().unwrap().clone_from(&self.peaks);
+            self.published = true;
+            self.start_window(self.peaks.len());
+        }
+        Some(sample)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<I> Source for PeakMeter<I>
+where
+    I: Source<Item = f32>,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)
+    }
+}
+
+impl<I> Drop for PeakMeter<I> {
+    fn drop(&mut self) {
+        // Nothing plays through this sound anymore, and it takes no room in the queue.
+        if self.published {
+            self.controls.peaks.lock().unwrap().fill(0.0);
+        }
+        self.controls.notify_space_freed();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::atomic::Ordering;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use dasp_sample::Sample as _;
 
     use crate::buffer::SamplesBuffer;
-    use crate::{Sink, Source};
+    use crate::source::{SeekError, Zero};
+    use crate::{QueueFullError, Sink, Source};
 
     #[test]
     fn test_pause_and_stop() {
@@ -430,6 +1241,331 @@ mod tests {
         assert_eq!(queue_rx.next(), src.next());
     }
 
+    #[test]
+    fn test_source_start_tags() {
+        let (sink, mut queue_rx) = Sink::new();
+        let started = Arc::new(Mutex::new(Vec::new()));
+        let started_clone = started.clone();
+        sink.on_source_start(move |tag| {
+            let tag = tag.downcast_ref::<&str>().expect("tags are string slices");
+            started_clone.lock().unwrap().push(*tag);
+        });
+
+        let v = vec![10i16, -10, 20];
+        sink.append_with_tag(SamplesBuffer::new(1, 1, v.clone()), "first");
+        sink.append_with_tag(SamplesBuffer::new(1, 1, v.clone()), "second");
+        assert!(started.lock().unwrap().is_empty());
+
+        assert_eq!(queue_rx.next(), Some(10i16.to_sample()));
+        assert_eq!(*started.lock().unwrap(), vec!["first"]);
+
+        for _ in 1..v.len() {
+            queue_rx.next();
+        }
+        assert_eq!(*started.lock().unwrap(), vec!["first"]);
+
+        assert_eq!(queue_rx.next(), Some(10i16.to_sample()));
+        assert_eq!(*started.lock().unwrap(), vec!["first", "second"]);
+    }
+
+    #[test]
+    fn test_replace_queue() {
+        let (sink, mut queue_rx) = Sink::new();
+
+        sink.append(SamplesBuffer::new(1, 1, vec![1.0f32, 1.0, 1.0]));
+        sink.append(SamplesBuffer::new(1, 1, vec![2.0f32, 2.0]));
+        sink.append(SamplesBuffer::new(1, 1, vec![3.0f32, 3.0]));
+        assert_eq!(queue_rx.next(), Some(1.0));
+
+        sink.replace_queue([
+            SamplesBuffer::new(1, 1, vec![4.0f32, 4.0]),
+            SamplesBuffer::new(1, 1, vec![5.0f32]),
+        ]);
+        assert_eq!(sink.len(), 3);
+
+        let played: Vec<f32> = queue_rx.by_ref().take(6).collect();
+        assert_eq!(played, vec![1.0, 1.0, 4.0, 4.0, 5.0, 0.0]);
+        assert!(sink.empty());
+    }
+
+    #[test]
+    fn test_current_peak() {
+        let (sink, mut queue_rx) = Sink::new();
+        assert!(sink.current_peak().is_empty());
+
+        let frames = [0.5f32, -0.25, -0.5, 0.25].repeat(100);
+        sink.append(SamplesBuffer::new(2, 1000, frames));
+        queue_rx.by_ref().take(100).for_each(drop);
+        assert_eq!(sink.current_peak(), vec![0.5, 0.25]);
+
+        sink.pause();
+        queue_rx.by_ref().take(200).for_each(drop);
+        assert_eq!(sink.current_peak(), vec![0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_current_peak_of_mono_then_stereo() {
+        let (sink, mut queue_rx) = Sink::new();
+        let (tx, rx) = crate::queue::queue(false);
+        // Ends halfway through a peak window.
+        tx.append(SamplesBuffer::new(1, 1000, vec![0.1f32; 625]));
+        tx.append(SamplesBuffer::new(2, 1000, [0.2f32, -0.8].repeat(1000)));
+        sink.append(rx);
+
+        queue_rx.by_ref().take(625 + 1000).for_each(drop);
+        assert_eq!(sink.current_peak(), vec![0.2, 0.8]);
+    }
+
+    #[test]
+    fn test_stop_with_fade() {
+        let (sink, mut queue_rx) = Sink::new();
+        sink.append(SamplesBuffer::new(1, 1000, vec![1.0f32; 2000]));
+        assert_eq!(queue_rx.next(), Some(1.0));
+
+        sink.stop_with_fade(Duration::from_millis(100));
+        let faded: Vec<f32> = queue_rx.by_ref().take(120).collect();
+        assert!(faded.windows(2).all(|w| w[1] <= w[0]));
+        assert!(faded[..5].iter().all(|&s| s > 0.9));
+        assert!((faded[55] - 0.5).abs() < 0.1);
+        assert_eq!(faded[110..], [0.0; 10]);
+        assert!(sink.empty());
+    }
+
+    #[test]
+    fn test_stop_with_fade_ramps_every_sample() {
+        let (sink, mut queue_rx) = Sink::new();
+        sink.append(SamplesBuffer::new(1, 1000, vec![1.0f32; 2000]));
+        assert_eq!(queue_rx.next(), Some(1.0));
+
+        // Only a few periods of the controls long.
+        sink.stop_with_fade(Duration::from_millis(20));
+        let faded: Vec<f32> = queue_rx.by_ref().take(25).collect();
+        // The fade starts with the next period of the controls.
+        let start = faded.iter().position(|&s| s < 1.0).unwrap();
+        assert!(start <= 5, "{faded:?}");
+        for pair in faded[start - 1..start + 19].windows(2) {
+            let step = pair[0] - pair[1];
+            assert!(step > 0.0 && step < 0.06, "{faded:?}");
+        }
+    }
+
+    #[test]
+    fn test_append_during_stop_with_fade() {
+        let (sink, mut queue_rx) = Sink::new();
+        sink.append(SamplesBuffer::new(1, 1000, vec![1.0f32; 300]));
+        assert_eq!(queue_rx.next(), Some(1.0));
+
+        sink.stop_with_fade(Duration::from_millis(100));
+        let faded: Vec<f32> = queue_rx.by_ref().take(50).collect();
+        assert!(faded[45..].iter().all(|&s| s < 0.6));
+
+        // The sound that is fading out keeps fading, then the new one plays.
+        sink.append(SamplesBuffer::new(1, 1000, vec![1.0f32; 300]));
+        let rest: Vec<f32> = queue_rx.by_ref().take(300).collect();
+        assert!(rest.windows(2).take(45).all(|w| w[1] <= w[0]));
+        assert!(rest[..45].iter().all(|&s| s < 0.6));
+        assert!(rest[60..].iter().all(|&s| s == 1.0));
+        assert_eq!(sink.len(), 1);
+    }
+
+    #[test]
+    fn test_max_queue_len() {
+        let (sink, mut queue_rx) = Sink::new();
+        let sink = sink.with_max_queue_len(2);
+
+        let source = || SamplesBuffer::new(1, 1000, vec![1.0f32; 3]);
+        assert_eq!(sink.try_append(source()), Ok(()));
+        assert_eq!(sink.try_append(source()), Ok(()));
+        let err = sink.try_append(source()).unwrap_err();
+        assert_eq!(err, QueueFullError { max_len: 2 });
+        assert_eq!(sink.len(), 2);
+
+        // Playing the first sound to its end makes room again.
+        assert_eq!(queue_rx.by_ref().take(4).count(), 4);
+        assert_eq!(sink.len(), 1);
+        assert_eq!(sink.try_append(source()), Ok(()));
+    }
+
+    #[test]
+    fn test_max_queue_len_wakes_waiting_append() {
+        let (sink, mut queue_rx) = Sink::new();
+        let sink = sink.with_max_queue_len(1);
+        sink.append(SamplesBuffer::new(1, 1000, vec![1.0f32; 3]));
+
+        thread::scope(|scope| {
+            let waiting = scope.spawn(|| sink.append(SamplesBuffer::new(1, 1000, vec![2.0f32])));
+            thread::sleep(Duration::from_millis(20));
+            assert!(!waiting.is_finished());
+
+            // The append goes through once the first sound ended.
+            assert_eq!(queue_rx.by_ref().take(3).count(), 3);
+            assert_eq!(queue_rx.next(), Some(0.0));
+            waiting.join().unwrap();
+        });
+        assert_eq!(sink.len(), 1);
+    }
+
+    #[test]
+    fn test_replace_queue_respects_max_queue_len() {
+        let (sink, mut queue_rx) = Sink::new();
+        let sink = sink.with_max_queue_len(2);
+        sink.append(SamplesBuffer::new(1, 1000, vec![1.0f32; 2]));
+        assert_eq!(queue_rx.next(), Some(1.0));
+
+        sink.replace_queue([
+            SamplesBuffer::new(1, 1000, vec![2.0f32]),
+            SamplesBuffer::new(1, 1000, vec![3.0f32]),
+        ]);
+        assert_eq!(sink.len(), 2);
+        let played: Vec<f32> = queue_rx.take(3).collect();
+        assert_eq!(played, vec![1.0, 2.0, 0.0]);
+    }
+
+    #[test]
+    fn test_play_next() {
+        let (sink, mut queue_rx) = Sink::new();
+
+        sink.append(SamplesBuffer::new(1, 1000, vec![1.0f32; 2]));
+        // Start playing the first sound.
+        assert_eq!(queue_rx.next(), Some(1.0));
+        sink.append(SamplesBuffer::new(1, 1000, vec![3.0f32; 2]));
+        sink.play_next(SamplesBuffer::new(1, 1000, vec![2.0f32; 2]));
+        assert_eq!(sink.len(), 3);
+
+        let played: Vec<f32> = queue_rx.take(5).collect();
+        assert_eq!(played, vec![1.0, 2.0, 2.0, 3.0, 3.0]);
+    }
+
+    #[test]
+    fn test_loop() {
+        let (sink, mut queue_rx) = Sink::new();
+        sink.set_loop(true);
+        sink.append(SamplesBuffer::new(1, 1000, vec![1.0f32; 2]));
+        sink.append(SamplesBuffer::new(1, 1000, vec![2.0f32, 3.0]));
+
+        let played: Vec<f32> = queue_rx.by_ref().take(10).collect();
+        assert_eq!(
+            played,
+            vec![1.0, 1.0, 2.0, 3.0, 1.0, 1.0, 2.0, 3.0, 1.0, 1.0]
+        );
+
+        // The sounds already queued still play.
+        sink.set_loop(false);
+        let played: Vec<f32> = queue_rx.by_ref().take(4).collect();
+        assert_eq!(played, vec![2.0, 3.0, 0.0, 0.0]);
+        assert!(sink.empty());
+    }
+
+    #[test]
+    fn test_loop_prepares_next_cycle() {
+        let (sink, mut queue_rx) = Sink::new();
+        sink.set_loop(true);
+        sink.append(SamplesBuffer::new(1, 1000, vec![1.0f32; 2]));
+        let prepared = || {
+            let next_cycle = sink.looper.next_cycle.lock().unwrap();
+            next_cycle.as_ref().map(Vec::len)
+        };
+        // Ready before the audio thread needs it, without counting as queued.
+        assert_eq!(prepared(), Some(1));
+        assert_eq!(sink.len(), 1);
+
+        queue_rx.by_ref().take(3).for_each(drop);
+        assert_eq!(sink.len(), 1);
+        // The loop thread builds the one after it.
+        let start = Instant::now();
+        while prepared().is_none() && start.elapsed() < Duration::from_secs(1) {
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(prepared(), Some(1));
+
+        sink.clear();
+        assert_eq!(prepared(), Some(0));
+    }
+
+    #[test]
+    fn test_progress() {
+        let (sink, mut queue_rx) = Sink::new();
+        assert_eq!(sink.progress(), None);
+
+        sink.append(SamplesBuffer::new(1, 1000, vec![0.0f32; 1000]));
+        queue_rx.by_ref().take(501).for_each(drop);
+        let progress = sink.progress().unwrap();
+        assert!((progress - 0.5).abs() < 0.01, "{progress}");
+
+        // The length of an endless sound is unknown.
+        let (sink, mut queue_rx) = Sink::new();
+        sink.append(SamplesBuffer::new(1, 1000, vec![0.0f32; 10]).repeat_infinite());
+        queue_rx.by_ref().take(20).for_each(drop);
+        assert_eq!(sink.progress(), None);
+    }
+
+    #[test]
+    fn test_queue_positions() {
+        let (sink, mut queue_rx) = Sink::new();
+        assert!(sink.queue_positions().is_empty());
+
+        sink.append(SamplesBuffer::new(1, 1000, vec![0.0f32; 1000]));
+        sink.append(SamplesBuffer::new(1, 1000, vec![0.0f32; 500]));
+        sink.append(Zero::<f32>::new(1, 1000));
+        let second = Some(Duration::from_millis(500));
+        assert_eq!(
+            sink.queue_positions(),
+            [
+                (Duration::ZERO, Some(Duration::from_secs(1))),
+                (Duration::ZERO, second),
+                (Duration::ZERO, None),
+            ]
+        );
+
+        queue_rx.by_ref().take(1201).for_each(drop);
+        let positions = sink.queue_positions();
+        assert_eq!(positions.len(), 2);
+        assert_eq!(positions[0].0, sink.get_pos());
+        assert_eq!(positions[0], (Duration::from_millis(200), second));
+        assert_eq!(positions[1], (Duration::ZERO, None));
+    }
+
+    #[test]
+    fn test_append_controlled() {
+        let (sink, mut queue_rx) = Sink::new();
+        let first = sink.append_controlled(SamplesBuffer::new(1, 1000, vec![1.0f32; 100]));
+        let ramp: Vec<f32> = (0..20).map(|i| i as f32).collect();
+        let second = sink.append_controlled(SamplesBuffer::new(1, 1000, ramp));
+        assert_eq!(queue_rx.next(), Some(1.0));
+
+        // The second sound starts where it was sought to while waiting.
+        second.try_seek(Duration::from_millis(10)).unwrap();
+        first.stop();
+        // The stop takes effect at the end of the current control period.
+        let played: Vec<f32> = queue_rx.by_ref().take(14).collect();
+        assert_eq!(
+            played,
+            [1.0, 1.0, 1.0, 1.0, 10.0, 11.0, 12.0, 13.0, 14.0, 15.0, 16.0, 17.0, 18.0, 19.0]
+        );
+        assert!(first.is_finished());
+        assert!(!second.is_finished());
+        assert_eq!(second.get_pos(), Duration::from_millis(15));
+    }
+
+    #[test]
+    fn test_seek_source() {
+        let (sink, mut queue_rx) = Sink::new();
+
+        sink.append(SamplesBuffer::new(1, 1000, vec![-1.0f32; 4]));
+        let ramp: Vec<f32> = (0..1000).map(|i| i as f32).collect();
+        sink.append(SamplesBuffer::new(1, 1000, ramp));
+        assert_eq!(queue_rx.next(), Some(-1.0));
+
+        sink.seek_source(1, Duration::from_millis(500)).unwrap();
+        assert!(matches!(
+            sink.seek_source(2, Duration::ZERO),
+            Err(SeekError::NoSuchSource { index: 2 })
+        ));
+
+        let played: Vec<f32> = queue_rx.take(5).collect();
+        assert_eq!(played, vec![-1.0, -1.0, -1.0, 500.0, 501.0]);
+    }
+
     #[test]
     fn test_volume() {
         let (sink, mut queue_rx) = Sink::new();
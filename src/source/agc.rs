@@ -17,9 +17,9 @@ use super::SeekError;
 use crate::{Sample, Source};
 #[cfg(feature = "experimental")]
 use atomic_float::AtomicF32;
+use std::fmt;
 #[cfg(feature = "experimental")]
 use std::sync::atomic::{AtomicBool, Ordering};
-#[cfg(feature = "experimental")]
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -40,6 +40,15 @@ const fn power_of_two(n: usize) -> usize {
 /// A larger size provides more stable RMS values but increases latency.
 const RMS_WINDOW_SIZE: usize = power_of_two(8192);
 
+/// Shortest attack time in seconds, shorter times make the gain jump between samples.
+pub(crate) const MIN_ATTACK_TIME: f32 = 0.001;
+/// Longest attack time in seconds.
+pub(crate) const MAX_ATTACK_TIME: f32 = 60.0;
+/// Shortest release time in seconds.
+pub(crate) const MIN_RELEASE_TIME: f32 = 0.001;
+/// Longest release time in seconds.
+pub(crate) const MAX_RELEASE_TIME: f32 = 60.0;
+
 #[cfg(feature = "experimental")]
 /// Automatic Gain Control filter for maintaining consistent output levels.
 ///
@@ -57,6 +66,8 @@ pub struct AutomaticGainControl<I> {
     peak_level: f32,
     rms_window: CircularBuffer,
     is_enabled: Arc<AtomicBool>,
+    reported_gain: Arc<AtomicF32>,
+    gain_change: Option<GainChangeCallback>,
 }
 
 #[cfg(not(feature = "experimental"))]
@@ -76,6 +87,36 @@ pub struct AutomaticGainControl<I> {
     peak_level: f32,
     rms_window: CircularBuffer,
     is_enabled: bool,
+    gain_change: Option<GainChangeCallback>,
+}
+
+/// Callback invoked when the applied gain has moved far enough from the gain
+/// that was last reported.
+#[derive(Clone)]
+struct GainChangeCallback {
+    threshold_db: f32,
+    last_reported_gain: f32,
+    callback: Arc<dyn Fn(f32) + Send + Sync>,
+}
+
+impl fmt::Debug for GainChangeCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GainChangeCallback")
+            .field("threshold_db", &self.threshold_db)
+            .field("last_reported_gain", &self.last_reported_gain)
+            .finish_non_exhaustive()
+    }
+}
+
+impl GainChangeCallback {
+    #[inline]
+    fn update(&mut self, gain: f32) {
+        let change_db = 20.0 * (gain / self.last_reported_gain).log10();
+        if change_db.abs() > self.threshold_db {
+            self.last_reported_gain = gain;
+            (self.callback)(gain);
+        }
+    }
 }
 
 /// A circular buffer for efficient RMS calculation over a sliding window.
@@ -161,6 +202,8 @@ where
             peak_level: 0.0,
             rms_window: CircularBuffer::new(),
             is_enabled: Arc::new(AtomicBool::new(true)),
+            reported_gain: Arc::new(AtomicF32::new(1.0)),
+            gain_change: None,
         }
     }
 
@@ -177,6 +220,7 @@ where
             peak_level: 0.0,
             rms_window: CircularBuffer::new(),
             is_enabled: true,
+            gain_change: None,
         }
     }
 }
@@ -301,6 +345,40 @@ where
         Arc::clone(&self.is_enabled)
     }
 
+    #[cfg(feature = "experimental")]
+    /// Access the gain currently applied by the AGC.
+    ///
+    /// Use this to monitor the AGC while audio is processing, for example to display
+    /// how much a quiet or loud passage is being corrected. The value is a linear gain,
+    /// writing to it has no effect.
+    #[inline]
+    pub fn get_current_gain(&self) -> Arc<AtomicF32> {
+        Arc::clone(&self.reported_gain)
+    }
+
+    /// Returns the gain currently applied by the AGC as a linear factor.
+    #[inline]
+    pub fn current_gain(&self) -> f32 {
+        self.current_gain
+    }
+
+    /// Calls `callback` with the new gain whenever the applied gain has changed by
+    /// more than `threshold_db` decibels since the last call.
+    ///
+    /// The callback runs on the audio thread, keep it short.
+    #[inline]
+    pub fn on_gain_change<F>(mut self, threshold_db: f32, callback: F) -> Self
+    where
+        F: Fn(f32) + Send + Sync + 'static,
+    {
+        self.gain_change = Some(GainChangeCallback {
+            threshold_db,
+            last_reported_gain: self.current_gain,
+            callback: Arc::new(callback),
+        });
+        self
+    }
+
     #[cfg(not(feature = "experimental"))]
     /// Enable or disable AGC processing.
     ///
@@ -412,6 +490,13 @@ where
         // Ensure the calculated gain stays within the defined operational range
         self.current_gain = self.current_gain.clamp(0.1, self.absolute_max_gain());
 
+        #[cfg(feature = "experimental")]
+        self.reported_gain
+            .store(self.current_gain, Ordering::Relaxed);
+        if let Some(gain_change) = &mut self.gain_change {
+            gain_change.update(self.current_gain);
+        }
+
         // Output current gain value for developers to fine tune their inputs to automatic_gain_control
         #[cfg(feature = "tracing")]
         tracing::debug!("AGC gain: {}", self.current_gain,);
@@ -487,8 +572,75 @@ where
         self.input.total_duration()
     }
 
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
     #[inline]
     fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
         self.input.try_seek(pos)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::{automatic_gain_control, MAX_ATTACK_TIME, MIN_RELEASE_TIME};
+    use crate::buffer::SamplesBuffer;
+    use crate::Source;
+
+    fn time_to_coeff(time: f32, sample_rate: u32) -> f32 {
+        (-1.0 / (time * sample_rate as f32)).exp()
+    }
+
+    #[test]
+    fn keeps_long_attack_times() {
+        // Longer than the 10 seconds these used to be cut to.
+        let source = SamplesBuffer::new(1, 1000, vec![0.0f32; 10]);
+        let agc = source.automatic_gain_control(1.0, 20.0, 30.0, 5.0);
+        assert_eq!(agc.attack_coeff(), time_to_coeff(20.0, 1000));
+        assert_eq!(agc.release_coeff(), time_to_coeff(30.0, 1000));
+    }
+
+    #[test]
+    fn clamps_out_of_range_times() {
+        let source = SamplesBuffer::new(1, 1000, vec![0.0f32; 10]);
+        let agc = source.automatic_gain_control(1.0, 1000.0, 0.0, 5.0);
+        assert_eq!(agc.attack_coeff(), time_to_coeff(MAX_ATTACK_TIME, 1000));
+        assert_eq!(agc.release_coeff(), time_to_coeff(MIN_RELEASE_TIME, 1000));
+    }
+
+    #[test]
+    fn reports_gain_compensating_level_jump() {
+        let rate = 8000;
+        let tone = |amplitude: f32| (0..2 * rate).map(move |i| amplitude * (i as f32 * 0.1).sin());
+        let data: Vec<f32> = tone(0.05).chain(tone(0.8)).collect();
+        let source = SamplesBuffer::new(1, rate, data);
+
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let reported_clone = Arc::clone(&reported);
+        let mut agc = automatic_gain_control(source, 0.5, 0.5, 0.005, 20.0)
+            .on_gain_change(1.0, move |gain| reported_clone.lock().unwrap().push(gain));
+
+        agc.by_ref().take(2 * rate as usize).for_each(drop);
+        let quiet_gain = agc.current_gain();
+        assert!(
+            quiet_gain > 2.0,
+            "gain {quiet_gain} did not boost the quiet part"
+        );
+
+        agc.by_ref().for_each(drop);
+        let loud_gain = agc.current_gain();
+        assert!(
+            loud_gain < 1.0,
+            "gain {loud_gain} did not attenuate the loud part"
+        );
+
+        let reported = reported.lock().unwrap();
+        assert!(reported.len() > 2);
+        assert!(reported.iter().any(|&gain| gain > 2.0));
+        assert_eq!(reported.last().copied().map(|g| g < 1.5), Some(true));
+    }
+}
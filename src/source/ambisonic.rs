@@ -0,0 +1,232 @@
+use std::f32::consts::SQRT_2;
+use std::time::Duration;
+
+use super::{SeekError, SpeakerLayout};
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Internal function that builds an `AmbisonicDecoder` object.
+pub fn decode_ambisonic<I>(input: I, layout: SpeakerLayout) -> AmbisonicDecoder<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    AmbisonicDecoder {
+        input,
+        layout,
+        gains: decoding_matrix(layout),
+        frame: Vec::with_capacity(layout.channels() as usize),
+        pos: 0,
+    }
+}
+
+// Gains of W, X, Y and Z for every speaker of `layout`. This is the basic projection
+// decoder: every speaker samples the sound field in its own direction, and for a
+// sound in any direction the speakers add up to its original level. The layouts are
+// horizontal so Z is not used. The LFE channel stays silent.
+fn decoding_matrix(layout: SpeakerLayout) -> Vec<[f32; 4]> {
+    let azimuths = layout.azimuths();
+    let directional = azimuths.iter().flatten().count() as f32;
+    azimuths
+        .iter()
+        .map(|azimuth| match azimuth {
+            Some(azimuth) => {
+                // B-format measures angles counter-clockwise, with Y pointing left.
+                let angle = -azimuth;
+                [
+                    SQRT_2 / directional,
+                    2.0 * angle.cos() / directional,
+                    2.0 * angle.sin() / directional,
+                    0.0,
+                ]
+            }
+            None => [0.0; 4],
+        })
+        .collect()
+}
+
+/// Decodes first-order ambisonics to a speaker layout, see
+/// [`Source::decode_ambisonic`].
+#[derive(Clone, Debug)]
+pub struct AmbisonicDecoder<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    input: I,
+    layout: SpeakerLayout,
+    // Gains of W, X, Y and Z per output channel.
+    gains: Vec<[f32; 4]>,
+    // Current output frame and the index of its next sample.
+    frame: Vec<I::Item>,
+    pos: usize,
+}
+
+impl<I> AmbisonicDecoder<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    /// Returns the speaker layout the source plays on.
+    #[inline]
+    pub fn layout(&self) -> SpeakerLayout {
+        self.layout
+    }
+
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+
+    fn next_frame(&mut self) -> Option<()> {
+        let channels = self.input.channels() as usize;
+        let mut components = [I::Item::zero_value(); 4];
+        components[0] = self.input.next()?;
+        // Missing components are silent, channels beyond the fourth are dropped.
+        for index in 1..channels {
+            let Some(sample) = self.input.next() else {
+                break;
+            };
+            if let Some(component) = components.get_mut(index) {
+                *component = sample;
+            }
+        }
+
+        self.frame.clear();
+        self.pos = 0;
+        self.frame.extend(self.gains.iter().map(|gains| {
+            gains
+                .iter()
+                .zip(components)
+                .fold(I::Item::zero_value(), |sum, (&gain, component)| {
+                    sum.saturating_add(component.amplify(gain))
+                })
+        }));
+        Some(())
+    }
+
+    fn output_len(&self, input_len: usize) -> usize {
+        let in_channels = self.input.channels() as usize;
+        let out_channels = self.layout.channels() as usize;
+        input_len
+            .div_ceil(in_channels)
+            .saturating_mul(out_channels)
+            .saturating_add(self.frame.len() - self.pos)
+    }
+}
+
+impl<I> Iterator for AmbisonicDecoder<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        if self.pos == self.frame.len() {
+            self.next_frame()?;
+        }
+        let sample = self.frame[self.pos];
+        self.pos += 1;
+        Some(sample)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.input.size_hint();
+        (
+            self.output_len(lower),
+            upper.map(|upper| self.output_len(upper)),
+        )
+    }
+}
+
+impl<I> ExactSizeIterator for AmbisonicDecoder<I>
+where
+    I: Source + ExactSizeIterator,
+    I::Item: Sample,
+{
+}
+
+impl<I> Source for AmbisonicDecoder<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        self.input
+            .current_span_len()
+            .map(|len| self.output_len(len))
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.layout.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.frame.clear();
+        self.pos = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::buffer::SamplesBuffer;
+    use crate::source::SpeakerLayout;
+    use crate::Source;
+
+    #[test]
+    fn front_component_plays_on_front_speakers() {
+        let b_format = [0.0f32, 1.0, 0.0, 0.0].repeat(10);
+        let decoder =
+            SamplesBuffer::new(4, 1000, b_format).decode_ambisonic(SpeakerLayout::Surround51);
+        assert_eq!(decoder.channels(), 6);
+        assert_eq!(decoder.size_hint(), (60, Some(60)));
+
+        let output: Vec<f32> = decoder.collect();
+        for frame in output.chunks(6) {
+            let [left, right, center, lfe, surround_left, surround_right] = frame else {
+                panic!("incomplete frame");
+            };
+            assert!(*center > 0.0);
+            assert!((left - right).abs() < 1e-6 && *left > 0.0);
+            assert_eq!(*lfe, 0.0);
+            assert!((surround_left - surround_right).abs() < 1e-6);
+            assert!(left.abs() > surround_left.abs() * 2.0);
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use std::f32::consts::{FRAC_PI_4, TAU};
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Internal function that builds an `AutoPan` object.
+pub fn auto_pan<I>(input: I, rate_hz: f32, depth: f32) -> AutoPan<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    AutoPan {
+        input,
+        rate_hz,
+        depth: depth.clamp(0.0, 1.0),
+        phase: 0.0,
+        gains: [1.0, 1.0],
+        channel: 0,
+        right: None,
+    }
+}
+
+/// Sweeps the sound between the left and right speaker, see [`Source::auto_pan`].
+#[derive(Clone, Debug)]
+pub struct AutoPan<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    input: I,
+    rate_hz: f32,
+    depth: f32,
+    // Position of the LFO in its cycle, between 0 and 1.
+    phase: f32,
+    // Left and right gain for the current frame.
+    gains: [f32; 2],
+    // Channel of the next input sample.
+    channel: usize,
+    // Right sample of a mono input that still has to be played.
+    right: Option<I::Item>,
+}
+
+impl<I> AutoPan<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    /// Changes how many times per second the sound sweeps from one side to the other
+    /// and back.
+    #[inline]
+    pub fn set_rate(&mut self, rate_hz: f32) {
+        self.rate_hz = rate_hz;
+    }
+
+    /// Changes how far the sound sweeps, `1.0` reaches fully left and right.
+    #[inline]
+    pub fn set_depth(&mut self, depth: f32) {
+        self.depth = depth.clamp(0.0, 1.0);
+    }
+
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+
+    fn start_frame(&mut self) {
+        let pan = self.depth * (TAU * self.phase).sin();
+        // Constant power: the angle goes from 0 (left) to 90 degrees (right).
+        let angle = (pan + 1.0) * FRAC_PI_4;
+        self.gains = [angle.cos(), angle.sin()];
+        self.phase = (self.phase + self.rate_hz / self.input.sample_rate() as f32).fract();
+    }
+}
+
+impl<I> Iterator for AutoPan<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        if let Some(right) = self.right.take() {
+            return Some(right);
+        }
+
+        let sample = self.input.next()?;
+        if self.channel == 0 {
+            self.start_frame();
+        }
+        let channels = self.input.channels() as usize;
+        if channels == 1 {
+            self.right = Some(sample.amplify(self.gains[1]));
+            return Some(sample.amplify(self.gains[0]));
+        }
+
+        let gain = self.gains.get(self.channel).copied().unwrap_or(1.0);
+        self.channel = (self.channel + 1) % channels;
+        Some(sample.amplify(gain))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.input.size_hint();
+        let extra = self.right.is_some() as usize;
+        if self.input.channels() == 1 {
+            (
+                lower.saturating_mul(2).saturating_add(extra),
+                upper.and_then(|upper| upper.checked_mul(2)?.checked_add(extra)),
+            )
+        } else {
+            (lower, upper)
+        }
+    }
+}
+
+impl<I> ExactSizeIterator for AutoPan<I>
+where
+    I: Source + ExactSizeIterator,
+    I::Item: Sample,
+{
+}
+
+impl<I> Source for AutoPan<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        let extra = self.right.is_some() as usize;
+        match self.input.current_span_len() {
+            Some(len) if self.input.channels() == 1 => {
+                Some(len.saturating_mul(2).saturating_add(extra))
+            }
+            len => len,
+        }
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels().max(2)
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.phase = (pos.as_secs_f64() * self.rate_hz as f64).fract() as f32;
+        self.channel = 0;
+        self.right = None;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::buffer::SamplesBuffer;
+    use crate::Source;
+
+    #[test]
+    fn balance_oscillates_at_rate() {
+        let input = SamplesBuffer::new(1, 1000, vec![1.0f32; 1000]);
+        let panned = input.auto_pan(2.0, 1.0);
+        assert_eq!(panned.channels(), 2);
+
+        let frames: Vec<f32> = panned.collect();
+        let balance: Vec<f32> = frames.chunks(2).map(|f| f[1] - f[0]).collect();
+        assert_eq!(balance.len(), 1000);
+
+        // Fully right a quarter of the way through each cycle, fully left at three quarters.
+        assert!(balance[125] > 0.99 && balance[375] < -0.99);
+        assert!(balance[625] > 0.99 && balance[875] < -0.99);
+        for frame in frames.chunks(2) {
+            let power = frame[0] * frame[0] + frame[1] * frame[1];
+            assert!((power - 1.0).abs() < 1e-4);
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{BitDepth, ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Internal function that builds a `BitDepthReduce` object.
+pub fn to_bit_depth<I>(input: I, bits: BitDepth) -> BitDepthReduce<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    BitDepthReduce {
+        input,
+        scale: bits.scale_factor(),
+        dither: false,
+        rng_state: 0x9E37_79B9,
+    }
+}
+
+/// Filter that requantizes samples to a lower bit depth, see [`Source::to_bit_depth`].
+#[derive(Clone, Debug)]
+pub struct BitDepthReduce<I> {
+    input: I,
+    // Number of quantization steps between 0.0 and 1.0.
+    scale: f32,
+    dither: bool,
+    // State of the xorshift generator used for dither.
+    rng_state: u32,
+}
+
+impl<I> BitDepthReduce<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    /// Adds triangular dither of one step before quantizing.
+    ///
+    /// This turns the distortion caused by quantization into a low level of noise,
+    /// which is much less audible on quiet material.
+    #[inline]
+    pub fn with_dither(mut self) -> Self {
+        self.dither = true;
+        self
+    }
+
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+
+    // Uniform random number in `[0.0, 1.0)`.
+    fn next_random(&mut self) -> f32 {
+        self.rng_state ^= self.rng_state << 13;
+        self.rng_state ^= self.rng_state >> 17;
+        self.rng_state ^= self.rng_state << 5;
+        (self.rng_state >> 8) as f32 / (1 << 24) as f32
+    }
+}
+
+impl<I> Iterator for BitDepthReduce<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = f32;
+
+    #[inline]
+    fn next(&mut self) -> Option<f32> {
+        let sample = self.input.next()?.to_f32();
+        let noise = if self.dither {
+            self.next_random() - self.next_random()
+        } else {
+            0.0
+        };
+        let step = (sample * self.scale + noise)
+            .round()
+            .clamp(-self.scale, self.scale - 1.0) as i32;
+        Some(step as f32 / self.scale)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for BitDepthReduce<I>
+where
+    I: Source + ExactSizeIterator,
+    I::Item: Sample,
+{
+}
+
+impl<I> Source for BitDepthReduce<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::buffer::SamplesBuffer;
+    use crate::common::BitDepth;
+    use crate::Source;
+
+    fn ramp() -> SamplesBuffer<f32> {
+        let samples = (0..10_000)
+            .map(|i| i as f32 / 5_000.0 - 1.0)
+            .collect::<Vec<_>>();
+        SamplesBuffer::new(1, 48000, samples)
+    }
+
+    #[test]
+    fn reduces_to_256_values() {
+        let values: HashSet<u32> = ramp()
+            .to_bit_depth(BitDepth::BITS_8)
+            .map(f32::to_bits)
+            .collect();
+        assert_eq!(values.len(), 256);
+    }
+
+    #[test]
+    fn dither_stays_on_grid() {
+        let reduced = ramp().to_bit_depth(BitDepth::BITS_8).with_dither();
+        for sample in reduced {
+            let step = sample * 128.0;
+            assert_eq!(step, step.round());
+            assert!((-128.0..=127.0).contains(&step));
+        }
+    }
+}
@@ -3,6 +3,7 @@ use crate::Source;
 use std::f32::consts::PI;
 use std::time::Duration;
 
+use super::denormal::flush_denormal;
 use super::SeekError;
 
 // Implemented following http://www.musicdsp.org/files/Audio-EQ-Cookbook.txt
@@ -127,11 +128,12 @@ where
             Some(s) => s,
         };
 
-        let result = self
-            .applier
-            .as_ref()
-            .unwrap()
-            .apply(sample, self.x_n1, self.x_n2, self.y_n1, self.y_n2);
+        let result = flush_denormal(
+            self.applier
+                .as_ref()
+                .unwrap()
+                .apply(sample, self.x_n1, self.x_n2, self.y_n1, self.y_n2),
+        );
 
         self.y_n2 = self.y_n1;
         self.x_n2 = self.x_n1;
@@ -177,6 +179,11 @@ where
         self.input.total_duration()
     }
 
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
     #[inline]
     fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
         self.input.try_seek(pos)
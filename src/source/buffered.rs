@@ -65,6 +65,7 @@ where
     data: Vec<I::Item>,
     channels: ChannelCount,
     rate: SampleRate,
+    latency: Duration,
     next: Mutex<Arc<Span<I>>>,
 }
 
@@ -112,6 +113,7 @@ where
 
     let channels = input.channels();
     let rate = input.sample_rate();
+    let latency = input.latency();
     let data: Vec<I::Item> = input
         .by_ref()
         .take(cmp::min(span_len.unwrap_or(32768), 32768))
@@ -125,6 +127,7 @@ where
         data,
         channels,
         rate,
+        latency,
         next: Mutex::new(Arc::new(Span::Input(Mutex::new(Some(input))))),
     }))
 }
@@ -242,6 +245,15 @@ where
         self.total_duration
     }
 
+    #[inline]
+    fn latency(&self) -> Duration {
+        match *self.current_span {
+            Span::Data(SpanData { latency, .. }) => latency,
+            Span::End => Duration::ZERO,
+            Span::Input(_) => unreachable!(),
+        }
+    }
+
     /// Can not support seek, in the end state we lose the underlying source
     /// which makes seeking back impossible.
     #[inline]
@@ -0,0 +1,146 @@
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Internal function that builds a `ChannelGains` object.
+pub fn channel_gains<I>(input: I, gains: Vec<f32>) -> ChannelGains<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    assert_eq!(
+        gains.len(),
+        input.channels() as usize,
+        "there must be one gain for every channel"
+    );
+    ChannelGains {
+        input,
+        gains,
+        channel: 0,
+    }
+}
+
+/// Applies a fixed gain to every channel, see [`Source::channel_gains`].
+#[derive(Clone, Debug)]
+pub struct ChannelGains<I> {
+    input: I,
+    gains: Vec<f32>,
+    // Channel of the next sample.
+    channel: usize,
+}
+
+impl<I> ChannelGains<I> {
+    /// Returns the gain of every channel.
+    #[inline]
+    pub fn gains(&self) -> &[f32] {
+        &self.gains
+    }
+
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+}
+
+impl<I> Iterator for ChannelGains<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        let channels = self.input.channels() as usize;
+        let sample = self.input.next()?;
+        // Channels the gains do not cover pass unchanged.
+        let gain = self.gains.get(self.channel).copied().unwrap_or(1.0);
+        self.channel = (self.channel + 1) % channels;
+        Some(sample.amplify(gain))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for ChannelGains<I>
+where
+    I: Source + ExactSizeIterator,
+    I::Item: Sample,
+{
+}
+
+impl<I> Source for ChannelGains<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.channel = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::buffer::SamplesBuffer;
+    use crate::Source;
+
+    #[test]
+    fn silences_right_channel() {
+        let input = SamplesBuffer::new(2, 1000, vec![0.5f32, 0.5, -0.25, -0.25, 1.0, 1.0]);
+        let samples: Vec<f32> = input.channel_gains(vec![1.0, 0.0]).collect();
+        assert_eq!(samples, vec![0.5, 0.0, -0.25, 0.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "one gain for every channel")]
+    fn wrong_number_of_gains() {
+        let input = SamplesBuffer::new(2, 1000, vec![0.0f32; 4]);
+        let _ = input.channel_gains(vec![1.0]);
+    }
+}
@@ -0,0 +1,104 @@
+use crate::buffer::SamplesBuffer;
+use crate::{Sample, Source};
+
+/// Internal function that builds a `Chunked` object.
+pub fn chunked<I>(input: I, frames_per_chunk: usize) -> Chunked<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    assert!(frames_per_chunk > 0, "a chunk needs at least one frame");
+    Chunked {
+        input,
+        frames_per_chunk,
+    }
+}
+
+/// Iterator over blocks of a source as [`SamplesBuffer`]s, see [`Source::chunked`].
+#[derive(Clone, Debug)]
+pub struct Chunked<I> {
+    input: I,
+    frames_per_chunk: usize,
+}
+
+impl<I> Chunked<I> {
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+}
+
+impl<I> Iterator for Chunked<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = SamplesBuffer<f32>;
+
+    fn next(&mut self) -> Option<SamplesBuffer<f32>> {
+        let channels = self.input.channels();
+        let sample_rate = self.input.sample_rate();
+        let mut len = self.frames_per_chunk * channels as usize;
+        // Chunks end at span boundaries so the format stays the same within a chunk.
+        if let Some(span_len) = self.input.current_span_len().filter(|&len| len > 0) {
+            len = len.min(span_len);
+        }
+
+        let samples: Vec<f32> = self
+            .input
+            .by_ref()
+            .take(len)
+            .map(|sample| sample.to_f32())
+            .collect();
+        if samples.is_empty() {
+            None
+        } else {
+            Some(SamplesBuffer::new(channels, sample_rate, samples))
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let samples_per_chunk = self.frames_per_chunk * self.input.channels() as usize;
+        let (lower, upper) = self.input.size_hint();
+        (
+            lower / samples_per_chunk,
+            upper.map(|upper| upper.div_ceil(samples_per_chunk)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::buffer::SamplesBuffer;
+    use crate::Source;
+
+    #[test]
+    fn chunks_concatenate_to_original() {
+        let counter: Vec<f32> = (0..22).map(|n| n as f32).collect();
+        let input = SamplesBuffer::new(2, 8000, counter.clone());
+
+        let chunks: Vec<SamplesBuffer<f32>> = input.chunked(4).collect();
+        let sizes: Vec<usize> = chunks.iter().map(|chunk| chunk.size_hint().0).collect();
+        assert_eq!(sizes, [8, 8, 6]);
+        assert!(chunks
+            .iter()
+            .all(|chunk| chunk.channels() == 2 && chunk.sample_rate() == 8000));
+
+        let joined: Vec<f32> = chunks.into_iter().flatten().collect();
+        assert_eq!(joined, counter);
+    }
+}
@@ -0,0 +1,432 @@
+use std::time::Duration;
+
+use super::{LinkMode, SeekError};
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+// Level assumed for silence, keeps the logarithm finite.
+const SILENCE_DB: f32 = -120.0;
+
+/// Internal function that builds a `Compressor` object.
+pub fn compress<I>(input: I, settings: CompressorSettings) -> Compressor<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    Compressor {
+        input,
+        settings,
+        coefficients: None,
+        reductions: Vec::new(),
+        frame: Vec::new(),
+        frame_pos: 0,
+    }
+}
+
+/// Settings of a [`Compressor`], see [`Source::compress`].
+///
+/// Start from one of the presets, or from the default that gently compresses at a
+/// ratio of 2:1 above -18 dBFS, and adjust it with the `with_` methods.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use rodio::source::{CompressorSettings, SineWave, Source};
+///
+/// let settings = CompressorSettings::default()
+///     .with_threshold(-24.0)
+///     .with_ratio(4.0)
+///     .with_attack(Duration::from_millis(5))
+///     .with_makeup_gain(6.0);
+/// let source = SineWave::new(440.0).compress(settings);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CompressorSettings {
+    threshold_db: f32,
+    ratio: f32,
+    knee_db: f32,
+    attack: Duration,
+    release: Duration,
+    makeup_db: f32,
+    link: LinkMode,
+}
+
+impl Default for CompressorSettings {
+    fn default() -> Self {
+        CompressorSettings {
+            threshold_db: -18.0,
+            ratio: 2.0,
+            knee_db: 6.0,
+            attack: Duration::from_millis(10),
+            release: Duration::from_millis(100),
+            makeup_db: 0.0,
+            link: LinkMode::Coupled,
+        }
+    }
+}
+
+impl CompressorSettings {
+    /// Evens out speech: a 3:1 ratio above -20 dBFS with a fast attack, and 4 dB of
+    /// makeup gain to bring the quieter syllables forward.
+    pub fn voice() -> CompressorSettings {
+        CompressorSettings {
+            threshold_db: -20.0,
+            ratio: 3.0,
+            knee_db: 6.0,
+            attack: Duration::from_millis(5),
+            release: Duration::from_millis(80),
+            makeup_db: 4.0,
+            link: LinkMode::Coupled,
+        }
+    }
+
+    /// Glues a mix together without squashing it: a 2:1 ratio above -16 dBFS with a
+    /// soft knee and a slow attack that lets transients through.
+    pub fn music() -> CompressorSettings {
+        CompressorSettings {
+            threshold_db: -16.0,
+            ratio: 2.0,
+            knee_db: 10.0,
+            attack: Duration::from_millis(25),
+            release: Duration::from_millis(250),
+            makeup_db: 2.0,
+            link: LinkMode::Coupled,
+        }
+    }
+
+    /// Sets the level in dBFS above which the sound is compressed.
+    pub fn with_threshold(mut self, threshold_db: f32) -> CompressorSettings {
+        self.threshold_db = threshold_db;
+        self
+    }
+
+    /// Sets by how much the level above the threshold is divided, for example `4.0`
+    /// lets a sound 8 dB above the threshold come out 2 dB above it. A ratio of `1.0`
+    /// does not compress at all.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ratio` is below `1.0`.
+    pub fn with_ratio(mut self, ratio: f32) -> CompressorSettings {
+        assert!(ratio >= 1.0, "a compressor ratio must be at least 1");
+        self.ratio = ratio;
+        self
+    }
+
+    /// Sets the width in dB of the range around the threshold in which compression
+    /// sets in gradually, `0.0` for a hard knee.
+    pub fn with_knee(mut self, knee_db: f32) -> CompressorSettings {
+        self.knee_db = knee_db.max(0.0);
+        self
+    }
+
+    /// Sets how quickly the gain goes down once the sound rises above the threshold.
+    pub fn with_attack(mut self, attack: Duration) -> CompressorSettings {
+        self.attack = attack;
+        self
+    }
+
+    /// Sets how quickly the gain recovers once the sound falls below the threshold.
+    pub fn with_release(mut self, release: Duration) -> CompressorSettings {
+        self.release = release;
+        self
+    }
+
+    /// Sets the gain in dB applied after compressing, to make up for the loudness
+    /// taken away.
+    pub fn with_makeup_gain(mut self, makeup_db: f32) -> CompressorSettings {
+        self.makeup_db = makeup_db;
+        self
+    }
+
+    /// Chooses whether a loud channel lowers the gain of the others, the default is
+    /// [`LinkMode::Coupled`].
+    pub fn with_channel_link(mut self, link: LinkMode) -> CompressorSettings {
+        self.link = link;
+        self
+    }
+
+    // Gain reduction in dB, zero or negative, for a level of `level_db`.
+    fn reduction_db(&self, level_db: f32) -> f32 {
+        let over = level_db - self.threshold_db;
+        let slope = 1.0 / self.ratio - 1.0;
+        if 2.0 * over <= -self.knee_db {
+            0.0
+        } else if 2.0 * over < self.knee_db {
+            let into_knee = over + self.knee_db / 2.0;
+            slope * into_knee * into_knee / (2.0 * self.knee_db)
+        } else {
+            slope * over
+        }
+    }
+}
+
+/// Compresses the dynamic range of a sound, see [`Source::compress`].
+#[derive(Clone, Debug)]
+pub struct Compressor<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    input: I,
+    settings: CompressorSettings,
+    // Smoothing of the attack and release for the sample rate they were computed for.
+    coefficients: Option<(f32, f32, SampleRate)>,
+    // Smoothed gain reduction in dB, one for all channels when coupled, else one for
+    // every channel.
+    reductions: Vec<f32>,
+    // The frame being played, already compressed.
+    frame: Vec<I::Item>,
+    frame_pos: usize,
+}
+
+impl<I> Compressor<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    /// Returns the settings the compressor was built with.
+    #[inline]
+    pub fn settings(&self) -> CompressorSettings {
+        self.settings
+    }
+
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+
+    fn coefficients(&mut self) -> (f32, f32) {
+        let sample_rate = self.input.sample_rate();
+        match self.coefficients {
+            Some((attack, release, rate)) if rate == sample_rate => (attack, release),
+            _ => {
+                let coefficient =
+                    |time: Duration| (-1.0 / (time.as_secs_f32() * sample_rate as f32)).exp();
+                let attack = coefficient(self.settings.attack);
+                let release = coefficient(self.settings.release);
+                self.coefficients = Some((attack, release, sample_rate));
+                (attack, release)
+            }
+        }
+    }
+
+    // Reads and compresses the next frame. Returns `false` if the input has ended.
+    fn next_frame(&mut self) -> bool {
+        self.frame.clear();
+        self.frame_pos = 0;
+        for _ in 0..self.input.channels() {
+            let Some(sample) = self.input.next() else {
+                break;
+            };
+            self.frame.push(sample);
+        }
+        if self.frame.is_empty() {
+            return false;
+        }
+        if self.settings.ratio == 1.0 && self.settings.makeup_db == 0.0 {
+            return true;
+        }
+
+        let trackers = match self.settings.link {
+            LinkMode::Coupled => 1,
+            LinkMode::Independent => self.frame.len(),
+        };
+        self.reductions.resize(trackers, 0.0);
+        let (attack, release) = self.coefficients();
+        for (tracker, reduction) in self.reductions.iter_mut().enumerate() {
+            let peak = match self.settings.link {
+                LinkMode::Coupled => self
+                    .frame
+                    .iter()
+                    .fold(0.0f32, |peak, s| peak.max(s.to_f32().abs())),
+                LinkMode::Independent => self.frame[tracker].to_f32().abs(),
+            };
+            let level_db = if peak > 0.0 {
+                (20.0 * peak.log10()).max(SILENCE_DB)
+            } else {
+                SILENCE_DB
+            };
+            let target = self.settings.reduction_db(level_db);
+            // Going further down follows the attack, coming back up the release.
+            let coefficient = if target < *reduction { attack } else { release };
+            *reduction = target + (*reduction - target) * coefficient;
+        }
+
+        for (channel, sample) in self.frame.iter_mut().enumerate() {
+            let reduction = self.reductions[channel.min(trackers - 1)];
+            let gain = 10f32.powf((reduction + self.settings.makeup_db) / 20.0);
+            *sample = sample.amplify(gain);
+        }
+        true
+    }
+
+    fn buffered(&self) -> usize {
+        self.frame.len() - self.frame_pos
+    }
+}
+
+impl<I> Iterator for Compressor<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        if self.frame_pos >= self.frame.len() && !self.next_frame() {
+            return None;
+        }
+        let sample = self.frame[self.frame_pos];
+        self.frame_pos += 1;
+        Some(sample)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.input.size_hint();
+        (
+            lower.saturating_add(self.buffered()),
+            upper.and_then(|upper| upper.checked_add(self.buffered())),
+        )
+    }
+}
+
+impl<I> ExactSizeIterator for Compressor<I>
+where
+    I: Source + ExactSizeIterator,
+    I::Item: Sample,
+{
+}
+
+impl<I> Source for Compressor<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        self.input
+            .current_span_len()
+            .map(|len| len.saturating_add(self.buffered()))
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.frame.clear();
+        self.frame_pos = 0;
+        self.reductions.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+    use std::time::Duration;
+
+    use crate::buffer::SamplesBuffer;
+    use crate::source::{CompressorSettings, LinkMode};
+    use crate::Source;
+
+    fn stereo(left: f32, right: f32) -> SamplesBuffer<f32> {
+        let samples = (0..48000)
+            .flat_map(|n| {
+                let tone = (2.0 * PI * 1000.0 * n as f32 / 48000.0).sin();
+                [left * tone, right * tone]
+            })
+            .collect::<Vec<_>>();
+        SamplesBuffer::new(2, 48000, samples)
+    }
+
+    // Peaks of the left and right channel once the compressor settled.
+    fn settled_peaks(source: impl Source<Item = f32>) -> (f32, f32) {
+        let samples = source.collect::<Vec<_>>();
+        samples[48000..]
+            .chunks(2)
+            .fold((0.0, 0.0), |(l, r), frame| {
+                (l.max(frame[0].abs()), r.max(frame[1].abs()))
+            })
+    }
+
+    #[test]
+    fn ratio_of_one_is_a_no_op() {
+        let settings = CompressorSettings::default()
+            .with_threshold(-40.0)
+            .with_ratio(1.0);
+        let compressed: Vec<f32> = stereo(1.0, 0.5).compress(settings).collect();
+        assert_eq!(compressed, stereo(1.0, 0.5).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn compresses_above_threshold() {
+        // A fast attack against a slow release follows the peaks of the tone closely.
+        let settings = CompressorSettings::default()
+            .with_threshold(-20.0)
+            .with_ratio(4.0)
+            .with_knee(0.0)
+            .with_attack(Duration::from_micros(100))
+            .with_release(Duration::from_millis(500));
+        // 14 dB above the threshold comes out 3.5 dB above it.
+        let (left, _) = settled_peaks(stereo(0.5, 0.5).compress(settings));
+        let expected = 10f32.powf(-16.5 / 20.0);
+        assert!((left / expected - 1.0).abs() < 0.05, "peak of {left}");
+
+        // Below the threshold only the makeup gain applies.
+        let (left, _) = settled_peaks(stereo(0.05, 0.05).compress(settings.with_makeup_gain(6.0)));
+        assert!((left / 0.1 - 1.0).abs() < 0.01, "peak of {left}");
+    }
+
+    #[test]
+    fn independent_link_leaves_quiet_channel_alone() {
+        let settings = CompressorSettings::default()
+            .with_threshold(-20.0)
+            .with_ratio(4.0)
+            .with_knee(0.0);
+        let (_, coupled) = settled_peaks(stereo(0.5, 0.05).compress(settings));
+        let (_, independent) = settled_peaks(
+            stereo(0.5, 0.05).compress(settings.with_channel_link(LinkMode::Independent)),
+        );
+        assert!(coupled < 0.03, "coupled right channel peaks at {coupled}");
+        assert!(
+            (independent - 0.05).abs() < 1e-4,
+            "independent right channel peaks at {independent}"
+        );
+    }
+}
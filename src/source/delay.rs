@@ -2,6 +2,7 @@ use std::time::Duration;
 
 use super::SeekError;
 use crate::common::{ChannelCount, SampleRate};
+use crate::math;
 use crate::{Sample, Source};
 
 fn remaining_samples(
@@ -80,8 +81,8 @@ where
     fn size_hint(&self) -> (usize, Option<usize>) {
         let (min, max) = self.input.size_hint();
         (
-            min + self.remaining_samples,
-            max.map(|v| v + self.remaining_samples),
+            min.saturating_add(self.remaining_samples),
+            max.and_then(|v| v.checked_add(self.remaining_samples)),
         )
     }
 }
@@ -95,7 +96,7 @@ where
     fn current_span_len(&self) -> Option<usize> {
         self.input
             .current_span_len()
-            .map(|val| val + self.remaining_samples)
+            .map(|val| val.saturating_add(self.remaining_samples))
     }
 
     #[inline]
@@ -115,6 +116,11 @@ where
             .map(|val| val + self.requested_duration)
     }
 
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
     /// Pos is seen from the perspective of the api user.
     ///
     /// # Example
@@ -140,3 +146,146 @@ where
         self.input.try_seek(compensated_for_delay)
     }
 }
+
+/// Internal function that builds a `DelaySamples` object.
+pub fn delay_samples<I>(input: I, frames: usize) -> DelaySamples<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    DelaySamples {
+        remaining_samples: frames * input.channels() as usize,
+        frames,
+        input,
+    }
+}
+
+/// A source that delays the given source by a number of frames.
+#[derive(Clone, Debug)]
+pub struct DelaySamples<I> {
+    input: I,
+    remaining_samples: usize,
+    frames: usize,
+}
+
+impl<I> DelaySamples<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+
+    fn delay_duration(&self) -> Duration {
+        math::frames_to_duration(self.frames as u64, self.input.sample_rate())
+    }
+}
+
+impl<I> Iterator for DelaySamples<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = <I as Iterator>::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<<I as Iterator>::Item> {
+        if self.remaining_samples >= 1 {
+            self.remaining_samples -= 1;
+            Some(Sample::zero_value())
+        } else {
+            self.input.next()
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (min, max) = self.input.size_hint();
+        (
+            min.saturating_add(self.remaining_samples),
+            max.and_then(|v| v.checked_add(self.remaining_samples)),
+        )
+    }
+}
+
+impl<I> Source for DelaySamples<I>
+where
+    I: Iterator + Source,
+    I::Item: Sample,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        self.input
+            .current_span_len()
+            .map(|val| val.saturating_add(self.remaining_samples))
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input
+            .total_duration()
+            .map(|val| val + self.delay_duration())
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
+    /// Pos is seen from the perspective of the api user, like for [`Delay`].
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        let delay = self.delay_duration();
+        if pos < delay {
+            self.input.try_seek(Duration::ZERO)?;
+            let skipped = math::duration_to_frames(pos, self.sample_rate()) as usize;
+            self.remaining_samples = (self.frames - skipped) * self.channels() as usize;
+        } else {
+            self.input.try_seek(pos - delay)?;
+            self.remaining_samples = 0;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::buffer::SamplesBuffer;
+    use crate::Source;
+
+    #[test]
+    fn delay_samples_prepends_exact_frames() {
+        let input = SamplesBuffer::new(2, 44100, vec![1.0f32, 2.0, 3.0, 4.0]);
+        let delayed = input.delay_samples(3);
+        assert_eq!(delayed.size_hint(), (10, Some(10)));
+        assert_eq!(
+            delayed.collect::<Vec<_>>(),
+            [0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 2.0, 3.0, 4.0]
+        );
+    }
+}
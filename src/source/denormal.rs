@@ -0,0 +1,157 @@
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Samples closer to zero than this are replaced by zero. About -300 dB, far below
+/// anything audible but well above the range of denormal floats.
+const FLUSH_THRESHOLD: f32 = 1e-15;
+
+/// Replaces values too small to be audible by zero, so they never become denormal
+/// floats. Filters with feedback call this on their state.
+#[inline]
+pub(crate) fn flush_denormal(value: f32) -> f32 {
+    if value.abs() < FLUSH_THRESHOLD {
+        0.0
+    } else {
+        value
+    }
+}
+
+/// Internal function that builds a `FlushDenormals` object.
+pub fn flush_denormals<I>(input: I) -> FlushDenormals<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    FlushDenormals { input }
+}
+
+/// Filter that replaces inaudibly small samples by silence.
+#[derive(Clone, Debug)]
+pub struct FlushDenormals<I> {
+    input: I,
+}
+
+impl<I> FlushDenormals<I> {
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+}
+
+impl<I> Iterator for FlushDenormals<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        self.input.next().map(|sample| {
+            if sample.to_f32().abs() < FLUSH_THRESHOLD {
+                I::Item::zero_value()
+            } else {
+                sample
+            }
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for FlushDenormals<I>
+where
+    I: Source + ExactSizeIterator,
+    I::Item: Sample,
+{
+}
+
+impl<I> Source for FlushDenormals<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::buffer::SamplesBuffer;
+    use crate::Source;
+
+    fn is_normal_or_zero(sample: &f32) -> bool {
+        *sample == 0.0 || sample.is_normal()
+    }
+
+    #[test]
+    fn flushes_decaying_tail() {
+        // A one pole feedback loop decays into denormals after a few hundred samples.
+        let tail: Vec<f32> = std::iter::successors(Some(1.0f32), |y| Some(y * 0.7))
+            .take(400)
+            .collect();
+        assert!(!tail.iter().all(is_normal_or_zero));
+
+        let flushed: Vec<f32> = SamplesBuffer::new(1, 48000, tail)
+            .flush_denormals()
+            .collect();
+        assert!(flushed.iter().all(is_normal_or_zero));
+        assert_eq!(flushed[0], 1.0);
+    }
+
+    #[test]
+    fn filter_tail_has_no_denormals() {
+        let mut impulse = vec![0.0f32; 48000];
+        impulse[0] = 1.0;
+        let filtered: Vec<f32> = SamplesBuffer::new(1, 48000, impulse)
+            .low_pass(1000)
+            .collect();
+        assert!(filtered.iter().all(is_normal_or_zero));
+    }
+}
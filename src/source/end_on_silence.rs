@@ -0,0 +1,223 @@
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Internal function that builds an `EndOnSilence` object.
+pub fn end_on_silence<I>(input: I, threshold_db: f32, duration: Duration) -> EndOnSilence<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    EndOnSilence {
+        input,
+        threshold: 10f32.powf(threshold_db / 20.0),
+        duration,
+        silent_frames: 0,
+        frame_silent: true,
+        channel: 0,
+        ended: false,
+    }
+}
+
+/// Ends a source once it has been silent for too long.
+#[derive(Clone, Debug)]
+pub struct EndOnSilence<I> {
+    input: I,
+    // Linear amplitude below which a sample counts as silent.
+    threshold: f32,
+    duration: Duration,
+    // Number of consecutive silent frames played.
+    silent_frames: u64,
+    // Whether all samples of the current frame so far were silent.
+    frame_silent: bool,
+    // Channel of the next sample.
+    channel: ChannelCount,
+    ended: bool,
+}
+
+impl<I> EndOnSilence<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    /// Returns `true` if the source was ended because it was silent.
+    #[inline]
+    pub fn ended_on_silence(&self) -> bool {
+        self.ended
+    }
+
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+
+    #[inline]
+    fn max_silent_frames(&self) -> u64 {
+        (self.duration.as_secs_f64() * self.input.sample_rate() as f64).ceil() as u64
+    }
+}
+
+impl<I> Iterator for EndOnSilence<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        if self.ended {
+            return None;
+        }
+
+        // Only ends between frames, so the last frame played is complete.
+        if self.channel == 0 && self.silent_frames >= self.max_silent_frames() {
+            self.ended = true;
+            return None;
+        }
+
+        let sample = self.input.next()?;
+        // A frame is silent only if all of its channels are.
+        self.frame_silent &= sample.to_f32().abs() < self.threshold;
+        self.channel += 1;
+        if self.channel >= self.input.channels() {
+            self.channel = 0;
+            if self.frame_silent {
+                self.silent_frames += 1;
+            } else {
+                self.silent_frames = 0;
+            }
+            self.frame_silent = true;
+        }
+        Some(sample)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.ended {
+            (0, Some(0))
+        } else {
+            (0, self.input.size_hint().1)
+        }
+    }
+}
+
+impl<I> Source for EndOnSilence<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        if self.ended {
+            Some(0)
+        } else {
+            self.input.current_span_len()
+        }
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.silent_frames = 0;
+        self.frame_silent = true;
+        self.channel = 0;
+        self.ended = false;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::end_on_silence;
+    use crate::buffer::SamplesBuffer;
+
+    #[test]
+    fn ends_after_silence_window() {
+        // One second of signal followed by two seconds of near silence.
+        let mut data = vec![0.5f32; 100];
+        data.extend([0.0001; 200]);
+        let mut source = end_on_silence(
+            SamplesBuffer::new(1, 100, data),
+            -60.0,
+            Duration::from_millis(500),
+        );
+
+        assert_eq!(source.by_ref().count(), 150);
+        assert!(source.ended_on_silence());
+        assert_eq!(source.next(), None);
+    }
+
+    #[test]
+    fn short_pauses_do_not_end() {
+        let data = [vec![0.5f32; 10], vec![0.0; 40], vec![0.5; 10]].concat();
+        let source = end_on_silence(
+            SamplesBuffer::new(1, 100, data),
+            -60.0,
+            Duration::from_millis(500),
+        );
+
+        assert_eq!(source.count(), 60);
+    }
+
+    #[test]
+    fn stereo_counts_silent_frames() {
+        // Sound on one channel is enough to keep playing.
+        let data = [0.0f32, 0.5].repeat(100);
+        let source = end_on_silence(
+            SamplesBuffer::new(2, 100, data),
+            -60.0,
+            Duration::from_millis(500),
+        );
+        assert_eq!(source.count(), 200);
+
+        // The silence starts on the right channel of the last loud frame, the source
+        // still ends after whole frames.
+        let mut data = vec![0.5f32; 19];
+        data.resize(220, 0.0);
+        let mut source = end_on_silence(
+            SamplesBuffer::new(2, 100, data),
+            -60.0,
+            Duration::from_millis(500),
+        );
+        assert_eq!(source.by_ref().count(), 120);
+        assert!(source.ended_on_silence());
+    }
+}
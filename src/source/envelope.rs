@@ -0,0 +1,216 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Internal function that builds an `EnvelopeFollower` object.
+pub fn envelope_follower<I>(
+    input: I,
+    attack: Duration,
+    release: Duration,
+) -> (EnvelopeFollower<I>, EnvelopeHandle)
+where
+    I: Source,
+    I::Item: Sample,
+{
+    let handle = EnvelopeHandle {
+        envelope: Arc::new(AtomicU32::new(0.0f32.to_bits())),
+    };
+    let source = EnvelopeFollower {
+        input,
+        attack,
+        release,
+        coeffs: None,
+        envelope: 0.0,
+        handle: handle.clone(),
+    };
+    (source, handle)
+}
+
+/// Reads the envelope of an [`EnvelopeFollower`] from anywhere, for example from the
+/// thread drawing a level meter.
+#[derive(Clone, Debug)]
+pub struct EnvelopeHandle {
+    // Bits of the `f32` envelope.
+    envelope: Arc<AtomicU32>,
+}
+
+impl EnvelopeHandle {
+    /// Returns the envelope of the samples played last, between `0.0` for silence and
+    /// `1.0` for full scale.
+    #[inline]
+    pub fn envelope(&self) -> f32 {
+        f32::from_bits(self.envelope.load(Ordering::Relaxed))
+    }
+}
+
+/// Passes the sound through unchanged while tracking its envelope, see
+/// [`Source::envelope_follower`].
+#[derive(Clone, Debug)]
+pub struct EnvelopeFollower<I> {
+    input: I,
+    attack: Duration,
+    release: Duration,
+    // Attack and release coefficients and the format they were computed for.
+    coeffs: Option<(f32, f32, ChannelCount, SampleRate)>,
+    envelope: f32,
+    handle: EnvelopeHandle,
+}
+
+impl<I> EnvelopeFollower<I> {
+    /// Returns a handle that reads the envelope while the source plays.
+    #[inline]
+    pub fn handle(&self) -> EnvelopeHandle {
+        self.handle.clone()
+    }
+
+    /// Returns the current envelope, see [`EnvelopeHandle::envelope`].
+    #[inline]
+    pub fn envelope(&self) -> f32 {
+        self.envelope
+    }
+
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+}
+
+impl<I> EnvelopeFollower<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    fn coeffs(&mut self) -> (f32, f32) {
+        let (channels, rate) = (self.input.channels(), self.input.sample_rate());
+        match self.coeffs {
+            Some((attack, release, c, r)) if (c, r) == (channels, rate) => (attack, release),
+            _ => {
+                // All channels feed the same envelope, one after another.
+                let samples_per_sec = rate as f32 * channels as f32;
+                let coeff =
+                    |time: Duration| 1.0 - (-1.0 / (time.as_secs_f32() * samples_per_sec)).exp();
+                let (attack, release) = (coeff(self.attack), coeff(self.release));
+                self.coeffs = Some((attack, release, channels, rate));
+                (attack, release)
+            }
+        }
+    }
+}
+
+impl<I> Iterator for EnvelopeFollower<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        let sample = self.input.next()?;
+        let (attack, release) = self.coeffs();
+        let value = sample.to_f32().abs();
+        let coeff = if value > self.envelope {
+            attack
+        } else {
+            release
+        };
+        self.envelope += (value - self.envelope) * coeff;
+        self.handle
+            .envelope
+            .store(self.envelope.to_bits(), Ordering::Relaxed);
+        Some(sample)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for EnvelopeFollower<I>
+where
+    I: Source + ExactSizeIterator,
+    I::Item: Sample,
+{
+}
+
+impl<I> Source for EnvelopeFollower<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::buffer::SamplesBuffer;
+    use crate::Source;
+
+    #[test]
+    fn follows_burst() {
+        let mut samples = vec![0.0f32; 100];
+        samples.extend((0..200).map(|i| if i & 1 == 0 { 1.0 } else { -1.0 }));
+        samples.extend([0.0; 500]);
+        let (source, handle) = SamplesBuffer::new(1, 1000, samples)
+            .envelope_follower(Duration::from_millis(5), Duration::from_millis(50));
+
+        let envelope: Vec<f32> = source.map(|_| handle.envelope()).collect();
+        assert!(envelope[..100].iter().all(|&e| e == 0.0));
+        // Rises within a few attack times.
+        assert!(envelope[100..300].windows(2).all(|w| w[1] >= w[0]));
+        assert!(envelope[109] > 0.85);
+        assert!(envelope[299] > 0.99);
+        // Falls to 1/e of the peak one release time after the burst.
+        assert!(envelope[300..].windows(2).all(|w| w[1] < w[0]));
+        let decayed = envelope[349] / envelope[299];
+        assert!((decayed - (-1.0f32).exp()).abs() < 0.01, "{decayed}");
+    }
+}
@@ -0,0 +1,168 @@
+use std::time::Duration;
+
+use super::{AudioClock, SeekError};
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Internal function that builds an `ExternalClock` object.
+pub fn with_external_clock<I>(input: I, clock: AudioClock) -> ExternalClock<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    ExternalClock {
+        start: clock.now(),
+        input,
+        clock,
+        played: 0,
+        channel: 0,
+        waiting: false,
+    }
+}
+
+/// Plays a sound only as far as an [`AudioClock`] has been advanced, see
+/// [`Source::with_external_clock`].
+#[derive(Clone, Debug)]
+pub struct ExternalClock<I> {
+    input: I,
+    clock: AudioClock,
+    // Frame of the clock when the source was created.
+    start: u64,
+    // Frames of the input played so far.
+    played: u64,
+    // Channel of the next sample.
+    channel: usize,
+    // The current frame is silence because the clock had not advanced.
+    waiting: bool,
+}
+
+impl<I> ExternalClock<I> {
+    /// Returns the clock the source follows.
+    #[inline]
+    pub fn clock(&self) -> &AudioClock {
+        &self.clock
+    }
+
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+}
+
+impl<I> Iterator for ExternalClock<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        if self.channel == 0 {
+            self.waiting = self.start + self.played >= self.clock.now();
+        }
+        let sample = if self.waiting {
+            I::Item::zero_value()
+        } else {
+            self.input.next()?
+        };
+        self.channel += 1;
+        if self.channel >= self.input.channels() as usize {
+            self.channel = 0;
+            if !self.waiting {
+                self.played += 1;
+            }
+        }
+        Some(sample)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Waiting for the clock can take any number of samples.
+        (self.input.size_hint().0, None)
+    }
+}
+
+impl<I> Source for ExternalClock<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.channel = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::{FRAC_PI_4, TAU};
+
+    use crate::buffer::SamplesBuffer;
+    use crate::source::AudioClock;
+    use crate::Source;
+
+    #[test]
+    fn lfo_follows_clock() {
+        let clock = AudioClock::new();
+        let mut source = SamplesBuffer::new(1, 1000, vec![1.0f32; 1000])
+            .auto_pan(2.0, 1.0)
+            .with_external_clock(clock.clone());
+
+        // Nothing plays until the clock moves.
+        assert_eq!(source.by_ref().take(4).collect::<Vec<_>>(), [0.0; 4]);
+
+        for step in [1, 10, 100] {
+            clock.advance(step);
+            let frame = clock.now() - 1;
+            let played: Vec<f32> = source.by_ref().take(2 * step as usize).collect();
+            let pan = (TAU * 2.0 * frame as f32 / 1000.0).sin();
+            let angle = (pan + 1.0) * FRAC_PI_4;
+            let last = &played[played.len() - 2..];
+            assert!((last[0] - angle.cos()).abs() < 1e-4, "{last:?} at {frame}");
+            assert!((last[1] - angle.sin()).abs() < 1e-4, "{last:?} at {frame}");
+            // Then waits for the clock again.
+            assert_eq!(source.by_ref().take(2).collect::<Vec<_>>(), [0.0; 2]);
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::math;
+use crate::{Sample, Source};
+
+/// Internal function that builds a `FadeOnSeek` object.
+pub fn fade_on_seek<I>(input: I, fade: Duration) -> FadeOnSeek<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    let tail_format = (input.channels(), input.sample_rate());
+    FadeOnSeek {
+        input,
+        fade,
+        channel: 0,
+        tail: VecDeque::new(),
+        tail_format,
+        fade_out_len: 0,
+        fade_in_len: 0,
+        fade_in_left: 0,
+    }
+}
+
+/// Fades out before and fades in after every seek, see [`Source::fade_on_seek`].
+#[derive(Clone, Debug)]
+pub struct FadeOnSeek<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    input: I,
+    fade: Duration,
+    // Channel of the next input sample.
+    channel: usize,
+    // Samples read before the last seek that still have to be played.
+    tail: VecDeque<I::Item>,
+    tail_format: (ChannelCount, SampleRate),
+    // Number of samples at the end of `tail` that are faded out.
+    fade_out_len: usize,
+    fade_in_len: usize,
+    fade_in_left: usize,
+}
+
+impl<I> FadeOnSeek<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+
+    fn fade_len(&self) -> usize {
+        let frames = math::duration_to_frames(self.fade, self.input.sample_rate()) as usize;
+        frames * self.input.channels() as usize
+    }
+
+    fn next_input(&mut self) -> Option<I::Item> {
+        let sample = self.input.next()?;
+        self.channel = (self.channel + 1) % self.input.channels() as usize;
+        Some(sample)
+    }
+}
+
+impl<I> Iterator for FadeOnSeek<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        if let Some(sample) = self.tail.pop_front() {
+            if self.tail.len() >= self.fade_out_len {
+                return Some(sample);
+            }
+            let channels = self.tail_format.0 as usize;
+            let frames = self.fade_out_len.div_ceil(channels);
+            let frames_left = self.tail.len() / channels;
+            return Some(sample.amplify(frames_left as f32 / frames as f32));
+        }
+
+        let sample = self.next_input()?;
+        if self.fade_in_left == 0 {
+            return Some(sample);
+        }
+        let channels = self.input.channels() as usize;
+        let frame = (self.fade_in_len - self.fade_in_left) / channels;
+        self.fade_in_left -= 1;
+        let frames = self.fade_in_len / channels;
+        Some(sample.amplify(frame as f32 / frames as f32))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.input.size_hint();
+        (
+            lower.saturating_add(self.tail.len()),
+            upper.and_then(|upper| upper.checked_add(self.tail.len())),
+        )
+    }
+}
+
+impl<I> ExactSizeIterator for FadeOnSeek<I>
+where
+    I: Source + ExactSizeIterator,
+    I::Item: Sample,
+{
+}
+
+impl<I> Source for FadeOnSeek<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        if self.tail.is_empty() {
+            self.input.current_span_len()
+        } else {
+            Some(self.tail.len())
+        }
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        if self.tail.is_empty() {
+            self.input.channels()
+        } else {
+            self.tail_format.0
+        }
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        if self.tail.is_empty() {
+            self.input.sample_rate()
+        } else {
+            self.tail_format.1
+        }
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        // Reads ahead to fade out, unless a fade out is still playing.
+        if self.tail.is_empty() {
+            self.tail_format = (self.input.channels(), self.input.sample_rate());
+            let channels = self.tail_format.0 as usize;
+            // Finishes the current frame at full volume.
+            let lead = (channels - self.channel) % channels;
+            for _ in 0..lead + self.fade_len() {
+                match self.next_input() {
+                    Some(sample) => self.tail.push_back(sample),
+                    None => break,
+                }
+            }
+            self.fade_out_len = self.tail.len().saturating_sub(lead);
+        }
+
+        if let Err(err) = self.input.try_seek(pos) {
+            // Plays what was read ahead as if nothing happened.
+            self.fade_out_len = 0;
+            return Err(err);
+        }
+        self.channel = 0;
+        self.fade_in_len = self.fade_len();
+        self.fade_in_left = self.fade_in_len;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::buffer::SamplesBuffer;
+    use crate::Source;
+
+    #[test]
+    fn dips_around_seek() {
+        let mut source =
+            SamplesBuffer::new(1, 1000, vec![1.0f32; 1000]).fade_on_seek(Duration::from_millis(10));
+        assert!(source.by_ref().take(100).all(|s| s == 1.0));
+
+        source.try_seek(Duration::from_millis(500)).unwrap();
+        let samples: Vec<f32> = source.collect();
+        // 10 ms read ahead before the jump and the 500 ms after it.
+        assert_eq!(samples.len(), 510);
+
+        assert!(samples[..10].windows(2).all(|w| w[1] < w[0]));
+        assert!(samples[0] < 1.0);
+        assert_eq!(samples[9], 0.0);
+        assert_eq!(samples[10], 0.0);
+        assert!(samples[10..20].windows(2).all(|w| w[1] > w[0]));
+        assert!(samples[20..].iter().all(|&s| s == 1.0));
+    }
+}
@@ -0,0 +1,161 @@
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::math;
+use crate::{Sample, Source};
+
+/// Internal function that builds a `FadeTail` object.
+pub fn fade_tail<I>(input: I, duration: Duration) -> FadeTail<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    FadeTail {
+        input,
+        duration,
+        gain: 1.0,
+        channel: 0,
+    }
+}
+
+/// Fades out the end of a sound as it approaches it, see [`Source::fade_tail`].
+#[derive(Clone, Debug)]
+pub struct FadeTail<I> {
+    input: I,
+    duration: Duration,
+    // Gain of the current frame.
+    gain: f32,
+    // Channel of the next sample.
+    channel: usize,
+}
+
+impl<I> FadeTail<I> {
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+}
+
+impl<I> FadeTail<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    fn start_frame(&mut self) {
+        self.gain = match self.input.size_hint() {
+            (lower, Some(upper)) if lower == upper => {
+                let frames_left = lower.div_ceil(self.input.channels() as usize) as u64;
+                let fade_frames =
+                    math::duration_to_frames(self.duration, self.input.sample_rate()).max(1);
+                // The last frame is silent.
+                (frames_left.saturating_sub(1) as f32 / fade_frames as f32).min(1.0)
+            }
+            _ => 1.0,
+        };
+    }
+}
+
+impl<I> Iterator for FadeTail<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        if self.channel == 0 {
+            self.start_frame();
+        }
+        let sample = self.input.next()?;
+        self.channel = (self.channel + 1) % self.input.channels() as usize;
+        Some(sample.amplify(self.gain))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for FadeTail<I>
+where
+    I: Source + ExactSizeIterator,
+    I::Item: Sample,
+{
+}
+
+impl<I> Source for FadeTail<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.channel = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::buffer::SamplesBuffer;
+    use crate::Source;
+
+    #[test]
+    fn ramps_to_zero_at_end() {
+        let source =
+            SamplesBuffer::new(2, 1000, vec![1.0f32; 2000]).fade_tail(Duration::from_millis(10));
+        let samples: Vec<f32> = source.collect();
+        assert_eq!(samples.len(), 2000);
+
+        assert!(samples[..1980].iter().all(|&s| s == 1.0));
+        let tail: Vec<f32> = samples[1980..].chunks(2).map(|frame| frame[0]).collect();
+        assert!(tail.windows(2).all(|w| w[1] < w[0]));
+        assert!(samples[1980..].chunks(2).all(|frame| frame[0] == frame[1]));
+        assert_eq!(tail[0], 0.9);
+        assert_eq!(tail[9], 0.0);
+    }
+}
@@ -95,6 +95,11 @@ where
         self.inner().total_duration()
     }
 
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.inner().latency()
+    }
+
     #[inline]
     fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
         self.inner_mut().try_seek(pos)
@@ -0,0 +1,140 @@
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Internal function that builds a `Finite` object.
+pub fn finite<I>(input: I) -> Result<Finite<I>, InfiniteSourceError>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    match input.total_duration() {
+        Some(_) => Ok(Finite { input }),
+        None => Err(InfiniteSourceError),
+    }
+}
+
+/// Error returned by [`Source::finite`] for a source without a known end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InfiniteSourceError;
+
+impl fmt::Display for InfiniteSourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the source does not report a total duration")
+    }
+}
+
+impl Error for InfiniteSourceError {}
+
+/// A source that is known to end, see [`Source::finite`].
+#[derive(Clone, Debug)]
+pub struct Finite<I> {
+    input: I,
+}
+
+impl<I> Finite<I> {
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+}
+
+impl<I> Iterator for Finite<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        self.input.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for Finite<I>
+where
+    I: Source + ExactSizeIterator,
+    I::Item: Sample,
+{
+}
+
+impl<I> Source for Finite<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::InfiniteSourceError;
+    use crate::source::{SineWave, Source};
+
+    #[test]
+    fn rejects_infinite_sources() {
+        assert_eq!(
+            SineWave::new(440.0).finite().unwrap_err(),
+            InfiniteSourceError
+        );
+
+        let finite = SineWave::new(440.0)
+            .take_duration(Duration::from_millis(10))
+            .finite()
+            .unwrap();
+        assert_eq!(finite.count(), 480);
+    }
+}
@@ -138,6 +138,13 @@ where
         None
     }
 
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.current_source
+            .as_ref()
+            .map_or(Duration::ZERO, |source| source.latency())
+    }
+
     #[inline]
     fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
         if let Some(source) = self.current_source.as_mut() {
@@ -148,10 +155,135 @@ where
     }
 }
 
+/// Builds a source from an iterator of samples that can fail, such as the output of a
+/// decoder.
+///
+/// The source ends at the first error instead of panicking, use
+/// [`FromTryIter::error`] to find out whether it ended because of one.
+///
+/// # Panics
+///
+/// Panics if `channels` or `sample_rate` is zero.
+pub fn from_try_iter<I, S, E>(
+    iterator: I,
+    channels: ChannelCount,
+    sample_rate: SampleRate,
+) -> FromTryIter<I::IntoIter, E>
+where
+    I: IntoIterator<Item = Result<S, E>>,
+    S: Sample,
+{
+    assert!(channels > 0, "a source needs at least one channel");
+    assert!(sample_rate > 0, "the sample rate must be positive");
+    FromTryIter {
+        iterator: iterator.into_iter(),
+        channels,
+        sample_rate,
+        failed: false,
+        error: None,
+    }
+}
+
+/// A source that plays samples from a fallible iterator, see [`from_try_iter`].
+#[derive(Clone, Debug)]
+pub struct FromTryIter<I, E> {
+    iterator: I,
+    channels: ChannelCount,
+    sample_rate: SampleRate,
+    failed: bool,
+    // The error that ended the source until it is taken.
+    error: Option<E>,
+}
+
+impl<I, E> FromTryIter<I, E> {
+    /// The error that ended the source, if any.
+    #[inline]
+    pub fn error(&self) -> Option<&E> {
+        self.error.as_ref()
+    }
+
+    /// Takes the error that ended the source, the source stays ended.
+    #[inline]
+    pub fn take_error(&mut self) -> Option<E> {
+        self.error.take()
+    }
+
+    /// Returns the inner iterator.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.iterator
+    }
+}
+
+impl<I, S, E> Iterator for FromTryIter<I, E>
+where
+    I: Iterator<Item = Result<S, E>>,
+    S: Sample,
+{
+    type Item = S;
+
+    #[inline]
+    fn next(&mut self) -> Option<S> {
+        if self.failed {
+            return None;
+        }
+        match self.iterator.next()? {
+            Ok(sample) => Some(sample),
+            Err(error) => {
+                self.failed = true;
+                self.error = Some(error);
+                None
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.failed {
+            (0, Some(0))
+        } else {
+            (0, self.iterator.size_hint().1)
+        }
+    }
+}
+
+impl<I, S, E> Source for FromTryIter<I, E>
+where
+    I: Iterator<Item = Result<S, E>>,
+    S: Sample,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.channels
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.sample_rate
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+
+    #[inline]
+    fn try_seek(&mut self, _: Duration) -> Result<(), SeekError> {
+        Err(SeekError::NotSupported {
+            underlying_source: std::any::type_name::<Self>(),
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::buffer::SamplesBuffer;
-    use crate::source::{from_iter, Source};
+    use crate::source::{from_iter, from_try_iter, Source};
 
     #[test]
     fn basic() {
@@ -180,4 +312,16 @@ mod tests {
         assert_eq!(rx.next(), Some(5));
         assert_eq!(rx.next(), None);
     }
+
+    #[test]
+    fn ends_on_error() {
+        let samples = (0..20).map(|n| if n < 10 { Ok(n as f32) } else { Err(n) });
+        let mut source = from_try_iter(samples, 1, 48000);
+
+        let played: Vec<f32> = source.by_ref().collect();
+        assert_eq!(played, (0..10).map(|n| n as f32).collect::<Vec<_>>());
+        assert_eq!(source.error(), Some(&10));
+        assert_eq!(source.take_error(), Some(10));
+        assert_eq!(source.next(), None);
+    }
 }
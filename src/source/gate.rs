@@ -0,0 +1,374 @@
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::math;
+use crate::{Sample, Source};
+
+// Time for the envelope of a channel to fall 63% of the way after a peak.
+const ENVELOPE_RELEASE: f32 = 0.01;
+
+/// Internal function that builds a `NoiseGate` object.
+pub fn noise_gate<I>(input: I, settings: GateSettings) -> NoiseGate<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    NoiseGate {
+        input,
+        settings,
+        timing: None,
+        envelopes: Vec::new(),
+        open: true,
+        below_frames: 0,
+        gain: 1.0,
+        frame: Vec::new(),
+        frame_pos: 0,
+    }
+}
+
+/// Settings of a [`NoiseGate`], see [`Source::noise_gate`].
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use rodio::source::{GateSettings, SineWave, Source};
+///
+/// let settings = GateSettings::new()
+///     .with_threshold(-45.0)
+///     .with_hold(Duration::from_millis(100));
+/// let source = SineWave::new(440.0).noise_gate(settings);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GateSettings {
+    threshold_db: f32,
+    hysteresis_db: f32,
+    attack: Duration,
+    release: Duration,
+    hold: Duration,
+}
+
+impl Default for GateSettings {
+    fn default() -> Self {
+        GateSettings {
+            threshold_db: -50.0,
+            hysteresis_db: 6.0,
+            attack: Duration::from_millis(1),
+            release: Duration::from_millis(50),
+            hold: Duration::from_millis(50),
+        }
+    }
+}
+
+impl GateSettings {
+    /// Closes below -50 dBFS and opens 6 dB above that, with an attack of 1 ms and a
+    /// hold and release of 50 ms.
+    pub fn new() -> GateSettings {
+        GateSettings::default()
+    }
+
+    /// Sets the level in dBFS below which the gate closes.
+    pub fn with_threshold(mut self, threshold_db: f32) -> GateSettings {
+        self.threshold_db = threshold_db;
+        self
+    }
+
+    /// Sets how many dB above the threshold the sound has to rise to open the gate
+    /// again. This keeps a sound hovering around the threshold from opening and
+    /// closing the gate over and over.
+    pub fn with_hysteresis(mut self, hysteresis_db: f32) -> GateSettings {
+        self.hysteresis_db = hysteresis_db.max(0.0);
+        self
+    }
+
+    /// Sets the time the gain takes to rise from silence to full once the gate opens.
+    pub fn with_attack(mut self, attack: Duration) -> GateSettings {
+        self.attack = attack;
+        self
+    }
+
+    /// Sets the time the gain takes to fall from full to silence once the gate closes.
+    pub fn with_release(mut self, release: Duration) -> GateSettings {
+        self.release = release;
+        self
+    }
+
+    /// Sets how long the sound has to stay below the threshold before the gate closes,
+    /// so short pauses between words do not cut in.
+    pub fn with_hold(mut self, hold: Duration) -> GateSettings {
+        self.hold = hold;
+        self
+    }
+}
+
+/// Silences a sound while it is quieter than a threshold, see
+/// [`Source::noise_gate`].
+#[derive(Clone, Debug)]
+pub struct NoiseGate<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    input: I,
+    settings: GateSettings,
+    // Envelope decay per frame, gain steps per frame while opening and closing and
+    // frames of hold, for the sample rate they were computed for.
+    timing: Option<(Timing, SampleRate)>,
+    // Envelope of every channel.
+    envelopes: Vec<f32>,
+    open: bool,
+    // Frames the sound has been below the threshold while open.
+    below_frames: u64,
+    gain: f32,
+    // The frame being played, already gated.
+    frame: Vec<I::Item>,
+    frame_pos: usize,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Timing {
+    decay: f32,
+    attack_step: f32,
+    release_step: f32,
+    hold_frames: u64,
+}
+
+impl<I> NoiseGate<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    /// Returns `true` while the gate lets the sound through or is opening.
+    #[inline]
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+
+    fn timing(&mut self) -> Timing {
+        let sample_rate = self.input.sample_rate();
+        match self.timing {
+            Some((timing, rate)) if rate == sample_rate => timing,
+            _ => {
+                let frames = |time: Duration| time.as_secs_f32() * sample_rate as f32;
+                let timing = Timing {
+                    decay: (-1.0 / (ENVELOPE_RELEASE * sample_rate as f32)).exp(),
+                    attack_step: 1.0 / frames(self.settings.attack).max(1.0),
+                    release_step: 1.0 / frames(self.settings.release).max(1.0),
+                    hold_frames: math::duration_to_frames(self.settings.hold, sample_rate),
+                };
+                self.timing = Some((timing, sample_rate));
+                timing
+            }
+        }
+    }
+
+    // Reads and gates the next frame. Returns `false` if the input has ended.
+    fn next_frame(&mut self) -> bool {
+        self.frame.clear();
+        self.frame_pos = 0;
+        for _ in 0..self.input.channels() {
+            let Some(sample) = self.input.next() else {
+                break;
+            };
+            self.frame.push(sample);
+        }
+        if self.frame.is_empty() {
+            return false;
+        }
+
+        let timing = self.timing();
+        self.envelopes.resize(self.frame.len(), 0.0);
+        for (envelope, sample) in self.envelopes.iter_mut().zip(&self.frame) {
+            *envelope = sample.to_f32().abs().max(*envelope * timing.decay);
+        }
+        // The loudest channel decides for all of them, so the stereo image stays intact.
+        let level = self.envelopes.iter().fold(0.0f32, |max, &e| max.max(e));
+        let level_db = 20.0 * level.log10();
+
+        if self.open {
+            if level_db >= self.settings.threshold_db {
+                self.below_frames = 0;
+            } else if self.below_frames >= timing.hold_frames {
+                self.open = false;
+            } else {
+                self.below_frames += 1;
+            }
+        } else if level_db >= self.settings.threshold_db + self.settings.hysteresis_db {
+            self.open = true;
+            self.below_frames = 0;
+        }
+
+        self.gain = if self.open {
+            (self.gain + timing.attack_step).min(1.0)
+        } else {
+            (self.gain - timing.release_step).max(0.0)
+        };
+        if self.gain < 1.0 {
+            for sample in &mut self.frame {
+                *sample = sample.amplify(self.gain);
+            }
+        }
+        true
+    }
+
+    fn buffered(&self) -> usize {
+        self.frame.len() - self.frame_pos
+    }
+}
+
+impl<I> Iterator for NoiseGate<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        if self.frame_pos >= self.frame.len() && !self.next_frame() {
+            return None;
+        }
+        let sample = self.frame[self.frame_pos];
+        self.frame_pos += 1;
+        Some(sample)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.input.size_hint();
+        (
+            lower.saturating_add(self.buffered()),
+            upper.and_then(|upper| upper.checked_add(self.buffered())),
+        )
+    }
+}
+
+impl<I> ExactSizeIterator for NoiseGate<I>
+where
+    I: Source + ExactSizeIterator,
+    I::Item: Sample,
+{
+}
+
+impl<I> Source for NoiseGate<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        self.input
+            .current_span_len()
+            .map(|len| len.saturating_add(self.buffered()))
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.frame.clear();
+        self.frame_pos = 0;
+        self.envelopes.clear();
+        self.open = true;
+        self.below_frames = 0;
+        self.gain = 1.0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+    use std::time::Duration;
+
+    use crate::buffer::SamplesBuffer;
+    use crate::source::GateSettings;
+    use crate::Source;
+
+    // 100 ms of a tone on each channel for every pair of amplitudes.
+    fn sections(amplitudes: &[(f32, f32)]) -> SamplesBuffer<f32> {
+        let samples = amplitudes
+            .iter()
+            .flat_map(|&(left, right)| {
+                (0..4800).flat_map(move |n| {
+                    let tone = (2.0 * PI * 500.0 * n as f32 / 48000.0).sin();
+                    [left * tone, right * tone]
+                })
+            })
+            .collect::<Vec<_>>();
+        SamplesBuffer::new(2, 48000, samples)
+    }
+
+    fn settings() -> GateSettings {
+        GateSettings::new()
+            .with_threshold(-40.0)
+            .with_hysteresis(6.0)
+            .with_hold(Duration::from_millis(20))
+            .with_release(Duration::from_millis(20))
+            .with_attack(Duration::from_millis(1))
+    }
+
+    #[test]
+    fn gates_quiet_parts_and_keeps_channels_together() {
+        // The right channel alone is below the threshold, then both are.
+        let input = sections(&[(0.5, 0.005), (0.5, 0.005), (0.001, 0.001), (0.001, 0.001)]);
+        let output: Vec<f32> = input.clone().noise_gate(settings()).collect();
+        let input: Vec<f32> = input.collect();
+
+        assert_eq!(output[..19200], input[..19200]);
+        // Closed once the envelope fell, the hold passed and the gain ramped down.
+        assert!(output[28800..].iter().all(|&s| s == 0.0));
+    }
+
+    #[test]
+    fn hysteresis_keeps_gate_closed_near_threshold() {
+        // Closes on near silence. Just above the threshold stays closed, 14 dB above
+        // it opens.
+        let input = sections(&[(0.001, 0.001), (0.015, 0.015), (0.05, 0.05)]);
+        let mut gate = input.clone().noise_gate(settings());
+        let output: Vec<f32> = gate.by_ref().collect();
+        let input: Vec<f32> = input.collect();
+
+        assert!(output[9600..19200].iter().all(|&s| s == 0.0));
+        assert_eq!(output[20000..], input[20000..]);
+        assert!(gate.is_open());
+    }
+}
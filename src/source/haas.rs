@@ -0,0 +1,217 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Internal function that builds a `HaasWiden` object.
+pub fn haas_widen<I>(input: I, delay_ms: f32) -> HaasWiden<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    let mut haas = HaasWiden {
+        input,
+        delay_ms: delay_ms.max(0.0),
+        line: VecDeque::new(),
+        line_rate: 0,
+        channel: 0,
+        right: None,
+    };
+    haas.fill_line();
+    haas
+}
+
+/// Widens a sound by delaying its right channel, see [`Source::haas_widen`].
+#[derive(Clone, Debug)]
+pub struct HaasWiden<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    input: I,
+    delay_ms: f32,
+    // Right samples waiting to be played, oldest first. Always holds exactly the
+    // delay.
+    line: VecDeque<I::Item>,
+    // Sample rate the delay line was sized for.
+    line_rate: SampleRate,
+    // Channel of the next input sample.
+    channel: usize,
+    // Right sample of a mono input that still has to be played.
+    right: Option<I::Item>,
+}
+
+impl<I> HaasWiden<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    /// Returns the number of frames the right channel is delayed by.
+    #[inline]
+    pub fn delay_frames(&self) -> usize {
+        self.line.len()
+    }
+
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+
+    // Restarts the delay line with silence, sized for the current sample rate.
+    fn fill_line(&mut self) {
+        self.line_rate = self.input.sample_rate();
+        let frames = (self.delay_ms * self.line_rate as f32 / 1000.0).round() as usize;
+        self.line.clear();
+        self.line.resize(frames, I::Item::zero_value());
+    }
+
+    fn delay_right(&mut self, sample: I::Item) -> I::Item {
+        self.line.push_back(sample);
+        self.line.pop_front().expect("just pushed")
+    }
+}
+
+impl<I> Iterator for HaasWiden<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        if let Some(right) = self.right.take() {
+            return Some(right);
+        }
+
+        let sample = self.input.next()?;
+        if self.channel == 0 && self.input.sample_rate() != self.line_rate {
+            self.fill_line();
+        }
+        let channels = self.input.channels() as usize;
+        if channels == 1 {
+            self.right = Some(self.delay_right(sample));
+            return Some(sample);
+        }
+
+        let sample = if self.channel == 1 {
+            self.delay_right(sample)
+        } else {
+            sample
+        };
+        self.channel = (self.channel + 1) % channels;
+        Some(sample)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.input.size_hint();
+        let extra = self.right.is_some() as usize;
+        if self.input.channels() == 1 {
+            (
+                lower.saturating_mul(2).saturating_add(extra),
+                upper.and_then(|upper| upper.checked_mul(2)?.checked_add(extra)),
+            )
+        } else {
+            (lower, upper)
+        }
+    }
+}
+
+impl<I> ExactSizeIterator for HaasWiden<I>
+where
+    I: Source + ExactSizeIterator,
+    I::Item: Sample,
+{
+}
+
+impl<I> Source for HaasWiden<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        let extra = self.right.is_some() as usize;
+        match self.input.current_span_len() {
+            Some(len) if self.input.channels() == 1 => {
+                Some(len.saturating_mul(2).saturating_add(extra))
+            }
+            len => len,
+        }
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels().max(2)
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.fill_line();
+        self.channel = 0;
+        self.right = None;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::buffer::SamplesBuffer;
+    use crate::Source;
+
+    #[test]
+    fn delays_right_channel() {
+        let samples: Vec<f32> = (1..=100).flat_map(|i| [i as f32, -i as f32]).collect();
+        let widened = SamplesBuffer::new(2, 1000, samples).haas_widen(15.0);
+        assert_eq!(widened.delay_frames(), 15);
+
+        let output: Vec<f32> = widened.collect();
+        assert_eq!(output.len(), 200);
+        for (i, frame) in output.chunks(2).enumerate() {
+            let expected_right = if i < 15 { 0.0 } else { -((i - 15 + 1) as f32) };
+            assert_eq!(frame, [(i + 1) as f32, expected_right]);
+        }
+
+        // A mono sound becomes stereo.
+        let widened = SamplesBuffer::new(1, 1000, vec![1.0f32; 10]).haas_widen(5.0);
+        assert_eq!(widened.channels(), 2);
+        let output: Vec<f32> = widened.collect();
+        assert_eq!(
+            output[..10],
+            [1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0]
+        );
+        assert_eq!(output[10..], [1.0; 10]);
+    }
+}
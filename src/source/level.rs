@@ -0,0 +1,201 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Internal function that builds a `Level` object.
+pub fn level<I>(input: I, target_peak: f32, window: Duration) -> Level<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    let window_len = (window.as_secs_f32() * input.sample_rate() as f32 * input.channels() as f32)
+        .round()
+        .max(1.0);
+
+    Level {
+        input,
+        target_peak,
+        window_len: window_len as u64,
+        rise_coeff: (-1.0 / window_len).exp(),
+        gain: 1.0,
+        sample_idx: 0,
+        peaks: VecDeque::new(),
+    }
+}
+
+/// Filter that slowly levels a sound towards a target peak amplitude.
+///
+/// The peak is tracked over a sliding window. The gain is lowered the moment the
+/// peak grows and raised gradually when it shrinks, so the output never goes above
+/// the target.
+#[derive(Clone, Debug)]
+pub struct Level<I> {
+    input: I,
+    target_peak: f32,
+    // Number of samples the peak is tracked over.
+    window_len: u64,
+    // Smoothing applied when raising the gain.
+    rise_coeff: f32,
+    gain: f32,
+    sample_idx: u64,
+    // Candidates for the window peak as (sample index, absolute value), strictly
+    // decreasing in value. The front is the peak of the current window.
+    peaks: VecDeque<(u64, f32)>,
+}
+
+impl<I> Level<I> {
+    /// Modifies the target peak amplitude.
+    #[inline]
+    pub fn set_target_peak(&mut self, target_peak: f32) {
+        self.target_peak = target_peak;
+    }
+
+    /// Returns the gain currently applied.
+    #[inline]
+    pub fn gain(&self) -> f32 {
+        self.gain
+    }
+
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+
+    fn update_peak(&mut self, value: f32) -> f32 {
+        while self.peaks.back().is_some_and(|&(_, peak)| peak <= value) {
+            self.peaks.pop_back();
+        }
+        self.peaks.push_back((self.sample_idx, value));
+        while self
+            .peaks
+            .front()
+            .is_some_and(|&(idx, _)| idx + self.window_len <= self.sample_idx)
+        {
+            self.peaks.pop_front();
+        }
+        self.sample_idx += 1;
+
+        self.peaks.front().map_or(value, |&(_, peak)| peak)
+    }
+}
+
+impl<I> Iterator for Level<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        let sample = self.input.next()?;
+        let peak = self.update_peak(sample.to_f32().abs());
+
+        // Keep the gain as is during silence instead of boosting it without bound.
+        if peak > f32::EPSILON {
+            let desired = self.target_peak / peak;
+            self.gain = if desired < self.gain {
+                desired
+            } else {
+                desired + (self.gain - desired) * self.rise_coeff
+            };
+        }
+
+        Some(sample.amplify(self.gain))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for Level<I>
+where
+    I: Source + ExactSizeIterator,
+    I::Item: Sample,
+{
+}
+
+impl<I> Source for Level<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::level;
+    use crate::buffer::SamplesBuffer;
+
+    #[test]
+    fn levels_quiet_then_loud_without_overshoot() {
+        let rate = 1000;
+        let tone = |amplitude: f32| (0..rate).map(move |i| amplitude * (i as f32 * 0.3).sin());
+        let data: Vec<f32> = tone(0.1).chain(tone(0.8)).collect();
+        let leveled: Vec<f32> = level(
+            SamplesBuffer::new(1, rate, data),
+            0.5,
+            Duration::from_millis(100),
+        )
+        .collect();
+
+        assert!(leveled.iter().all(|s| s.abs() <= 0.5 + 1e-6));
+
+        let peak = |samples: &[f32]| samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        let rate = rate as usize;
+        // Both the quiet and the loud part end up close to the target.
+        assert!(peak(&leveled[rate - 100..rate]) > 0.45);
+        assert!(peak(&leveled[2 * rate - 100..]) > 0.45);
+        // The quiet part is raised gradually, not in one step.
+        assert!(peak(&leveled[..20]) < 0.3);
+    }
+}
@@ -64,6 +64,17 @@ where
     pub fn into_inner(self) -> I {
         self.input
     }
+
+    /// Starts a new ramp from `start_gain` to `end_gain` at the current position. The
+    /// end gain is kept once the ramp is done.
+    pub(crate) fn restart(&mut self, duration: Duration, start_gain: f32, end_gain: f32) {
+        self.elapsed_ns = 0.0;
+        self.total_ns = (duration.as_nanos() as f32).max(1.0);
+        self.start_gain = start_gain;
+        self.end_gain = end_gain;
+        self.clamp_end = true;
+        self.sample_idx = 0;
+    }
 }
 
 impl<I> Iterator for LinearGainRamp<I>
@@ -136,6 +147,11 @@ where
         self.input.total_duration()
     }
 
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
     #[inline]
     fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
         self.elapsed_ns = pos.as_nanos() as f32;
@@ -0,0 +1,219 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use dasp_sample::FromSample;
+
+use super::repeat::{self, RepeatCrossfade};
+use super::uniform::UniformSourceIterator;
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::math;
+use crate::{Sample, Source};
+
+/// Internal function that builds a `LoopWithOutro` object.
+pub fn loop_with_outro<I, O>(
+    loop_region: I,
+    outro: O,
+    crossfade: Duration,
+) -> (LoopWithOutro<I::Item>, OutroHandle)
+where
+    I: Source,
+    I::Item: FromSample<O::Item> + Sample,
+    O: Source,
+    O::Item: Sample,
+{
+    let channels = loop_region.channels();
+    let sample_rate = loop_region.sample_rate();
+    let looped = repeat::repeat_crossfade(loop_region, crossfade);
+    let mut outro: Vec<I::Item> =
+        UniformSourceIterator::<O, I::Item>::new(outro, channels, sample_rate).collect();
+    let outro_frames = outro.len() / channels as usize;
+    outro.truncate(outro_frames * channels as usize);
+    let fade_frames = (math::duration_to_frames(crossfade, sample_rate) as usize).min(outro_frames);
+
+    let handle = OutroHandle {
+        triggered: Arc::new(AtomicBool::new(false)),
+    };
+    let source = LoopWithOutro {
+        looped,
+        outro,
+        fade_len: fade_frames * channels as usize,
+        outro_pos: None,
+        handle: handle.clone(),
+        channel: 0,
+    };
+    (source, handle)
+}
+
+/// Ends the loop of a [`LoopWithOutro`] source from anywhere, for example from the
+/// game logic.
+#[derive(Clone, Debug)]
+pub struct OutroHandle {
+    triggered: Arc<AtomicBool>,
+}
+
+impl OutroHandle {
+    /// Leaves the loop and plays the outro, beginning with the next frame.
+    #[inline]
+    pub fn trigger_outro(&self) {
+        self.triggered.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if the outro was triggered.
+    #[inline]
+    pub fn is_outro_triggered(&self) -> bool {
+        self.triggered.load(Ordering::Relaxed)
+    }
+}
+
+/// Loops a region until told to play the outro, see
+/// [`Source::with_crossfade_loop_region`].
+#[derive(Clone, Debug)]
+pub struct LoopWithOutro<S> {
+    looped: RepeatCrossfade<S>,
+    outro: Vec<S>,
+    // Number of samples at the start of the outro that are blended with the loop.
+    fade_len: usize,
+    // Position in `outro` of the next sample, once the outro was triggered.
+    outro_pos: Option<usize>,
+    handle: OutroHandle,
+    // Channel of the next sample.
+    channel: usize,
+}
+
+impl<S> LoopWithOutro<S> {
+    /// Returns a handle that triggers the outro.
+    #[inline]
+    pub fn handle(&self) -> OutroHandle {
+        self.handle.clone()
+    }
+
+    /// Returns `true` once the outro is playing.
+    #[inline]
+    pub fn is_playing_outro(&self) -> bool {
+        self.outro_pos.is_some()
+    }
+}
+
+impl<S> Iterator for LoopWithOutro<S>
+where
+    S: Sample,
+{
+    type Item = S;
+
+    #[inline]
+    fn next(&mut self) -> Option<S> {
+        if self.channel == 0 && self.outro_pos.is_none() && self.handle.is_outro_triggered() {
+            self.outro_pos = Some(0);
+        }
+        self.channel = (self.channel + 1) % self.looped.channels() as usize;
+
+        let Some(pos) = self.outro_pos else {
+            return self.looped.next();
+        };
+        let sample = *self.outro.get(pos)?;
+        self.outro_pos = Some(pos + 1);
+        if pos >= self.fade_len {
+            return Some(sample);
+        }
+        // Blends the loop into the beginning of the outro.
+        let channels = self.looped.channels() as usize;
+        let gain = (pos / channels) as f32 / (self.fade_len / channels) as f32;
+        let looped = self.looped.next().unwrap_or(S::zero_value());
+        Some(
+            looped
+                .amplify(1.0 - gain)
+                .saturating_add(sample.amplify(gain)),
+        )
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.outro_pos {
+            Some(pos) => {
+                let left = self.outro.len().saturating_sub(pos);
+                (left, Some(left))
+            }
+            None => (self.looped.size_hint().0.min(self.outro.len()), None),
+        }
+    }
+}
+
+impl<S> Source for LoopWithOutro<S>
+where
+    S: Sample,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.looped.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.looped.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.looped.latency()
+    }
+
+    // Seeks within the loop, going back to it if the outro was playing.
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        if self.outro_pos.is_some() {
+            self.handle.triggered.store(false, Ordering::Relaxed);
+            self.outro_pos = None;
+        }
+        self.channel = 0;
+        self.looped.try_seek(pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::buffer::SamplesBuffer;
+    use crate::Source;
+
+    #[test]
+    fn loops_until_outro_is_triggered() {
+        let ramp: Vec<f32> = (0..50).map(|i| i as f32 / 50.0).collect();
+        let loop_region = SamplesBuffer::new(1, 1000, ramp);
+        let outro = SamplesBuffer::new(1, 1000, vec![-1.0f32; 30]);
+        let (mut source, handle) =
+            loop_region.with_crossfade_loop_region(outro, Duration::from_millis(10));
+
+        // Every loop after the first skips the 10 samples faded in at its end.
+        let looped: Vec<f32> = source.by_ref().take(1000).collect();
+        for k in 10..960 {
+            assert_eq!(looped[k], looped[k + 40]);
+        }
+        assert!(!source.is_playing_outro());
+
+        handle.trigger_outro();
+        let outro: Vec<f32> = source.by_ref().collect();
+        assert!(source.is_playing_outro());
+        assert_eq!(outro.len(), 30);
+        // Fades from the loop, which carries on meanwhile, into the outro.
+        for k in 0..10 {
+            let gain = k as f32 / 10.0;
+            let expected = looped[960 + k] * (1.0 - gain) - gain;
+            assert!((outro[k] - expected).abs() < 1e-6);
+        }
+        assert!(outro[10..].iter().all(|&s| s == -1.0));
+        assert_eq!(source.next(), None);
+    }
+}
@@ -0,0 +1,338 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+// Time over which the loudness is averaged, about the momentary loudness of EBU R128.
+const WINDOW: Duration = Duration::from_millis(400);
+// The corrective gain stays within +-24 dB.
+const MAX_GAIN: f32 = 15.85;
+// Below this mean square, about -80 dBFS, the output is silent and left alone.
+const SILENCE: f32 = 1e-8;
+
+/// Internal function that builds a `LoudnessProbe` object.
+pub fn measure_loudness<I>(input: I) -> (LoudnessProbe<I>, LoudnessReference)
+where
+    I: Source,
+    I::Item: Sample,
+{
+    let reference = LoudnessReference {
+        mean_square: Arc::new(AtomicU32::new(0.0f32.to_bits())),
+    };
+    let probe = LoudnessProbe {
+        input,
+        meter: MeanSquare::default(),
+        reference: reference.clone(),
+    };
+    (probe, reference)
+}
+
+/// Internal function that builds a `LoudnessCompensated` object.
+pub fn with_loudness_compensation<I>(
+    input: I,
+    reference: LoudnessReference,
+) -> LoudnessCompensated<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    LoudnessCompensated {
+        input,
+        meter: MeanSquare::default(),
+        reference,
+    }
+}
+
+// Mean square of the samples over about `WINDOW`.
+#[derive(Clone, Debug, Default)]
+struct MeanSquare {
+    value: f32,
+    // Smoothing per sample and the format it was computed for.
+    coeff: Option<(f32, ChannelCount, SampleRate)>,
+}
+
+impl MeanSquare {
+    fn update(&mut self, sample: f32, channels: ChannelCount, sample_rate: SampleRate) -> f32 {
+        let coeff = match self.coeff {
+            Some((coeff, c, r)) if c == channels && r == sample_rate => coeff,
+            _ => {
+                let samples = WINDOW.as_secs_f32() * sample_rate as f32 * channels as f32;
+                let coeff = 1.0 - (-1.0 / samples.max(1.0)).exp();
+                self.coeff = Some((coeff, channels, sample_rate));
+                coeff
+            }
+        };
+        self.value += coeff * (sample * sample - self.value);
+        self.value
+    }
+}
+
+/// Carries the loudness measured by a [`LoudnessProbe`] to a
+/// [`LoudnessCompensated`] source, see [`Source::measure_loudness`].
+#[derive(Clone, Debug)]
+pub struct LoudnessReference {
+    // Bits of the `f32` mean square.
+    mean_square: Arc<AtomicU32>,
+}
+
+impl LoudnessReference {
+    /// Returns the RMS level measured last, between `0.0` for silence and `1.0` for
+    /// a full scale square wave.
+    #[inline]
+    pub fn rms(&self) -> f32 {
+        self.mean_square().sqrt()
+    }
+
+    fn mean_square(&self) -> f32 {
+        f32::from_bits(self.mean_square.load(Ordering::Relaxed))
+    }
+}
+
+/// Passes the sound through unchanged while measuring its loudness, see
+/// [`Source::measure_loudness`].
+#[derive(Clone, Debug)]
+pub struct LoudnessProbe<I> {
+    input: I,
+    meter: MeanSquare,
+    reference: LoudnessReference,
+}
+
+impl<I> LoudnessProbe<I> {
+    /// Returns the reference that carries the measured loudness.
+    #[inline]
+    pub fn reference(&self) -> LoudnessReference {
+        self.reference.clone()
+    }
+
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+}
+
+impl<I> Iterator for LoudnessProbe<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        let sample = self.input.next()?;
+        let mean_square = self.meter.update(
+            sample.to_f32(),
+            self.input.channels(),
+            self.input.sample_rate(),
+        );
+        self.reference
+            .mean_square
+            .store(mean_square.to_bits(), Ordering::Relaxed);
+        Some(sample)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for LoudnessProbe<I>
+where
+    I: Source + ExactSizeIterator,
+    I::Item: Sample,
+{
+}
+
+impl<I> Source for LoudnessProbe<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)
+    }
+}
+
+/// Brings the loudness of a sound back to the one measured by a
+/// [`LoudnessProbe`], see [`Source::with_loudness_compensation`].
+#[derive(Clone, Debug)]
+pub struct LoudnessCompensated<I> {
+    input: I,
+    meter: MeanSquare,
+    reference: LoudnessReference,
+}
+
+impl<I> LoudnessCompensated<I> {
+    /// Returns the gain applied to the sound right now.
+    #[inline]
+    pub fn gain(&self) -> f32 {
+        let output = self.meter.value;
+        if output < SILENCE {
+            return 1.0;
+        }
+        (self.reference.mean_square() / output)
+            .sqrt()
+            .clamp(1.0 / MAX_GAIN, MAX_GAIN)
+    }
+
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+}
+
+impl<I> Iterator for LoudnessCompensated<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        let sample = self.input.next()?;
+        self.meter.update(
+            sample.to_f32(),
+            self.input.channels(),
+            self.input.sample_rate(),
+        );
+        Some(sample.amplify(self.gain()))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for LoudnessCompensated<I>
+where
+    I: Source + ExactSizeIterator,
+    I::Item: Sample,
+{
+}
+
+impl<I> Source for LoudnessCompensated<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::source::{SineWave, Source};
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn matches_loudness_before_boost() {
+        let input: Vec<f32> = SineWave::new(1000.0).amplify(0.25).take(48000).collect();
+
+        // A resonant low pass boosts its cutoff frequency by its Q, 2 is +6 dB.
+        let boosted: Vec<f32> = SineWave::new(1000.0)
+            .amplify(0.25)
+            .low_pass_with_q(1000, 2.0)
+            .take(48000)
+            .collect();
+        let boost = rms(&boosted[24000..]) / rms(&input[24000..]);
+        assert!((boost - 2.0).abs() < 0.1, "boost of {boost}");
+
+        let (probe, reference) = SineWave::new(1000.0).amplify(0.25).measure_loudness();
+        let compensated: Vec<f32> = probe
+            .low_pass_with_q(1000, 2.0)
+            .with_loudness_compensation(reference.clone())
+            .take(48000)
+            .collect();
+        assert!((reference.rms() - rms(&input)).abs() < 0.01);
+        let ratio = rms(&compensated[24000..]) / rms(&input[24000..]);
+        assert!((ratio - 1.0).abs() < 0.02, "loudness ratio of {ratio}");
+    }
+}
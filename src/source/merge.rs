@@ -0,0 +1,245 @@
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Builds a source that interleaves several mono sources into one multichannel source.
+///
+/// The first source becomes the first channel, the second source the second channel
+/// and so on. By default the merged source ends as soon as any of the inputs ends, use
+/// [`MergeChannels::pad_with_silence`] to keep playing until all inputs have ended.
+///
+/// # Panics
+///
+/// - Panics if `sources` is empty.
+/// - Panics if a source does not have exactly one channel.
+/// - Panics if the sources do not all have the same sample rate.
+pub fn merge_channels<I>(sources: Vec<I>) -> MergeChannels<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    assert!(!sources.is_empty(), "at least one source is required");
+    let sample_rate = sources[0].sample_rate();
+    for source in &sources {
+        assert_eq!(source.channels(), 1, "only mono sources can be merged");
+        assert_eq!(
+            source.sample_rate(),
+            sample_rate,
+            "merged sources must have the same sample rate"
+        );
+    }
+
+    let channels = sources.len();
+    MergeChannels {
+        sources,
+        frame: Vec::with_capacity(channels),
+        current_channel: channels,
+        pad_with_silence: false,
+    }
+}
+
+/// A source that interleaves several mono sources, see [`merge_channels`].
+#[derive(Clone, Debug)]
+pub struct MergeChannels<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    sources: Vec<I>,
+    // Samples of the frame that is being played, one per source.
+    frame: Vec<I::Item>,
+    // Index in `frame` of the next sample to return.
+    current_channel: usize,
+    pad_with_silence: bool,
+}
+
+impl<I> MergeChannels<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    /// Keep playing until all inputs have ended, replacing the samples of inputs
+    /// that ended early with silence.
+    #[inline]
+    pub fn pad_with_silence(mut self) -> Self {
+        self.pad_with_silence = true;
+        self
+    }
+
+    /// Returns a reference to the inner sources.
+    #[inline]
+    pub fn inner(&self) -> &[I] {
+        &self.sources
+    }
+
+    /// Returns a mutable reference to the inner sources.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut [I] {
+        &mut self.sources
+    }
+
+    /// Returns the inner sources.
+    #[inline]
+    pub fn into_inner(self) -> Vec<I> {
+        self.sources
+    }
+
+    // Pulls one sample from every input. Returns `false` if the merged source has ended.
+    fn next_frame(&mut self) -> bool {
+        self.frame.clear();
+        let mut ended = 0;
+        for source in &mut self.sources {
+            match source.next() {
+                Some(sample) => self.frame.push(sample),
+                None => {
+                    ended += 1;
+                    self.frame.push(I::Item::zero_value());
+                }
+            }
+        }
+        self.current_channel = 0;
+
+        if self.pad_with_silence {
+            ended < self.sources.len()
+        } else {
+            ended == 0
+        }
+    }
+}
+
+impl<I> Iterator for MergeChannels<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        if self.current_channel >= self.sources.len() && !self.next_frame() {
+            self.current_channel = self.sources.len();
+            return None;
+        }
+
+        let sample = self.frame[self.current_channel];
+        self.current_channel += 1;
+        Some(sample)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let buffered = self.sources.len() - self.current_channel.min(self.sources.len());
+        let hints = self.sources.iter().map(|source| source.size_hint());
+        let (lower, upper) = if self.pad_with_silence {
+            hints.fold((0, Some(0)), |(lower, upper), (l, u)| {
+                (lower.max(l), upper.zip(u).map(|(a, b)| a.max(b)))
+            })
+        } else {
+            hints.fold(
+                (usize::MAX, None),
+                |(lower, upper): (usize, Option<usize>), (l, u)| {
+                    let upper = match (upper, u) {
+                        (Some(a), Some(b)) => Some(a.min(b)),
+                        (a, b) => a.or(b),
+                    };
+                    (lower.min(l), upper)
+                },
+            )
+        };
+
+        let channels = self.sources.len();
+        (
+            lower.saturating_mul(channels).saturating_add(buffered),
+            upper.and_then(|upper| upper.checked_mul(channels)?.checked_add(buffered)),
+        )
+    }
+}
+
+impl<I> Source for MergeChannels<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.sources.len() as ChannelCount
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.sources[0].sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        let mut durations = self.sources.iter().map(|source| source.total_duration());
+        if self.pad_with_silence {
+            durations.try_fold(Duration::ZERO, |total, d| Some(total.max(d?)))
+        } else {
+            durations.fold(None, |total: Option<Duration>, d| match (total, d) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            })
+        }
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.sources
+            .iter()
+            .map(|source| source.latency())
+            .max()
+            .unwrap_or_default()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        for source in &mut self.sources {
+            source.try_seek(pos)?;
+        }
+        // Drop the remainder of the current frame, the next sample is the first channel.
+        self.current_channel = self.sources.len();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::merge_channels;
+    use crate::buffer::SamplesBuffer;
+    use crate::Source;
+
+    fn ramp(start: i16, length: i16) -> SamplesBuffer<i16> {
+        SamplesBuffer::new(1, 48000, (start..start + length).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn interleaves_inputs() {
+        let merged = merge_channels(vec![ramp(0, 4), ramp(100, 4)]);
+        assert_eq!(merged.channels(), 2);
+        assert_eq!(merged.sample_rate(), 48000);
+        assert_eq!(
+            merged.collect::<Vec<_>>(),
+            vec![0, 100, 1, 101, 2, 102, 3, 103]
+        );
+    }
+
+    #[test]
+    fn ends_with_shortest_input() {
+        let merged = merge_channels(vec![ramp(0, 2), ramp(100, 4)]);
+        assert_eq!(merged.collect::<Vec<_>>(), vec![0, 100, 1, 101]);
+    }
+
+    #[test]
+    fn pads_shorter_inputs() {
+        let merged = merge_channels(vec![ramp(0, 2), ramp(100, 3)]).pad_with_silence();
+        assert_eq!(merged.collect::<Vec<_>>(), vec![0, 100, 1, 101, 0, 102]);
+    }
+}
@@ -0,0 +1,242 @@
+use std::time::Duration;
+
+use dasp_sample::FromSample;
+
+use super::uniform::UniformSourceIterator;
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::math;
+use crate::{Sample, Source};
+
+/// Internal function that builds a `WithMetronome` object.
+pub fn with_metronome<I, C>(input: I, bpm: f32, click: C) -> WithMetronome<I>
+where
+    I: Source,
+    I::Item: FromSample<C::Item> + Sample,
+    C: Source,
+    C::Item: Sample,
+{
+    assert!(
+        bpm.is_finite() && bpm > 0.0,
+        "the tempo of a metronome has to be a positive number of beats per minute"
+    );
+    let channels = input.channels();
+    let sample_rate = input.sample_rate();
+    let mut click: Vec<I::Item> =
+        UniformSourceIterator::<C, I::Item>::new(click, channels, sample_rate).collect();
+    click.truncate(click.len() / channels as usize * channels as usize);
+
+    let mut metronome = WithMetronome {
+        input,
+        click,
+        frames_per_beat: 60.0 * sample_rate as f64 / bpm as f64,
+        frame: 0,
+        channel: 0,
+        next_beat: 0,
+        click_pos: None,
+    };
+    metronome.move_to_frame(0);
+    metronome
+}
+
+/// Plays a click on every beat on top of a sound, see [`Source::with_metronome`].
+#[derive(Clone, Debug)]
+pub struct WithMetronome<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    input: I,
+    // The click in the channels and sample rate the input started with.
+    click: Vec<I::Item>,
+    frames_per_beat: f64,
+    // Frame of the next sample and its channel.
+    frame: u64,
+    channel: ChannelCount,
+    // Index of the next beat, its frame is computed from the index so rounding never
+    // adds up over long durations.
+    next_beat: u64,
+    // Position in `click` of the next sample while a click plays.
+    click_pos: Option<usize>,
+}
+
+impl<I> WithMetronome<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+
+    fn beat_frame(&self, beat: u64) -> u64 {
+        (beat as f64 * self.frames_per_beat).round() as u64
+    }
+
+    // Continues the click that started before `frame`, if it is still playing.
+    fn move_to_frame(&mut self, frame: u64) {
+        self.frame = frame;
+        self.channel = 0;
+        let mut beat = (frame as f64 / self.frames_per_beat).ceil() as u64;
+        if self.beat_frame(beat) < frame {
+            beat += 1;
+        }
+        self.next_beat = beat;
+        self.click_pos = beat
+            .checked_sub(1)
+            .map(|previous| (frame - self.beat_frame(previous)) as usize)
+            .map(|frames| frames * self.input.channels() as usize)
+            .filter(|&pos| pos < self.click.len());
+    }
+}
+
+impl<I> Iterator for WithMetronome<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        let sample = self.input.next()?;
+        if self.channel == 0 && self.frame == self.beat_frame(self.next_beat) {
+            self.click_pos = Some(0);
+            self.next_beat += 1;
+        }
+
+        let click = match self.click_pos {
+            Some(pos) if pos < self.click.len() => {
+                self.click_pos = Some(pos + 1);
+                self.click[pos]
+            }
+            _ => {
+                self.click_pos = None;
+                I::Item::zero_value()
+            }
+        };
+
+        self.channel += 1;
+        if self.channel >= self.input.channels() {
+            self.channel = 0;
+            self.frame += 1;
+        }
+        Some(sample.saturating_add(click))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for WithMetronome<I>
+where
+    I: Source + ExactSizeIterator,
+    I::Item: Sample,
+{
+}
+
+impl<I> Source for WithMetronome<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        let frame = math::duration_to_frames(pos, self.input.sample_rate());
+        self.move_to_frame(frame);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::buffer::SamplesBuffer;
+    use crate::source::Zero;
+    use crate::Source;
+
+    fn click_starts(samples: &[f32]) -> Vec<usize> {
+        (0..samples.len())
+            .filter(|&i| samples[i] != 0.0 && (i == 0 || samples[i - 1] == 0.0))
+            .collect()
+    }
+
+    #[test]
+    fn clicks_on_every_beat_without_drift() {
+        let click = SamplesBuffer::new(1, 44100, vec![1.0f32; 10]);
+        // 130 BPM at 44.1 kHz is 20353.85 frames per beat, the clicks have to round
+        // to the nearest frame of every beat instead of adding up the rounding.
+        let samples: Vec<f32> = Zero::new(1, 44100)
+            .with_metronome(130.0, click)
+            .take(44100 * 59)
+            .collect();
+
+        let starts = click_starts(&samples);
+        assert_eq!(starts.len(), 128);
+        for (beat, start) in starts.into_iter().enumerate() {
+            let expected = (beat as f64 * 60.0 * 44100.0 / 130.0).round() as usize;
+            assert_eq!(start, expected, "beat {beat}");
+        }
+        assert!(samples[..10].iter().all(|&s| s == 1.0));
+        assert_eq!(samples[10], 0.0);
+    }
+
+    #[test]
+    fn stereo_clicks_and_seeking() {
+        let click = SamplesBuffer::new(1, 1000, vec![1.0f32; 4]);
+        let mut source = Zero::new(2, 1000).with_metronome(120.0, click);
+
+        let samples: Vec<f32> = source.by_ref().take(2 * 1500).collect();
+        assert_eq!(click_starts(&samples), [0, 1000, 2000]);
+        assert!(samples[1000..1008].iter().all(|&s| s == 1.0));
+
+        // Lands in the middle of the click of the second beat.
+        source.try_seek(Duration::from_millis(502)).unwrap();
+        let samples: Vec<f32> = source.take(2 * 600).collect();
+        assert_eq!(&samples[..5], [1.0, 1.0, 1.0, 1.0, 0.0]);
+        assert_eq!(click_starts(&samples)[1], 2 * 498);
+    }
+}
@@ -122,6 +122,11 @@ where
         }
     }
 
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input1.latency().max(self.input2.latency())
+    }
+
     /// Will only attempt a seek if both underlying sources support seek.
     #[inline]
     fn try_seek(&mut self, _: Duration) -> Result<(), SeekError> {
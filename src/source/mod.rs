@@ -1,88 +1,176 @@
 //! Sources of sound and various filters.
 
 use core::fmt;
+use core::num::NonZero;
 use core::time::Duration;
+use std::sync::mpsc::SyncSender;
 
-use crate::common::{ChannelCount, SampleRate};
+use crate::buffer::SamplesBuffer;
+use crate::common::{BitDepth, ChannelCount, SampleRate};
+use crate::mixer::Mixer;
 use crate::Sample;
 use dasp_sample::FromSample;
 
 pub use self::agc::AutomaticGainControl;
+pub use self::ambisonic::AmbisonicDecoder;
 pub use self::amplify::Amplify;
+pub use self::auto_pan::AutoPan;
+pub use self::bit_depth::BitDepthReduce;
 pub use self::blt::BltFilter;
 pub use self::buffered::Buffered;
+pub use self::channel_gains::ChannelGains;
 pub use self::channel_volume::ChannelVolume;
 pub use self::chirp::{chirp, Chirp};
+pub use self::chunked::Chunked;
+pub use self::compressor::{Compressor, CompressorSettings};
 pub use self::crossfade::Crossfade;
-pub use self::delay::Delay;
+pub use self::delay::{Delay, DelaySamples};
+pub use self::denormal::FlushDenormals;
 pub use self::done::Done;
 pub use self::empty::Empty;
 pub use self::empty_callback::EmptyCallback;
+pub use self::end_on_silence::EndOnSilence;
+pub use self::envelope::{EnvelopeFollower, EnvelopeHandle};
+pub use self::external_clock::ExternalClock;
+pub use self::fade_on_seek::FadeOnSeek;
+pub use self::fade_tail::FadeTail;
 pub use self::fadein::FadeIn;
 pub use self::fadeout::FadeOut;
+pub use self::finite::{Finite, InfiniteSourceError};
 pub use self::from_factory::{from_factory, FromFactoryIter};
-pub use self::from_iter::{from_iter, FromIter};
+pub use self::from_iter::{from_iter, from_try_iter, FromIter, FromTryIter};
+pub use self::gate::{GateSettings, NoiseGate};
+pub use self::haas::HaasWiden;
+pub use self::level::Level;
 pub use self::linear_ramp::LinearGainRamp;
+pub use self::loop_outro::{LoopWithOutro, OutroHandle};
+pub use self::loudness_comp::{LoudnessCompensated, LoudnessProbe, LoudnessReference};
+pub use self::merge::{merge_channels, MergeChannels};
+pub use self::metronome::WithMetronome;
 pub use self::mix::Mix;
+pub use self::on_format_change::OnFormatChange;
+pub use self::pan_automation::PanAutomation;
 pub use self::pausable::Pausable;
 pub use self::periodic::PeriodicAccess;
+pub use self::planar::PlanarChunks;
 pub use self::position::TrackPosition;
-pub use self::repeat::Repeat;
+pub use self::preload::Preloaded;
+pub use self::protect::Protect;
+pub use self::remove_center::RemoveCenter;
+pub use self::repeat::{Repeat, RepeatCrossfade};
+pub use self::resample::{Resample, ResampleConfig, WindowFunction};
+pub use self::retrigger::{Retrigger, RetriggerHandle};
+pub use self::rotate_field::RotateField;
 pub use self::samples_converter::SamplesConverter;
 pub use self::sawtooth::SawtoothWave;
+pub use self::scheduled::{AudioClock, Scheduled};
 pub use self::signal_generator::{Function, SignalGenerator};
 pub use self::sine::SineWave;
 pub use self::skip::SkipDuration;
 pub use self::skippable::Skippable;
-pub use self::spatial::Spatial;
+pub use self::smooth::SmoothTransitions;
+pub use self::smooth_gain::{GainHandle, SmoothGain};
+pub use self::spatial::{Spatial, SpatialSurround, SpeakerLayout};
 pub use self::speed::Speed;
 pub use self::square::SquareWave;
 pub use self::stoppable::Stoppable;
 pub use self::take::TakeDuration;
+pub use self::tap::Tap;
+pub use self::tremolo::Tremolo;
 pub use self::triangle::TriangleWave;
+pub use self::true_peak::{LinkMode, StreamingPlatform, TruePeakLimit};
 pub use self::uniform::UniformSourceIterator;
+pub use self::upmix::SurroundUpmix;
+pub use self::vibrato::Vibrato;
+pub use self::windowed::Windowed;
 pub use self::zero::Zero;
 
 mod agc;
+mod ambisonic;
 mod amplify;
+mod auto_pan;
+mod bit_depth;
 mod blt;
 mod buffered;
+mod channel_gains;
 mod channel_volume;
 mod chirp;
+mod chunked;
+mod compressor;
 mod crossfade;
 mod delay;
+mod denormal;
 mod done;
 mod empty;
 mod empty_callback;
+mod end_on_silence;
+mod envelope;
+mod external_clock;
+mod fade_on_seek;
+mod fade_tail;
 mod fadein;
 mod fadeout;
+mod finite;
 mod from_factory;
 mod from_iter;
+mod gate;
+mod haas;
+mod level;
 mod linear_ramp;
+mod loop_outro;
+mod loudness_comp;
+mod merge;
+mod metronome;
 mod mix;
+mod mono_compat;
+mod on_format_change;
+mod pan_automation;
 mod pausable;
 mod periodic;
+mod planar;
 mod position;
+mod preload;
+mod protect;
+mod remove_center;
 mod repeat;
+mod resample;
+mod retrigger;
+mod rotate_field;
 mod samples_converter;
 mod sawtooth;
+mod scheduled;
 mod signal_generator;
 mod sine;
 mod skip;
 mod skippable;
+mod smooth;
+mod smooth_gain;
 mod spatial;
 mod speed;
+mod split_at;
 mod square;
 mod stoppable;
 mod take;
+mod tap;
+mod tremolo;
 mod triangle;
+mod true_peak;
 mod uniform;
+mod upmix;
+mod vibrato;
+mod windowed;
 mod zero;
 
 #[cfg(feature = "noise")]
 mod noise;
+#[cfg(feature = "fft")]
+mod pitch_shift;
+#[cfg(feature = "fft")]
+mod spectrum;
 #[cfg(feature = "noise")]
 pub use self::noise::{pink, white, PinkNoise, WhiteNoise};
+#[cfg(feature = "fft")]
+pub use self::pitch_shift::PitchShift;
 
 /// A source of samples.
 ///
@@ -175,6 +263,18 @@ where
     /// `None` indicates at the same time "infinite" or "unknown".
     fn total_duration(&self) -> Option<Duration>;
 
+    /// Returns how long a sample takes to get through the effects of this source.
+    ///
+    /// Effects that look ahead, such as [`Source::true_peak_limit`], have to read a
+    /// bit of the input before they can play a sample. That delays the sound when the
+    /// input is produced live, for example from a microphone, and can put it out of
+    /// sync with video. Every effect adds its own latency to that of its input, so
+    /// this is the latency of the whole chain. Most sources have none.
+    #[inline]
+    fn latency(&self) -> Duration {
+        Duration::ZERO
+    }
+
     /// Stores the source in a buffer in addition to returning it. This iterator can be cloned.
     #[inline]
     fn buffered(self) -> Buffered<Self>
@@ -193,248 +293,1191 @@ where
         S: Source,
         S::Item: Sample,
     {
-        mix::mix(self, other)
+        mix::mix(self, other)
+    }
+
+    /// Plays `click` on top of this source on every beat of a tempo of `bpm` beats
+    /// per minute, starting with the first sample, for example to practice along.
+    ///
+    /// Every beat starts at the frame nearest to its exact time, so the clicks do not
+    /// drift from the tempo no matter how long the source plays. A click longer than a
+    /// beat is cut off by the next one. The click is read into memory when this is
+    /// called, so it must end, and it is converted to the channels and sample rate of
+    /// this source.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bpm` is not a positive number.
+    #[inline]
+    fn with_metronome<C>(self, bpm: f32, click: C) -> WithMetronome<Self>
+    where
+        Self: Sized,
+        Self::Item: FromSample<C::Item> + Sample,
+        C: Source,
+        C::Item: Sample,
+    {
+        metronome::with_metronome(self, bpm, click)
+    }
+
+    /// Repeats this source forever.
+    ///
+    /// Note that this works by storing the data in a buffer, so the amount of memory used is
+    /// proportional to the size of the sound.
+    #[inline]
+    fn repeat_infinite(self) -> Repeat<Self>
+    where
+        Self: Sized,
+    {
+        repeat::repeat(self)
+    }
+
+    /// Repeats this source forever, blending the last `crossfade` of it into its
+    /// beginning on every loop.
+    ///
+    /// This hides the cut at the loop boundary, for example in ambient loops. The whole
+    /// source is read into memory when this is called, so it must end. Every loop after
+    /// the first is `crossfade` shorter than the source.
+    #[inline]
+    fn repeat_crossfade(self, crossfade: Duration) -> RepeatCrossfade<Self::Item>
+    where
+        Self: Sized,
+        Self::Item: Sample,
+    {
+        repeat::repeat_crossfade(self, crossfade)
+    }
+
+    /// Plays this source as a loop region until the returned [`OutroHandle`] is
+    /// triggered, then plays `outro` once and ends, for example a music stinger in a
+    /// game.
+    ///
+    /// The loop boundary and the move from the loop into the outro are both blended
+    /// over `crossfade`. Like [`repeat_crossfade`](Source::repeat_crossfade), the
+    /// loop region and the outro are read into memory when this is called, so both
+    /// must end. The outro is converted to the channels and sample rate of this
+    /// source.
+    #[inline]
+    fn with_crossfade_loop_region<O>(
+        self,
+        outro: O,
+        crossfade: Duration,
+    ) -> (LoopWithOutro<Self::Item>, OutroHandle)
+    where
+        Self: Sized,
+        Self::Item: FromSample<O::Item> + Sample,
+        O: Source,
+        O::Item: Sample,
+    {
+        loop_outro::loop_with_outro(self, outro, crossfade)
+    }
+
+    /// Lets the sound restart from the beginning whenever the returned
+    /// [`RetriggerHandle`] is triggered, for example a drum hit or a game sound
+    /// that plays again before it finished.
+    ///
+    /// The restart happens at the next frame boundary by seeking to the start, the
+    /// sound has to support [`try_seek`](Source::try_seek). Decoded files that can
+    /// not seek can be kept in memory with
+    /// [`Decoder::into_replayable`](crate::Decoder::into_replayable). A sound that
+    /// can not seek keeps playing.
+    #[inline]
+    fn retriggerable(self) -> (Retrigger<Self>, RetriggerHandle)
+    where
+        Self: Sized,
+        Self::Item: Sample,
+    {
+        retrigger::retriggerable(self)
+    }
+
+    /// Takes a certain duration of this source and then stops.
+    #[inline]
+    fn take_duration(self, duration: Duration) -> TakeDuration<Self>
+    where
+        Self: Sized,
+    {
+        take::take_duration(self, duration)
+    }
+
+    /// Delays the sound by a certain duration.
+    ///
+    /// The rate and channels of the silence will use the same format as the first span of the
+    /// source.
+    #[inline]
+    fn delay(self, duration: Duration) -> Delay<Self>
+    where
+        Self: Sized,
+    {
+        delay::delay(self, duration)
+    }
+
+    /// Delays the sound by exactly `frames` frames of silence.
+    ///
+    /// Unlike [`Source::delay`] this does not round, which is needed to line up the
+    /// phase of sources such as several microphones recording the same sound.
+    #[inline]
+    fn delay_samples(self, frames: usize) -> DelaySamples<Self>
+    where
+        Self: Sized,
+    {
+        delay::delay_samples(self, frames)
+    }
+
+    /// Plays silence until `clock` reaches `frame`, then plays the sound.
+    ///
+    /// Sources scheduled on the same [`AudioClock`] share one timeline, so sounds
+    /// layered with this start at exactly the frames asked for, no matter when they
+    /// were added to a mixer. A source added after its frame has passed plays right
+    /// away.
+    #[inline]
+    fn start_at(self, clock: AudioClock, frame: u64) -> Scheduled<Self>
+    where
+        Self: Sized,
+        Self::Item: Sample,
+    {
+        scheduled::start_at(self, clock, frame)
+    }
+
+    /// Plays the sound only as far as `clock` has been advanced with
+    /// [`AudioClock::advance`], and silence while it waits for the clock to move on.
+    ///
+    /// Effects that change over time, such as fades or LFOs, count time in samples
+    /// played. This lets a test step them in exact increments and check the output at
+    /// every step. The sound starts at the frame the clock is at when this is called.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rodio::source::{AudioClock, SineWave, Source};
+    ///
+    /// let clock = AudioClock::new();
+    /// let mut source = SineWave::new(440.0).with_external_clock(clock.clone());
+    /// assert_eq!(source.next(), Some(0.0));
+    ///
+    /// clock.advance(10);
+    /// let played: Vec<f32> = source.by_ref().take(10).collect();
+    /// assert!(played.iter().any(|&sample| sample != 0.0));
+    /// ```
+    #[inline]
+    fn with_external_clock(self, clock: AudioClock) -> ExternalClock<Self>
+    where
+        Self: Sized,
+        Self::Item: Sample,
+    {
+        external_clock::with_external_clock(self, clock)
+    }
+
+    /// Checks that the sound ends, which adapters that read all of it need.
+    ///
+    /// Reading an endless sound to the end never returns. This returns an error for
+    /// a sound that does not know its [`Source::total_duration`], such as
+    /// [`SineWave`], so the mistake shows up right away. Limit an endless sound with
+    /// [`Source::take_duration`] first. Some sounds that do end, like a decoder for a
+    /// stream without length information, do not know their duration either.
+    #[inline]
+    fn finite(self) -> Result<Finite<Self>, InfiniteSourceError>
+    where
+        Self: Sized,
+        Self::Item: Sample,
+    {
+        finite::finite(self)
+    }
+
+    /// Splits the sound at `point` into the part before and the part after it.
+    ///
+    /// The two parts are independent sources that play back to back like the original,
+    /// which is what trimming or cutting a clip in an editor needs. The whole sound is
+    /// read into memory, so it must be finite. All of it takes on the channel count and
+    /// sample rate of its first span.
+    #[inline]
+    fn split_at(self, point: Duration) -> (SamplesBuffer<Self::Item>, SamplesBuffer<Self::Item>)
+    where
+        Self: Sized,
+        Self::Item: Sample,
+    {
+        split_at::split_at(self, point)
+    }
+
+    /// Immediately skips a certain duration of this source.
+    ///
+    /// If the specified duration is longer than the source itself, `skip_duration` will skip to the end of the source.
+    #[inline]
+    fn skip_duration(self, duration: Duration) -> SkipDuration<Self>
+    where
+        Self: Sized,
+    {
+        skip::skip_duration(self, duration)
+    }
+
+    /// Amplifies the sound by the given value.
+    #[inline]
+    fn amplify(self, value: f32) -> Amplify<Self>
+    where
+        Self: Sized,
+    {
+        amplify::amplify(self, value)
+    }
+
+    /// Amplifies every channel by its own gain, `gains[0]` for the first channel and so
+    /// on.
+    ///
+    /// Useful to correct a multichannel recording with unbalanced levels. Unlike
+    /// [`ChannelVolume`] the channels stay separate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of gains differs from the channel count of the sound.
+    #[inline]
+    fn channel_gains(self, gains: Vec<f32>) -> ChannelGains<Self>
+    where
+        Self: Sized,
+        Self::Item: Sample,
+    {
+        channel_gains::channel_gains(self, gains)
+    }
+
+    /// Applies automatic gain control to the sound.
+    ///
+    /// Automatic Gain Control (AGC) adjusts the amplitude of the audio signal
+    /// to maintain a consistent output level.
+    ///
+    /// # Parameters
+    ///
+    /// `target_level`:
+    ///   **TL;DR**: Desired output level. 1.0 = original level, > 1.0 amplifies, < 1.0 reduces.
+    ///
+    ///   The desired output level, where 1.0 represents the original sound level.
+    ///   Values above 1.0 will amplify the sound, while values below 1.0 will lower it.
+    ///   For example, a target_level of 1.4 means that at normal sound levels, the AGC
+    ///   will aim to increase the gain by a factor of 1.4, resulting in a minimum 40% amplification.
+    ///   A recommended level is `1.0`, which maintains the original sound level.
+    ///
+    /// `attack_time`:
+    ///   **TL;DR**: Response time for volume increases. Shorter = faster but may cause abrupt changes. **Recommended: `4.0` seconds**.
+    ///
+    ///   The time (in seconds) for the AGC to respond to input level increases.
+    ///   Shorter times mean faster response but may cause abrupt changes. Longer times result
+    ///   in smoother transitions but slower reactions to sudden volume changes. Too short can
+    ///   lead to overreaction to peaks, causing unnecessary adjustments. Too long can make the
+    ///   AGC miss important volume changes or react too slowly to sudden loud passages. Very
+    ///   high values might result in excessively loud output or sluggish response, as the AGC's
+    ///   adjustment speed is limited by the attack time. Balance is key for optimal performance.
+    ///   A recommended attack_time of `4.0` seconds provides a sweet spot for most applications.
+    ///
+    /// `release_time`:
+    ///   **TL;DR**: Response time for volume decreases. Shorter = faster gain reduction. **Recommended: `0.005` seconds**.
+    ///
+    ///   The time (in seconds) for the AGC to respond to input level decreases.
+    ///   This parameter controls how quickly the gain is reduced when the signal level drops.
+    ///   Shorter release times result in faster gain reduction, which can be useful for quick
+    ///   adaptation to quieter passages but may lead to pumping effects. Longer release times
+    ///   provide smoother transitions but may be slower to respond to sudden decreases in volume.
+    ///   However, if the release_time is too high, the AGC may not be able to lower the gain
+    ///   quickly enough, potentially leading to clipping and distorted sound before it can adjust.
+    ///   Finding the right balance is crucial for maintaining natural-sounding dynamics and
+    ///   preventing distortion. A recommended release_time of `0.005` seconds often works well for
+    ///   general use, providing a good balance between responsiveness and smooth transitions.
+    ///
+    /// Attack and release times are limited to between `0.001` and `60.0` seconds.
+    ///
+    /// `absolute_max_gain`:
+    ///   **TL;DR**: Maximum allowed gain. Prevents over-amplification. **Recommended: `5.0`**.
+    ///
+    ///   The maximum gain that can be applied to the signal.
+    ///   This parameter acts as a safeguard against excessive amplification of quiet signals
+    ///   or background noise. It establishes an upper boundary for the AGC's signal boost,
+    ///   effectively preventing distortion or overamplification of low-level sounds.
+    ///   This is crucial for maintaining audio quality and preventing unexpected volume spikes.
+    ///   A recommended value for `absolute_max_gain` is `5`, which provides a good balance between
+    ///   amplification capability and protection against distortion in most scenarios.
+    ///
+    /// `automatic_gain_control` example in this project shows a pattern you can use
+    /// to enable/disable the AGC filter dynamically.
+    ///
+    /// # Example (Quick start)
+    ///
+    /// ```rust
+    /// // Apply Automatic Gain Control to the source (AGC is on by default)
+    /// use rodio::source::{Source, SineWave};
+    /// use rodio::Sink;
+    /// let source = SineWave::new(444.0); // An example.
+    /// let (sink, output) = Sink::new(); // An example.
+    ///
+    /// let agc_source = source.automatic_gain_control(1.0, 4.0, 0.005, 5.0);
+    ///
+    /// // Add the AGC-controlled source to the sink
+    /// sink.append(agc_source);
+    ///
+    /// ```
+    #[inline]
+    fn automatic_gain_control(
+        self,
+        target_level: f32,
+        attack_time: f32,
+        release_time: f32,
+        absolute_max_gain: f32,
+    ) -> AutomaticGainControl<Self>
+    where
+        Self: Sized,
+    {
+        // Added Limits to prevent the AGC from blowing up. ;)
+        let attack_time = attack_time.clamp(agc::MIN_ATTACK_TIME, agc::MAX_ATTACK_TIME);
+        let release_time = release_time.clamp(agc::MIN_RELEASE_TIME, agc::MAX_RELEASE_TIME);
+
+        agc::automatic_gain_control(
+            self,
+            target_level,
+            attack_time,
+            release_time,
+            absolute_max_gain,
+        )
+    }
+
+    /// Levels the sound towards a peak amplitude in a single pass, for streams that
+    /// can not be normalized up front.
+    ///
+    /// The peak is tracked over the last `window`. When the peak grows the gain is lowered
+    /// right away, when it shrinks the gain is raised over roughly `window`. Unlike
+    /// [`automatic_gain_control`](Source::automatic_gain_control) the output never goes
+    /// above `target_peak` and there is no pumping.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use rodio::source::{SineWave, Source};
+    ///
+    /// let source = SineWave::new(440.0).amplify(0.1).level(0.8, Duration::from_secs(2));
+    /// ```
+    #[inline]
+    fn level(self, target_peak: f32, window: Duration) -> Level<Self>
+    where
+        Self: Sized,
+    {
+        level::level(self, target_peak, window)
+    }
+
+    /// Compresses the dynamic range of the sound, so loud parts come out quieter
+    /// relative to soft ones.
+    ///
+    /// The level above the threshold of `settings` is divided by its ratio, easing in
+    /// over the width of the knee. The gain follows the level with the attack and
+    /// release times of `settings`, then the makeup gain is applied. Unlike a limiter
+    /// the ratio is finite, so peaks are only reduced and not stopped at the
+    /// threshold, see [`true_peak_limit`](Source::true_peak_limit) for that. A ratio
+    /// of `1.0` without makeup gain leaves the sound untouched.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rodio::source::{CompressorSettings, SineWave, Source};
+    ///
+    /// let source = SineWave::new(440.0).compress(CompressorSettings::voice());
+    /// ```
+    #[inline]
+    fn compress(self, settings: CompressorSettings) -> Compressor<Self>
+    where
+        Self: Sized,
+        Self::Item: Sample,
+    {
+        compressor::compress(self, settings)
+    }
+
+    /// Silences the sound while it is quieter than the threshold of `settings`, to
+    /// cut background noise from a microphone or between lines of dialogue.
+    ///
+    /// Once the level stayed below the threshold for the hold time the gain falls to
+    /// zero over the release time. It rises back over the attack time as soon as the
+    /// level goes above the threshold plus the hysteresis. The loudest channel opens
+    /// and closes the gate for all of them, so a quiet channel is never cut while the
+    /// others play.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rodio::source::{GateSettings, SineWave, Source};
+    ///
+    /// let source = SineWave::new(440.0).noise_gate(GateSettings::new().with_threshold(-45.0));
+    /// ```
+    #[inline]
+    fn noise_gate(self, settings: GateSettings) -> NoiseGate<Self>
+    where
+        Self: Sized,
+        Self::Item: Sample,
+    {
+        gate::noise_gate(self, settings)
+    }
+
+    /// Keeps the peaks between samples below `ceiling_db` dBFS.
+    ///
+    /// A signal whose samples all stay below full scale can still reach above it once
+    /// a DAC reconstructs the waveform between them. This converts the sound to
+    /// `oversample` times its sample rate, limits the peaks there and converts it back.
+    /// Streaming platforms usually ask for a true peak ceiling of -1 dB with an
+    /// `oversample` factor of at least 4.
+    ///
+    /// A peak on one channel lowers the gain of all of them, use
+    /// [`TruePeakLimit::with_channel_link`] to limit every channel on its own.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `oversample` is zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rodio::source::{SineWave, Source};
+    ///
+    /// let source = SineWave::new(440.0).true_peak_limit(-1.0, 4);
+    /// ```
+    #[inline]
+    fn true_peak_limit(self, ceiling_db: f32, oversample: usize) -> TruePeakLimit<Self>
+    where
+        Self: Sized,
+        Self::Item: Sample + FromSample<f32>,
+    {
+        true_peak::true_peak_limit(self, ceiling_db, oversample)
+    }
+
+    /// Keeps the peaks between samples below the ceiling a streaming platform asks
+    /// for, see [`StreamingPlatform`] for the values.
+    ///
+    /// This is [`true_peak_limit`](Source::true_peak_limit) with the platform's
+    /// ceiling and an `oversample` factor of 4.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rodio::source::{SineWave, Source, StreamingPlatform};
+    ///
+    /// let source = SineWave::new(440.0).limit_true_peak_for_platform(StreamingPlatform::Spotify);
+    /// ```
+    #[inline]
+    fn limit_true_peak_for_platform(self, platform: StreamingPlatform) -> TruePeakLimit<Self>
+    where
+        Self: Sized,
+        Self::Item: Sample + FromSample<f32>,
+    {
+        true_peak::limit_true_peak_for_platform(self, platform)
+    }
+
+    /// Lowers the gain only when the sound would rise above `headroom_db` dB below
+    /// full scale, as a last safety net in front of the speakers.
+    ///
+    /// Unlike a limiter that is always working, the sound passes untouched as long as
+    /// it stays within the headroom. The sound is read 2 ms ahead, so the gain ramps
+    /// down before a frame that would exceed the headroom and reaches just enough
+    /// reduction when it plays, which adds 2 ms of [`latency`](Source::latency). The
+    /// gain is held for a moment and then recovers smoothly to leave the sound
+    /// untouched again.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rodio::source::{SineWave, Source};
+    ///
+    /// let source = SineWave::new(440.0).amplify(2.0).protect(1.0);
+    /// ```
+    #[inline]
+    fn protect(self, headroom_db: f32) -> Protect<Self>
+    where
+        Self: Sized,
+        Self::Item: Sample,
+    {
+        protect::protect(self, headroom_db)
+    }
+
+    /// Passes the sound through unchanged and publishes its envelope through the
+    /// returned [`EnvelopeHandle`], for example to drive a level meter or to duck
+    /// another sound while this one plays.
+    ///
+    /// The envelope follows rising levels within about `attack` and falling levels
+    /// within about `release`, both are the time to cover 63% of a change.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use rodio::source::{SineWave, Source};
+    ///
+    /// let (source, envelope) = SineWave::new(440.0)
+    ///     .envelope_follower(Duration::from_millis(5), Duration::from_millis(100));
+    /// // While the source plays, on another thread:
+    /// let level = envelope.envelope();
+    /// ```
+    #[inline]
+    fn envelope_follower(
+        self,
+        attack: Duration,
+        release: Duration,
+    ) -> (EnvelopeFollower<Self>, EnvelopeHandle)
+    where
+        Self: Sized,
+        Self::Item: Sample,
+    {
+        envelope::envelope_follower(self, attack, release)
+    }
+
+    /// Passes the sound through unchanged and measures its loudness, so
+    /// [`with_loudness_compensation`](Source::with_loudness_compensation) can restore
+    /// it after effects that change it.
+    ///
+    /// An EQ boost or a filter makes a sound louder or quieter, which is easily
+    /// mistaken for an improvement. Measure the sound before the effects and
+    /// compensate after them to hear only the change in tone.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rodio::source::{SineWave, Source};
+    ///
+    /// let (source, reference) = SineWave::new(440.0).measure_loudness();
+    /// let source = source
+    ///     .low_pass_with_q(440, 2.0)
+    ///     .with_loudness_compensation(reference);
+    /// ```
+    #[inline]
+    fn measure_loudness(self) -> (LoudnessProbe<Self>, LoudnessReference)
+    where
+        Self: Sized,
+        Self::Item: Sample,
+    {
+        loudness_comp::measure_loudness(self)
+    }
+
+    /// Adjusts the gain so the sound is as loud as measured by
+    /// [`measure_loudness`](Source::measure_loudness) earlier in the chain.
+    ///
+    /// Both sides average the RMS level over the last 400 milliseconds, so the gain
+    /// follows changes in the effects within about that time. The gain stays within
+    /// 24 dB either way and silence is left alone.
+    #[inline]
+    fn with_loudness_compensation(self, reference: LoudnessReference) -> LoudnessCompensated<Self>
+    where
+        Self: Sized,
+        Self::Item: Sample,
+    {
+        loudness_comp::with_loudness_compensation(self, reference)
+    }
+
+    /// Plays the whole sound and returns its root mean square level over all channels.
+    ///
+    /// The samples are not kept, so this works on long sounds. Useful to pick a gain
+    /// when normalizing, the source must be finite. Returns `0.0` for an empty sound.
+    fn integrated_rms(self) -> f32
+    where
+        Self: Sized,
+        Self::Item: Sample,
+    {
+        let (sum, count) = self.fold((0.0f64, 0u64), |(sum, count), sample| {
+            let sample = sample.to_f32() as f64;
+            (sum + sample * sample, count + 1)
+        });
+        if count == 0 {
+            0.0
+        } else {
+            (sum / count as f64).sqrt() as f32
+        }
+    }
+
+    /// Plays the whole sound and returns the highest absolute sample value over all
+    /// channels.
+    ///
+    /// Like [`Source::integrated_rms`] the samples are not kept and the source must be
+    /// finite. Returns `0.0` for an empty sound.
+    fn integrated_peak(self) -> f32
+    where
+        Self: Sized,
+        Self::Item: Sample,
+    {
+        self.fold(0.0, |peak: f32, sample| peak.max(sample.to_f32().abs()))
+    }
+
+    /// Plays the whole sound and returns how much of its energy is left when the left
+    /// and right channel are summed to mono.
+    ///
+    /// This is the energy of the mono sum divided by the average energy of the two
+    /// channels. Identical channels give `1.0`, unrelated ones about `0.5` and a value
+    /// near `0.0` warns that the channels cancel out, as happens when one of them is
+    /// phase inverted. Other channels are ignored and a mono sound counts as both left
+    /// and right. The source must be finite. Returns `1.0` for silence.
+    fn mono_compatibility(self) -> f32
+    where
+        Self: Sized,
+        Self::Item: Sample,
+    {
+        mono_compat::mono_compatibility(self)
+    }
+
+    /// Plays the whole sound and returns its power at each frequency, averaged over
+    /// blocks of `fft_size` frames.
+    ///
+    /// Element `k` of the result is the power around `k * sample_rate / fft_size` Hz,
+    /// there are `fft_size / 2 + 1` of them. The channels are mixed to mono and each block
+    /// is weighed with a Hann window, the last block is padded with silence. A sine wave
+    /// of amplitude `a` centered on a frequency shows up with a power of `a * a / 2`. The
+    /// source must be finite.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `fft_size` is zero.
+    #[cfg(feature = "fft")]
+    fn power_spectrum(self, fft_size: usize) -> Vec<f32>
+    where
+        Self: Sized,
+        Self::Item: Sample,
+    {
+        spectrum::power_spectrum(self, fft_size)
+    }
+
+    /// Mixes this sound fading out with another sound fading in for the given duration.
+    ///
+    /// Only the crossfaded portion (beginning of self, beginning of other) is returned.
+    #[inline]
+    fn take_crossfade_with<S: Source>(self, other: S, duration: Duration) -> Crossfade<Self, S>
+    where
+        Self: Sized,
+        Self::Item: FromSample<S::Item>,
+        <S as Iterator>::Item: Sample,
+    {
+        crossfade::crossfade(self, other, duration)
+    }
+
+    /// Fades in the sound.
+    #[inline]
+    fn fade_in(self, duration: Duration) -> FadeIn<Self>
+    where
+        Self: Sized,
+    {
+        fadein::fadein(self, duration)
+    }
+
+    /// Fades out the sound.
+    #[inline]
+    fn fade_out(self, duration: Duration) -> FadeOut<Self>
+    where
+        Self: Sized,
+    {
+        fadeout::fadeout(self, duration)
+    }
+
+    /// Fades out before and fades in after every seek.
+    ///
+    /// Jumping to another position causes an audible click. Seeking first reads ahead
+    /// `fade` of the sound to fade it out, so the jump is heard `fade` later.
+    #[inline]
+    fn fade_on_seek(self, fade: Duration) -> FadeOnSeek<Self>
+    where
+        Self: Sized,
+        Self::Item: Sample,
+    {
+        fade_on_seek::fade_on_seek(self, fade)
+    }
+
+    /// Fades out the last `duration` of the sound, so it does not click when it ends
+    /// in the middle of a waveform.
+    ///
+    /// Unlike [`fade_out`](Source::fade_out), which starts fading right away, this
+    /// waits for the end. It is found from [`size_hint`](Iterator::size_hint), so the
+    /// length of the sound does not have to be known up front. Sounds that do not
+    /// know exactly how many samples they have left play unchanged.
+    #[inline]
+    fn fade_tail(self, duration: Duration) -> FadeTail<Self>
+    where
+        Self: Sized,
+        Self::Item: Sample,
+    {
+        fade_tail::fade_tail(self, duration)
+    }
+
+    /// Dips the volume where the channel count or sample rate of the sound changes.
+    ///
+    /// Switching formats abruptly, for example between two sounds in a queue, causes an
+    /// audible click. This fades out the last `fade` before the change and fades in the
+    /// first `fade` after it. The change is found by reading ahead to the end of each
+    /// span, if the length of the span is unknown only the fade in is applied.
+    #[inline]
+    fn smooth_transitions(self, fade: Duration) -> SmoothTransitions<Self>
+    where
+        Self: Sized,
+        Self::Item: Sample,
+    {
+        smooth::smooth_transitions(self, fade)
+    }
+
+    /// Applies a gain that glides to every new value instead of jumping.
+    ///
+    /// Changing the volume instantly causes an audible click. Set the gain with
+    /// [`SmoothGain::set_gain`] or from another thread with the [`GainHandle`]
+    /// returned by [`SmoothGain::handle`], the applied gain then covers 63% of the
+    /// distance to the new value every `time_constant`. A few milliseconds are
+    /// enough to avoid clicks. The gain starts at `1.0`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use rodio::source::{SineWave, Source};
+    ///
+    /// let source = SineWave::new(440.0).smooth_gain(Duration::from_millis(5));
+    /// let volume = source.handle();
+    /// // Later, while the source plays:
+    /// volume.set_gain(0.5);
+    /// ```
+    #[inline]
+    fn smooth_gain(self, time_constant: Duration) -> SmoothGain<Self>
+    where
+        Self: Sized,
+        Self::Item: Sample,
+    {
+        smooth_gain::smooth_gain(self, time_constant)
+    }
+
+    /// Applies a linear gain ramp to the sound.
+    ///
+    /// If `clamp_end` is `true`, all samples subsequent to the end of the ramp
+    /// will be scaled by the `end_value`. If `clamp_end` is `false`, all
+    /// subsequent samples will not have any scaling applied.
+    #[inline]
+    fn linear_gain_ramp(
+        self,
+        duration: Duration,
+        start_value: f32,
+        end_value: f32,
+        clamp_end: bool,
+    ) -> LinearGainRamp<Self>
+    where
+        Self: Sized,
+    {
+        linear_ramp::linear_gain_ramp(self, duration, start_value, end_value, clamp_end)
+    }
+
+    /// Decodes the first `duration` of the sound right away, on the calling thread.
+    ///
+    /// The rest is decoded while playing as usual. Starting a sound can make the decoder
+    /// read and parse the file, which takes time on the audio thread and can cause a
+    /// short hitch. Preloading does that work up front, for example while loading a
+    /// level in a game.
+    ///
+    /// Preloading stops early if the channel count or sample rate changes.
+    #[inline]
+    fn preload(self, duration: Duration) -> Preloaded<Self>
+    where
+        Self: Sized,
+        Self::Item: Sample,
+    {
+        preload::preload(self, duration)
+    }
+
+    /// Sends a copy of every sample that is played to `tx`, for example to record or
+    /// analyze the sound on another thread.
+    ///
+    /// The samples pass through unchanged. When the channel is full samples are dropped
+    /// instead of blocking playback, so pick a capacity that fits how fast the receiving
+    /// thread reads.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::mpsc::sync_channel;
+    /// use rodio::source::{SineWave, Source};
+    ///
+    /// let (tx, rx) = sync_channel(48000);
+    /// let source = SineWave::new(440.0).tap(tx);
+    /// ```
+    #[inline]
+    fn tap(self, tx: SyncSender<Self::Item>) -> Tap<Self>
+    where
+        Self: Sized,
+        Self::Item: Sample,
+    {
+        tap::tap(self, tx)
+    }
+
+    /// Calls the `access` closure on `Self` the first time the source is iterated and every
+    /// time `period` elapses.
+    ///
+    /// Later changes in either `sample_rate()` or `channels_count()` won't be reflected in
+    /// the rate of access.
+    ///
+    /// The rate is based on playback speed, so both the following will call `access` when the
+    /// same samples are reached:
+    /// `periodic_access(Duration::from_secs(1), ...).speed(2.0)`
+    /// `speed(2.0).periodic_access(Duration::from_secs(2), ...)`
+    #[inline]
+    fn periodic_access<F>(self, period: Duration, access: F) -> PeriodicAccess<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(&mut Self),
+    {
+        periodic::periodic(self, period, access)
+    }
+
+    /// Calls `callback` with the new sample rate and channel count whenever they
+    /// change while the sound plays, for example between the files of a queue or the
+    /// streams of a chained Ogg file.
+    ///
+    /// The callback runs on the thread playing the sound, right after the first
+    /// sample in the new format. It is not called for the format the sound starts
+    /// with.
+    #[inline]
+    fn on_format_change<F>(self, callback: F) -> OnFormatChange<Self, F>
+    where
+        Self: Sized,
+        Self::Item: Sample,
+        F: FnMut(SampleRate, ChannelCount),
+    {
+        on_format_change::on_format_change(self, callback)
+    }
+
+    /// Changes the play speed of the sound. Does not adjust the samples, only the playback speed.
+    ///
+    /// # Note:
+    /// 1. **Increasing the speed will increase the pitch by the same factor**
+    /// - If you set the speed to 0.5 this will halve the frequency of the sound
+    ///   lowering its pitch.
+    /// - If you set the speed to 2 the frequency will double raising the
+    ///   pitch of the sound.
+    /// 2. **Change in the speed affect the total duration inversely**
+    /// - If you set the speed to 0.5, the total duration will be twice as long.
+    /// - If you set the speed to 2 the total duration will be halve of what it
+    ///   was.
+    ///
+    /// See [`Speed`] for details
+    #[inline]
+    fn speed(self, ratio: f32) -> Speed<Self>
+    where
+        Self: Sized,
+    {
+        speed::speed(self, ratio)
+    }
+
+    /// Shifts the pitch of the sound by `semitones` without changing its speed or
+    /// duration. Negative values lower the pitch, `12.0` raises it by an octave.
+    ///
+    /// Unlike [`speed`](Source::speed) this plays every channel through a phase
+    /// vocoder, which works on blocks of 2048 frames. The sound comes out aligned with
+    /// the input and with as many samples, but the first frame is only played once
+    /// the first block has been read. Transients are smeared a little, more so the
+    /// larger the shift. A shift of `0.0` leaves the sound untouched.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rodio::source::{SineWave, Source};
+    ///
+    /// // Plays 880 Hz.
+    /// let source = SineWave::new(440.0).pitch_shift(12.0);
+    /// ```
+    #[cfg(feature = "fft")]
+    #[inline]
+    fn pitch_shift(self, semitones: f32) -> PitchShift<Self>
+    where
+        Self: Sized,
+        Self::Item: Sample + FromSample<f32>,
+    {
+        pitch_shift::pitch_shift(self, semitones)
     }
 
-    /// Repeats this source forever.
+    /// Ends the sound once it has been quieter than `threshold_db` for `duration`.
     ///
-    /// Note that this works by storing the data in a buffer, so the amount of memory used is
-    /// proportional to the size of the sound.
+    /// Useful for streams that may stall and keep producing silence instead of ending,
+    /// for example internet radio. Ending the source lets a [`Sink`](crate::Sink)
+    /// continue with the next one.
+    ///
+    /// `threshold_db` is relative to full scale, so `-60.0` treats anything below
+    /// an amplitude of `0.001` as silence. A frame only counts as silent if all of
+    /// its channels are, and the sound always ends after a complete frame.
     #[inline]
-    fn repeat_infinite(self) -> Repeat<Self>
+    fn end_on_silence(self, threshold_db: f32, duration: Duration) -> EndOnSilence<Self>
     where
         Self: Sized,
     {
-        repeat::repeat(self)
+        end_on_silence::end_on_silence(self, threshold_db, duration)
     }
 
-    /// Takes a certain duration of this source and then stops.
+    /// Pans the sound across a surround speaker setup, based on the direction of
+    /// `position` as seen from the listener. See [`SpatialSurround`] for details.
     #[inline]
-    fn take_duration(self, duration: Duration) -> TakeDuration<Self>
+    fn spatial_surround(self, layout: SpeakerLayout, position: [f32; 3]) -> SpatialSurround<Self>
     where
         Self: Sized,
     {
-        take::take_duration(self, duration)
+        SpatialSurround::new(self, layout, position)
     }
 
-    /// Delays the sound by a certain duration.
+    /// Spreads a stereo sound over the speakers of `layout`.
     ///
-    /// The rate and channels of the silence will use the same format as the first span of the
-    /// source.
+    /// Left and right pass unchanged. The center plays the sum of both, the LFE a
+    /// low-passed copy of that sum. The surrounds play the difference between left
+    /// and right, delayed by 10 and 15 ms and with opposite polarity so they do
+    /// not collapse into a single sound behind the listener. Mono sounds play on
+    /// both front speakers, channels beyond the first two are dropped.
     #[inline]
-    fn delay(self, duration: Duration) -> Delay<Self>
+    fn upmix_surround(self, layout: SpeakerLayout) -> SurroundUpmix<Self>
     where
         Self: Sized,
+        Self::Item: Sample,
     {
-        delay::delay(self, duration)
+        upmix::upmix_surround(self, layout)
     }
 
-    /// Immediately skips a certain duration of this source.
+    /// Turns the sound field around the listener by `angle` radians clockwise.
     ///
-    /// If the specified duration is longer than the source itself, `skip_duration` will skip to the end of the source.
+    /// The content of every speaker moves to the direction `angle` further along
+    /// and is panned between the two speakers closest to it, so a quarter turn of a
+    /// quad sound moves the front left channel to the front right speaker. The
+    /// speaker positions follow the channel count: two channels are stereo, four
+    /// are quad and six are 5.1, see [`SpeakerLayout`]. Other channel counts and
+    /// the LFE channel pass unchanged.
     #[inline]
-    fn skip_duration(self, duration: Duration) -> SkipDuration<Self>
+    fn rotate_field(self, angle: f32) -> RotateField<Self>
     where
         Self: Sized,
+        Self::Item: Sample,
     {
-        skip::skip_duration(self, duration)
+        rotate_field::rotate_field(self, angle)
     }
 
-    /// Amplifies the sound by the given value.
+    /// Decodes first-order ambisonics (B-format) to the speakers of `layout`.
+    ///
+    /// The channels of the source are W, X, Y and Z in that order, with W scaled
+    /// by 1/√2 as in the FuMa convention. Every speaker plays the sound field as heard
+    /// from its direction, see [`SpeakerLayout::azimuths`]. The layouts are all
+    /// horizontal, so the height component Z is ignored, and the LFE channel stays
+    /// silent. Missing components are treated as silence.
     #[inline]
-    fn amplify(self, value: f32) -> Amplify<Self>
+    fn decode_ambisonic(self, layout: SpeakerLayout) -> AmbisonicDecoder<Self>
     where
         Self: Sized,
+        Self::Item: Sample,
     {
-        amplify::amplify(self, value)
+        ambisonic::decode_ambisonic(self, layout)
     }
 
-    /// Applies automatic gain control to the sound.
+    /// Sweeps the sound back and forth between the left and right speaker `rate_hz`
+    /// times per second.
     ///
-    /// Automatic Gain Control (AGC) adjusts the amplitude of the audio signal
-    /// to maintain a consistent output level.
+    /// A `depth` of `1.0` reaches fully left and right, smaller values stay closer to
+    /// the center. Panning keeps the total power constant. Mono sounds become stereo,
+    /// for sounds with more channels only the first two are panned.
     ///
-    /// # Parameters
+    /// # Example
     ///
-    /// `target_level`:
-    ///   **TL;DR**: Desired output level. 1.0 = original level, > 1.0 amplifies, < 1.0 reduces.
+    /// ```
+    /// use rodio::source::{SineWave, Source};
     ///
-    ///   The desired output level, where 1.0 represents the original sound level.
-    ///   Values above 1.0 will amplify the sound, while values below 1.0 will lower it.
-    ///   For example, a target_level of 1.4 means that at normal sound levels, the AGC
-    ///   will aim to increase the gain by a factor of 1.4, resulting in a minimum 40% amplification.
-    ///   A recommended level is `1.0`, which maintains the original sound level.
+    /// let source = SineWave::new(440.0).auto_pan(0.5, 0.8);
+    /// ```
+    #[inline]
+    fn auto_pan(self, rate_hz: f32, depth: f32) -> AutoPan<Self>
+    where
+        Self: Sized,
+        Self::Item: Sample,
+    {
+        auto_pan::auto_pan(self, rate_hz, depth)
+    }
+
+    /// Makes the volume of the sound dip and come back `rate_hz` times per second.
     ///
-    /// `attack_time`:
-    ///   **TL;DR**: Response time for volume increases. Shorter = faster but may cause abrupt changes. **Recommended: `4.0` seconds**.
+    /// Every sample is multiplied by `1 - depth * (0.5 - 0.5 * cos(2π * rate_hz * t))`,
+    /// so the sound starts at full volume and a `depth` of `1.0` dips down to silence.
+    /// `depth` is clamped between `0.0` and `1.0`. All channels dip together.
     ///
-    ///   The time (in seconds) for the AGC to respond to input level increases.
-    ///   Shorter times mean faster response but may cause abrupt changes. Longer times result
-    ///   in smoother transitions but slower reactions to sudden volume changes. Too short can
-    ///   lead to overreaction to peaks, causing unnecessary adjustments. Too long can make the
-    ///   AGC miss important volume changes or react too slowly to sudden loud passages. Very
-    ///   high values might result in excessively loud output or sluggish response, as the AGC's
-    ///   adjustment speed is limited by the attack time. Balance is key for optimal performance.
-    ///   A recommended attack_time of `4.0` seconds provides a sweet spot for most applications.
+    /// # Example
     ///
-    /// `release_time`:
-    ///   **TL;DR**: Response time for volume decreases. Shorter = faster gain reduction. **Recommended: `0.005` seconds**.
+    /// ```
+    /// use rodio::source::{SineWave, Source};
     ///
-    ///   The time (in seconds) for the AGC to respond to input level decreases.
-    ///   This parameter controls how quickly the gain is reduced when the signal level drops.
-    ///   Shorter release times result in faster gain reduction, which can be useful for quick
-    ///   adaptation to quieter passages but may lead to pumping effects. Longer release times
-    ///   provide smoother transitions but may be slower to respond to sudden decreases in volume.
-    ///   However, if the release_time is too high, the AGC may not be able to lower the gain
-    ///   quickly enough, potentially leading to clipping and distorted sound before it can adjust.
-    ///   Finding the right balance is crucial for maintaining natural-sounding dynamics and
-    ///   preventing distortion. A recommended release_time of `0.005` seconds often works well for
-    ///   general use, providing a good balance between responsiveness and smooth transitions.
+    /// let source = SineWave::new(440.0).tremolo(5.0, 0.5);
+    /// ```
+    #[inline]
+    fn tremolo(self, rate_hz: f32, depth: f32) -> Tremolo<Self>
+    where
+        Self: Sized,
+        Self::Item: Sample,
+    {
+        tremolo::tremolo(self, rate_hz, depth)
+    }
+
+    /// Makes the pitch of the sound wobble up and down `rate_hz` times per second.
     ///
-    /// `absolute_max_gain`:
-    ///   **TL;DR**: Maximum allowed gain. Prevents over-amplification. **Recommended: `5.0`**.
+    /// The sound is played through a delay that sweeps between none and `depth_ms`
+    /// milliseconds, following a sine. While the delay grows the pitch drops and while
+    /// it shrinks the pitch rises, a few milliseconds give a singer's vibrato. Between
+    /// samples the delayed sound is interpolated linearly.
     ///
-    ///   The maximum gain that can be applied to the signal.
-    ///   This parameter acts as a safeguard against excessive amplification of quiet signals
-    ///   or background noise. It establishes an upper boundary for the AGC's signal boost,
-    ///   effectively preventing distortion or overamplification of low-level sounds.
-    ///   This is crucial for maintaining audio quality and preventing unexpected volume spikes.
-    ///   A recommended value for `absolute_max_gain` is `5`, which provides a good balance between
-    ///   amplification capability and protection against distortion in most scenarios.
+    /// # Example
     ///
-    /// `automatic_gain_control` example in this project shows a pattern you can use
-    /// to enable/disable the AGC filter dynamically.
+    /// ```
+    /// use rodio::source::{SineWave, Source};
     ///
-    /// # Example (Quick start)
+    /// let source = SineWave::new(440.0).vibrato(5.0, 2.0);
+    /// ```
+    #[inline]
+    fn vibrato(self, rate_hz: f32, depth_ms: f32) -> Vibrato<Self>
+    where
+        Self: Sized,
+        Self::Item: Sample + FromSample<f32>,
+    {
+        vibrato::vibrato(self, rate_hz, depth_ms)
+    }
+
+    /// Moves the sound between the left and right speaker along `keyframes`.
     ///
-    /// ```rust
-    /// // Apply Automatic Gain Control to the source (AGC is on by default)
-    /// use rodio::source::{Source, SineWave};
-    /// use rodio::Sink;
-    /// let source = SineWave::new(444.0); // An example.
-    /// let (sink, output) = Sink::new(); // An example.
+    /// Each keyframe is a time from the start of the sound and a pan position from
+    /// `-1.0` (fully left) to `1.0` (fully right). Between keyframes the position moves
+    /// linearly, before the first and after the last it holds. Panning keeps the total
+    /// power constant. Mono sounds become stereo, for sounds with more channels only
+    /// the first two are panned.
     ///
-    /// let agc_source = source.automatic_gain_control(1.0, 4.0, 0.005, 5.0);
+    /// # Example
     ///
-    /// // Add the AGC-controlled source to the sink
-    /// sink.append(agc_source);
+    /// ```
+    /// use std::time::Duration;
+    /// use rodio::source::{SineWave, Source};
     ///
+    /// let source = SineWave::new(440.0).automate_pan(vec![
+    ///     (Duration::ZERO, -1.0),
+    ///     (Duration::from_secs(2), 1.0),
+    /// ]);
     /// ```
     #[inline]
-    fn automatic_gain_control(
-        self,
-        target_level: f32,
-        attack_time: f32,
-        release_time: f32,
-        absolute_max_gain: f32,
-    ) -> AutomaticGainControl<Self>
+    fn automate_pan(self, keyframes: Vec<(Duration, f32)>) -> PanAutomation<Self>
     where
         Self: Sized,
+        Self::Item: Sample,
     {
-        // Added Limits to prevent the AGC from blowing up. ;)
-        const MIN_ATTACK_TIME: f32 = 10.0;
-        const MIN_RELEASE_TIME: f32 = 10.0;
-        let attack_time = attack_time.min(MIN_ATTACK_TIME);
-        let release_time = release_time.min(MIN_RELEASE_TIME);
-
-        agc::automatic_gain_control(
-            self,
-            target_level,
-            attack_time,
-            release_time,
-            absolute_max_gain,
-        )
+        pan_automation::automate_pan(self, keyframes)
     }
 
-    /// Mixes this sound fading out with another sound fading in for the given duration.
+    /// Widens a stereo sound by delaying its right channel by `delay_ms` milliseconds.
     ///
-    /// Only the crossfaded portion (beginning of self, beginning of other) is returned.
+    /// Thanks to the precedence (Haas) effect the ear still hears the sound come from
+    /// the earlier side, but as wider. Delays between 5 and 30 ms work best, longer
+    /// ones are heard as an echo. Mono sounds become stereo, for sounds with more
+    /// channels only the second is delayed. The last `delay_ms` of the right channel
+    /// are cut off at the end of the sound.
     #[inline]
-    fn take_crossfade_with<S: Source>(self, other: S, duration: Duration) -> Crossfade<Self, S>
+    fn haas_widen(self, delay_ms: f32) -> HaasWiden<Self>
     where
         Self: Sized,
-        Self::Item: FromSample<S::Item>,
-        <S as Iterator>::Item: Sample,
+        Self::Item: Sample,
     {
-        crossfade::crossfade(self, other, duration)
+        haas::haas_widen(self, delay_ms)
     }
 
-    /// Fades in the sound.
+    /// Removes what is panned to the center of a stereo sound, usually the vocals,
+    /// for example for karaoke.
+    ///
+    /// Half of the left channel minus the right one is played on both channels, which
+    /// cancels everything that is equally loud on both sides. The result can also be
+    /// played as one channel with [`RemoveCenter::with_mono_output`]. Bass and
+    /// cymbals are often in the center too, [`RemoveCenter::with_preserved_bands`]
+    /// keeps them. Sounds that are not stereo play unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rodio::buffer::SamplesBuffer;
+    /// use rodio::Source;
+    ///
+    /// let song = SamplesBuffer::new(2, 44100, vec![0.0f32; 44100]);
+    /// let karaoke = song.remove_center().with_preserved_bands(120.0, 8000.0);
+    /// ```
     #[inline]
-    fn fade_in(self, duration: Duration) -> FadeIn<Self>
+    fn remove_center(self) -> RemoveCenter<Self>
     where
         Self: Sized,
+        Self::Item: Sample + FromSample<f32>,
     {
-        fadein::fadein(self, duration)
+        remove_center::remove_center(self)
     }
 
-    /// Fades out the sound.
+    /// Converts the sound to another sample rate.
+    ///
+    /// Unlike [`speed`](Source::speed) this keeps the pitch and duration of the sound,
+    /// only the number of samples per second changes. See [`ResampleConfig`] for the
+    /// quality settings.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rodio::source::{ResampleConfig, SineWave, Source};
+    ///
+    /// let source = SineWave::new(440.0).resample(44100, ResampleConfig::accurate());
+    /// assert_eq!(source.sample_rate(), 44100);
+    /// ```
     #[inline]
-    fn fade_out(self, duration: Duration) -> FadeOut<Self>
+    fn resample(self, target_rate: SampleRate, config: ResampleConfig) -> Resample<Self>
     where
         Self: Sized,
     {
-        fadeout::fadeout(self, duration)
+        resample::resample(self, target_rate, config)
     }
 
-    /// Applies a linear gain ramp to the sound.
+    /// Converts the sound to another sample rate like [`Source::resample`], with
+    /// buffers allocated up front for `expected_chunk` input frames.
     ///
-    /// If `clamp_end` is `true`, all samples subsequent to the end of the ramp
-    /// will be scaled by the `end_value`. If `clamp_end` is `false`, all
-    /// subsequent samples will not have any scaling applied.
+    /// The output is the same, only allocation differs. Useful when the sound is
+    /// read in blocks of a known size, for example by an output device with a fixed
+    /// buffer size.
     #[inline]
-    fn linear_gain_ramp(
+    fn resample_hinted(
         self,
-        duration: Duration,
-        start_value: f32,
-        end_value: f32,
-        clamp_end: bool,
-    ) -> LinearGainRamp<Self>
+        target_rate: SampleRate,
+        config: ResampleConfig,
+        expected_chunk: usize,
+    ) -> Resample<Self>
     where
         Self: Sized,
     {
-        linear_ramp::linear_gain_ramp(self, duration, start_value, end_value, clamp_end)
+        resample::resample_hinted(self, target_rate, config, expected_chunk)
     }
 
-    /// Calls the `access` closure on `Self` the first time the source is iterated and every
-    /// time `period` elapses.
+    /// Converts the sound to `factor` times its own sample rate, see
+    /// [`Source::resample`].
     ///
-    /// Later changes in either `sample_rate()` or `channels_count()` won't be reflected in
-    /// the rate of access.
+    /// Effects that create harmonics, such as distortion or clipping, alias less when
+    /// they run at a higher rate. Bring the sound back down with
+    /// [`Source::downsample`] afterwards. The factor applies to the sample rate the
+    /// sound has when this is called.
     ///
-    /// The rate is based on playback speed, so both the following will call `access` when the
-    /// same samples are reached:
-    /// `periodic_access(Duration::from_secs(1), ...).speed(2.0)`
-    /// `speed(2.0).periodic_access(Duration::from_secs(2), ...)`
+    /// # Example
+    ///
+    /// ```
+    /// use std::num::NonZero;
+    /// use rodio::source::{ResampleConfig, SineWave, Source};
+    ///
+    /// let factor = NonZero::new(4).unwrap();
+    /// let source = SineWave::new(440.0).oversample(factor, ResampleConfig::fast());
+    /// assert_eq!(source.sample_rate(), 4 * 48000);
+    /// ```
     #[inline]
-    fn periodic_access<F>(self, period: Duration, access: F) -> PeriodicAccess<Self, F>
+    fn oversample(self, factor: NonZero<u32>, config: ResampleConfig) -> Resample<Self>
     where
         Self: Sized,
-        F: FnMut(&mut Self),
     {
-        periodic::periodic(self, period, access)
+        resample::oversample(self, factor, config)
     }
 
-    /// Changes the play speed of the sound. Does not adjust the samples, only the playback speed.
-    ///
-    /// # Note:
-    /// 1. **Increasing the speed will increase the pitch by the same factor**
-    /// - If you set the speed to 0.5 this will halve the frequency of the sound
-    ///   lowering its pitch.
-    /// - If you set the speed to 2 the frequency will double raising the
-    ///   pitch of the sound.
-    /// 2. **Change in the speed affect the total duration inversely**
-    /// - If you set the speed to 0.5, the total duration will be twice as long.
-    /// - If you set the speed to 2 the total duration will be halve of what it
-    ///   was.
-    ///
-    /// See [`Speed`] for details
+    /// Converts the sound to its own sample rate divided by `factor`, rounded down
+    /// but at least 1 Hz, see [`Source::resample`]. Undoes [`Source::oversample`].
     #[inline]
-    fn speed(self, ratio: f32) -> Speed<Self>
+    fn downsample(self, factor: NonZero<u32>, config: ResampleConfig) -> Resample<Self>
     where
         Self: Sized,
     {
-        speed::speed(self, ratio)
+        resample::downsample(self, factor, config)
+    }
+
+    /// Converts the sound to the sample rate of `mixer`, so the mixer does not
+    /// have to convert it.
+    #[inline]
+    fn resample_to<S>(self, mixer: &Mixer<S>, config: ResampleConfig) -> Resample<Self>
+    where
+        Self: Sized,
+        S: Sample + Send + 'static,
+    {
+        resample::resample(self, mixer.sample_rate(), config)
     }
 
     /// Adds a basic reverb effect.
@@ -458,6 +1501,101 @@ where
         self.mix(echo)
     }
 
+    /// Replaces samples too small to be audible by silence.
+    ///
+    /// Long decaying tails, such as those produced by filters with feedback, end up
+    /// as denormal floats which are very slow to process on some CPUs. Place this
+    /// after such a source to keep the effects that follow it fast. The filters in
+    /// this crate already do this internally.
+    #[inline]
+    fn flush_denormals(self) -> FlushDenormals<Self>
+    where
+        Self: Sized,
+    {
+        denormal::flush_denormals(self)
+    }
+
+    /// Requantizes the samples to the given bit depth while keeping them as `f32`.
+    ///
+    /// Unlike an effect like a bitcrusher this rounds every sample to the nearest
+    /// value that can be stored with `bits`, exactly what converting to an integer
+    /// format would do. Use [`BitDepthReduce::with_dither`] to add dither.
+    #[inline]
+    fn to_bit_depth(self, bits: BitDepth) -> BitDepthReduce<Self>
+    where
+        Self: Sized,
+        Self::Item: Sample,
+    {
+        bit_depth::to_bit_depth(self, bits)
+    }
+
+    /// Multiplies every block of `block` frames of the sound with `window`.
+    ///
+    /// Blocks start at the beginning of the sound, each channel is windowed the same.
+    /// Useful before a spectral analysis of the blocks, for example through
+    /// [`Source::planar_chunks`] with the same block size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `block` is zero.
+    #[inline]
+    fn apply_window(self, window: WindowFunction, block: usize) -> Windowed<Self>
+    where
+        Self: Sized,
+        Self::Item: Sample,
+    {
+        windowed::windowed(self, window, block)
+    }
+
+    /// Splits the sound into [`SamplesBuffer`]s of `frames_per_chunk` frames each.
+    ///
+    /// Each buffer carries the channel count and sample rate of its samples, a chunk
+    /// ends early where the format of the sound changes. The last chunk can be
+    /// shorter. Useful to process the sound in blocks or send it over the network.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `frames_per_chunk` is zero.
+    #[inline]
+    fn chunked(self, frames_per_chunk: usize) -> Chunked<Self>
+    where
+        Self: Sized,
+        Self::Item: Sample,
+    {
+        chunked::chunked(self, frames_per_chunk)
+    }
+
+    /// Splits the sound into blocks of `frames` frames with a separate buffer for each
+    /// channel, for processing code that wants planar rather than interleaved samples.
+    ///
+    /// Blocks end early at span boundaries so all frames in a block share one channel
+    /// count and sample rate. The samples are converted to `f32`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `frames` is zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rodio::buffer::SamplesBuffer;
+    /// use rodio::Source;
+    ///
+    /// let source = SamplesBuffer::new(2, 44100, vec![0.1, -0.1, 0.2, -0.2]);
+    /// for block in source.planar_chunks(1024) {
+    ///     let (left, right) = (&block[0], &block[1]);
+    ///     assert_eq!(left.len(), right.len());
+    /// }
+    /// ```
+    #[inline]
+    fn planar_chunks(self, frames: usize) -> PlanarChunks<Self>
+    where
+        Self: Sized,
+        Self::Item: Sample,
+    {
+        planar::planar_chunks(self, frames)
+    }
+
     /// Converts the samples of this source to another type.
     #[inline]
     fn convert_samples<D>(self) -> SamplesConverter<Self, D>
@@ -602,6 +1740,14 @@ pub enum SeekError {
     #[cfg(feature = "wav")]
     /// The hound (wav) decoder ran into an issue
     HoundDecoder(std::io::Error),
+    #[cfg(feature = "minimp3")]
+    /// The minimp3 (mp3) decoder could not rewind its data
+    Minimp3Decoder(std::io::Error),
+    /// There is no source at this position in the queue of a sink
+    NoSuchSource {
+        /// The position that was asked for
+        index: usize,
+    },
     // Prefer adding an enum variant to using this. It's meant for end users their
     // own `try_seek` implementations.
     /// Any other error probably in a custom Source
@@ -621,6 +1767,11 @@ impl fmt::Display for SeekError {
             SeekError::SymphoniaDecoder(err) => write!(f, "Error seeking: {}", err),
             #[cfg(feature = "wav")]
             SeekError::HoundDecoder(err) => write!(f, "Error seeking in wav source: {}", err),
+            #[cfg(feature = "minimp3")]
+            SeekError::Minimp3Decoder(err) => write!(f, "Error seeking in mp3 source: {}", err),
+            SeekError::NoSuchSource { index } => {
+                write!(f, "There is no source at position {} in the queue", index)
+            }
             SeekError::Other(_) => write!(f, "An error occurred"),
         }
     }
@@ -633,6 +1784,9 @@ impl std::error::Error for SeekError {
             SeekError::SymphoniaDecoder(err) => Some(err),
             #[cfg(feature = "wav")]
             SeekError::HoundDecoder(err) => Some(err),
+            #[cfg(feature = "minimp3")]
+            SeekError::Minimp3Decoder(err) => Some(err),
+            SeekError::NoSuchSource { .. } => None,
             SeekError::Other(err) => Some(err.as_ref()),
         }
     }
@@ -655,6 +1809,9 @@ impl SeekError {
             SeekError::SymphoniaDecoder(_) => false,
             #[cfg(feature = "wav")]
             SeekError::HoundDecoder(_) => false,
+            #[cfg(feature = "minimp3")]
+            SeekError::Minimp3Decoder(_) => false,
+            SeekError::NoSuchSource { .. } => true,
             SeekError::Other(_) => false,
         }
     }
@@ -683,6 +1840,11 @@ macro_rules! source_pointer_impl {
                 (**self).total_duration()
             }
 
+            #[inline]
+            fn latency(&self) -> Duration {
+                (**self).latency()
+            }
+
             #[inline]
             fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
                 (**self).try_seek(pos)
@@ -698,3 +1860,21 @@ source_pointer_impl!(<S> Source for Box<dyn Source<Item = S> + Send> where S: Sa
 source_pointer_impl!(<S> Source for Box<dyn Source<Item = S> + Send + Sync> where S: Sample,);
 
 source_pointer_impl!(<'a, S, C> Source for &'a mut C where S: Sample, C: Source<Item = S>,);
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::buffer::SamplesBuffer;
+    use crate::source::{SineWave, Source};
+
+    #[test]
+    fn integrated_rms_and_peak_of_sine() {
+        let sine = || SineWave::new(1000.0).take_duration(Duration::from_secs(1));
+        assert!((sine().integrated_rms() - 0.5f32.sqrt()).abs() < 1e-3);
+        assert!((sine().integrated_peak() - 1.0).abs() < 1e-3);
+
+        let empty = SamplesBuffer::new(1, 48000, Vec::<f32>::new());
+        assert_eq!(empty.integrated_rms(), 0.0);
+    }
+}
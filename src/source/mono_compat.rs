@@ -0,0 +1,67 @@
+use crate::{Sample, Source};
+
+/// Internal function that measures how well a source survives being summed to mono,
+/// see [`Source::mono_compatibility`].
+pub fn mono_compatibility<I>(mut input: I) -> f32
+where
+    I: Source,
+    I::Item: Sample,
+{
+    let mut mono_energy = 0.0f64;
+    let mut channel_energy = 0.0f64;
+    loop {
+        let channels = input.channels() as usize;
+        let Some(left) = input.next() else {
+            break;
+        };
+        let left = left.to_f32() as f64;
+        let right = if channels > 1 {
+            match input.next() {
+                Some(right) => right.to_f32() as f64,
+                None => break,
+            }
+        } else {
+            left
+        };
+        // Only the first two channels are compared.
+        input
+            .by_ref()
+            .take(channels.saturating_sub(2))
+            .for_each(drop);
+
+        let mono = (left + right) / 2.0;
+        mono_energy += mono * mono;
+        channel_energy += (left * left + right * right) / 2.0;
+    }
+
+    if channel_energy == 0.0 {
+        1.0
+    } else {
+        (mono_energy / channel_energy) as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::buffer::SamplesBuffer;
+    use crate::source::SineWave;
+    use crate::Source;
+
+    fn stereo(right_gain: f32) -> SamplesBuffer<f32> {
+        let samples = SineWave::new(440.0)
+            .take(48000)
+            .flat_map(|s| [s, s * right_gain])
+            .collect::<Vec<f32>>();
+        SamplesBuffer::new(2, 48000, samples)
+    }
+
+    #[test]
+    fn opposite_channels_cancel() {
+        assert!(stereo(-1.0).mono_compatibility() < 1e-6);
+    }
+
+    #[test]
+    fn identical_channels_survive() {
+        assert!((stereo(1.0).mono_compatibility() - 1.0).abs() < 1e-6);
+    }
+}
@@ -90,12 +90,20 @@ impl Source for WhiteNoise {
 ///
 /// The output of the source is the result of taking the output of the `WhiteNoise` source and
 /// filtering it according to a weighted-sum of seven FIR filters after [Paul Kellett's
-/// method][pk_method] from *musicdsp.org*.
+/// method][pk_method] from *musicdsp.org*. The filter falls at 3 dB per octave for about 11
+/// octaves below the Nyquist frequency, see [`PinkNoise::with_octaves`] to trade CPU for a wider
+/// range.
 ///
 /// [pk_method]: https://www.musicdsp.org/en/latest/Filters/76-pink-noise-filter.html
 pub struct PinkNoise {
     white_noise: WhiteNoise,
-    b: [f32; 7],
+    filter: PinkFilter,
+}
+
+enum PinkFilter {
+    Kellett([f32; 7]),
+    // Random rows where row `n` is redrawn every `2^(n + 1)` samples.
+    VossMcCartney { rows: Vec<f32>, counter: u64 },
 }
 
 impl PinkNoise {
@@ -103,9 +111,29 @@ impl PinkNoise {
     pub fn new(sample_rate: SampleRate) -> Self {
         Self {
             white_noise: WhiteNoise::new(sample_rate),
-            b: [0.0f32, 0.0f32, 0.0f32, 0.0f32, 0.0f32, 0.0f32, 0.0f32],
+            filter: PinkFilter::Kellett([0.0f32; 7]),
         }
     }
+
+    /// Switches to the Voss-McCartney algorithm with `octaves` rows of random values.
+    ///
+    /// The spectrum falls at 3 dB per octave for `octaves` octaves below the Nyquist frequency
+    /// and is flat below that. Every row costs an addition per sample, so more octaves reach
+    /// deeper bass at the expense of CPU. Going beyond 11 octaves extends the range of the
+    /// default filter.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `octaves` is zero or larger than 32.
+    pub fn with_octaves(mut self, octaves: u32) -> Self {
+        assert!(
+            (1..=32).contains(&octaves),
+            "the number of octaves must be between 1 and 32"
+        );
+        let rows = self.white_noise.by_ref().take(octaves as usize).collect();
+        self.filter = PinkFilter::VossMcCartney { rows, counter: 0 };
+        self
+    }
 }
 
 impl Iterator for PinkNoise {
@@ -113,23 +141,30 @@ impl Iterator for PinkNoise {
 
     fn next(&mut self) -> Option<Self::Item> {
         let white = self.white_noise.next().unwrap();
-        self.b[0] = 0.99886 * self.b[0] + white * 0.0555179;
-        self.b[1] = 0.99332 * self.b[1] + white * 0.0750759;
-        self.b[2] = 0.969 * self.b[2] + white * 0.153852;
-        self.b[3] = 0.8665 * self.b[3] + white * 0.3104856;
-        self.b[4] = 0.550 * self.b[4] + white * 0.5329522;
-        self.b[5] = -0.7616 * self.b[5] - white * 0.016898;
-
-        let pink = self.b[0]
-            + self.b[1]
-            + self.b[2]
-            + self.b[3]
-            + self.b[4]
-            + self.b[5]
-            + self.b[6]
-            + white * 0.5362;
-
-        self.b[6] = white * 0.115926;
+        let pink = match &mut self.filter {
+            PinkFilter::Kellett(b) => {
+                b[0] = 0.99886 * b[0] + white * 0.0555179;
+                b[1] = 0.99332 * b[1] + white * 0.0750759;
+                b[2] = 0.969 * b[2] + white * 0.153852;
+                b[3] = 0.8665 * b[3] + white * 0.3104856;
+                b[4] = 0.550 * b[4] + white * 0.5329522;
+                b[5] = -0.7616 * b[5] - white * 0.016898;
+
+                let pink = b[0] + b[1] + b[2] + b[3] + b[4] + b[5] + b[6] + white * 0.5362;
+
+                b[6] = white * 0.115926;
+                pink
+            }
+            PinkFilter::VossMcCartney { rows, counter } => {
+                *counter = counter.wrapping_add(1);
+                // At most one row changes per sample.
+                if let Some(row) = rows.get_mut(counter.trailing_zeros() as usize) {
+                    *row = self.white_noise.next().unwrap();
+                }
+                // The white sample fills the top octave.
+                (rows.iter().sum::<f32>() + white) / (rows.len() + 1) as f32
+            }
+        };
 
         Some(pink)
     }
@@ -158,3 +193,58 @@ impl Source for PinkNoise {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::TAU;
+
+    use super::{PinkNoise, WhiteNoise};
+
+    fn seeded_pink() -> PinkNoise {
+        PinkNoise {
+            white_noise: WhiteNoise::new_with_seed(44100, 7),
+            ..PinkNoise::new(44100)
+        }
+    }
+
+    // Measures the slope in dB per octave between 2^-14 and 2^-11 of the Nyquist frequency.
+    fn low_slope(noise: PinkNoise) -> f32 {
+        const BLOCK: usize = 512;
+        const SEGMENT: usize = 256;
+        const SEGMENTS: usize = 32;
+        // Averaging blocks of samples keeps the low octaves and reduces the work.
+        let decimated: Vec<f32> = noise
+            .take(BLOCK * SEGMENT * SEGMENTS)
+            .collect::<Vec<_>>()
+            .chunks(BLOCK)
+            .map(|block| block.iter().sum::<f32>() / BLOCK as f32)
+            .collect();
+
+        let power = |bin: usize| -> f32 {
+            let mut total = 0.0;
+            for segment in decimated.chunks(SEGMENT) {
+                let (mut re, mut im) = (0.0, 0.0);
+                for (n, sample) in segment.iter().enumerate() {
+                    let window = 0.5 - 0.5 * (TAU * n as f32 / SEGMENT as f32).cos();
+                    let phase = TAU * (bin * n % SEGMENT) as f32 / SEGMENT as f32;
+                    re += sample * window * phase.cos();
+                    im += sample * window * phase.sin();
+                }
+                total += re * re + im * im;
+            }
+            total
+        };
+        let power_low: f32 = (3..=5).map(power).sum();
+        let power_high: f32 = (28..=36).map(power).sum::<f32>() * 3.0 / 9.0;
+        10.0 * (power_high / power_low).log10() / 3.0
+    }
+
+    #[test]
+    fn more_octaves_reach_lower() {
+        let default = low_slope(seeded_pink());
+        let octaves = low_slope(seeded_pink().with_octaves(16));
+        // The default filter flattens out this far down.
+        assert!((octaves + 3.0).abs() < (default + 3.0).abs());
+        assert!((octaves + 3.0).abs() < 0.5);
+    }
+}
@@ -0,0 +1,151 @@
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Internal function that builds an `OnFormatChange` object.
+pub fn on_format_change<I, F>(input: I, callback: F) -> OnFormatChange<I, F>
+where
+    I: Source,
+    I::Item: Sample,
+    F: FnMut(SampleRate, ChannelCount),
+{
+    OnFormatChange {
+        input,
+        callback,
+        format: None,
+    }
+}
+
+/// Calls a function whenever the sample rate or channel count of a sound changes,
+/// see [`Source::on_format_change`].
+#[derive(Clone, Debug)]
+pub struct OnFormatChange<I, F> {
+    input: I,
+    callback: F,
+    // Format of the last sample played, `None` before the first one.
+    format: Option<(SampleRate, ChannelCount)>,
+}
+
+impl<I, F> OnFormatChange<I, F> {
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+}
+
+impl<I, F> Iterator for OnFormatChange<I, F>
+where
+    I: Source,
+    I::Item: Sample,
+    F: FnMut(SampleRate, ChannelCount),
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        let sample = self.input.next()?;
+        // Checked after every sample rather than at span boundaries, a queue only
+        // switches to the next sound once it is asked for a sample.
+        let format = (self.input.sample_rate(), self.input.channels());
+        if let Some(previous) = self.format.replace(format) {
+            if previous != format {
+                (self.callback)(format.0, format.1);
+            }
+        }
+        Some(sample)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<I, F> ExactSizeIterator for OnFormatChange<I, F>
+where
+    I: Source + ExactSizeIterator,
+    I::Item: Sample,
+    F: FnMut(SampleRate, ChannelCount),
+{
+}
+
+impl<I, F> Source for OnFormatChange<I, F>
+where
+    I: Source,
+    I::Item: Sample,
+    F: FnMut(SampleRate, ChannelCount),
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use crate::buffer::SamplesBuffer;
+    use crate::queue;
+    use crate::Source;
+
+    #[test]
+    fn fires_once_when_the_rate_changes() {
+        let (input, output) = queue::queue(false);
+        input.append(SamplesBuffer::new(1, 44100, vec![0.0f32; 10]));
+        input.append(SamplesBuffer::new(1, 48000, vec![0.0f32; 10]));
+
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        let seen = changes.clone();
+        let mut source = output.on_format_change(move |rate, channels| {
+            seen.lock().unwrap().push((rate, channels));
+        });
+
+        assert_eq!(source.by_ref().take(10).count(), 10);
+        assert!(changes.lock().unwrap().is_empty());
+        assert_eq!(source.by_ref().take(1).count(), 1);
+        assert_eq!(*changes.lock().unwrap(), [(48000, 1)]);
+        assert_eq!(source.count(), 9);
+        assert_eq!(changes.lock().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,237 @@
+use std::f32::consts::FRAC_PI_4;
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::math;
+use crate::{Sample, Source};
+
+/// Internal function that builds a `PanAutomation` object.
+pub fn automate_pan<I>(input: I, mut keyframes: Vec<(Duration, f32)>) -> PanAutomation<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    keyframes.sort_by_key(|&(time, _)| time);
+    for (_, pan) in &mut keyframes {
+        *pan = pan.clamp(-1.0, 1.0);
+    }
+    PanAutomation {
+        input,
+        keyframes,
+        next_keyframe: 0,
+        frame: 0,
+        gains: [1.0, 1.0],
+        channel: 0,
+        right: None,
+    }
+}
+
+/// Moves the sound between the left and right speaker along keyframes, see
+/// [`Source::automate_pan`].
+#[derive(Clone, Debug)]
+pub struct PanAutomation<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    input: I,
+    // Times and pan positions, sorted by time.
+    keyframes: Vec<(Duration, f32)>,
+    // Index of the first keyframe after the current frame.
+    next_keyframe: usize,
+    // Frames played since the start.
+    frame: u64,
+    // Left and right gain for the current frame.
+    gains: [f32; 2],
+    // Channel of the next input sample.
+    channel: usize,
+    // Right sample of a mono input that still has to be played.
+    right: Option<I::Item>,
+}
+
+impl<I> PanAutomation<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    /// Returns the keyframes, sorted by time.
+    #[inline]
+    pub fn keyframes(&self) -> &[(Duration, f32)] {
+        &self.keyframes
+    }
+
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+
+    // Pan position at `time`, held before the first and after the last keyframe.
+    fn pan_at(&mut self, time: Duration) -> f32 {
+        while self
+            .keyframes
+            .get(self.next_keyframe)
+            .is_some_and(|&(key_time, _)| key_time <= time)
+        {
+            self.next_keyframe += 1;
+        }
+        let previous = self.next_keyframe.checked_sub(1).map(|i| self.keyframes[i]);
+        match (previous, self.keyframes.get(self.next_keyframe)) {
+            (Some((from_time, from)), Some(&(to_time, to))) => {
+                let t = (time - from_time).as_secs_f32() / (to_time - from_time).as_secs_f32();
+                from + (to - from) * t
+            }
+            (Some((_, pan)), None) | (None, Some(&(_, pan))) => pan,
+            (None, None) => 0.0,
+        }
+    }
+
+    fn start_frame(&mut self) {
+        let time = math::frames_to_duration(self.frame, self.input.sample_rate());
+        let pan = self.pan_at(time);
+        // Constant power: the angle goes from 0 (left) to 90 degrees (right).
+        let angle = (pan + 1.0) * FRAC_PI_4;
+        self.gains = [angle.cos(), angle.sin()];
+        self.frame += 1;
+    }
+}
+
+impl<I> Iterator for PanAutomation<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        if let Some(right) = self.right.take() {
+            return Some(right);
+        }
+
+        let sample = self.input.next()?;
+        if self.channel == 0 {
+            self.start_frame();
+        }
+        let channels = self.input.channels() as usize;
+        if channels == 1 {
+            self.right = Some(sample.amplify(self.gains[1]));
+            return Some(sample.amplify(self.gains[0]));
+        }
+
+        let gain = self.gains.get(self.channel).copied().unwrap_or(1.0);
+        self.channel = (self.channel + 1) % channels;
+        Some(sample.amplify(gain))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.input.size_hint();
+        let extra = self.right.is_some() as usize;
+        if self.input.channels() == 1 {
+            (
+                lower.saturating_mul(2).saturating_add(extra),
+                upper.and_then(|upper| upper.checked_mul(2)?.checked_add(extra)),
+            )
+        } else {
+            (lower, upper)
+        }
+    }
+}
+
+impl<I> ExactSizeIterator for PanAutomation<I>
+where
+    I: Source + ExactSizeIterator,
+    I::Item: Sample,
+{
+}
+
+impl<I> Source for PanAutomation<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        let extra = self.right.is_some() as usize;
+        match self.input.current_span_len() {
+            Some(len) if self.input.channels() == 1 => {
+                Some(len.saturating_mul(2).saturating_add(extra))
+            }
+            len => len,
+        }
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels().max(2)
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.frame = math::duration_to_frames(pos, self.input.sample_rate());
+        self.next_keyframe = self.keyframes.partition_point(|&(time, _)| time <= pos);
+        self.channel = 0;
+        self.right = None;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::buffer::SamplesBuffer;
+    use crate::Source;
+
+    #[test]
+    fn moves_from_left_to_right() {
+        let input = SamplesBuffer::new(1, 1000, vec![1.0f32; 1500]);
+        let panned =
+            input.automate_pan(vec![(Duration::from_secs(1), 1.0), (Duration::ZERO, -1.0)]);
+        assert_eq!(panned.channels(), 2);
+
+        let frames: Vec<f32> = panned.collect();
+        let balance: Vec<f32> = frames.chunks(2).map(|f| f[1] - f[0]).collect();
+        assert_eq!(balance.len(), 1500);
+
+        assert!(balance[0] < -0.99);
+        assert!(balance[500].abs() < 1e-6);
+        assert!(balance[..1000].windows(2).all(|w| w[1] > w[0]));
+        // Holds the last keyframe.
+        assert!(balance[1000..].iter().all(|&b| b > 0.99));
+        for frame in frames.chunks(2) {
+            let power = frame[0] * frame[0] + frame[1] * frame[1];
+            assert!((power - 1.0).abs() < 1e-4);
+        }
+    }
+}
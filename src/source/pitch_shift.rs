@@ -0,0 +1,409 @@
+use std::f32::consts::TAU;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use dasp_sample::FromSample;
+use realfft::num_complex::Complex;
+use realfft::{ComplexToReal, RealFftPlanner, RealToComplex};
+
+use super::{SeekError, WindowFunction};
+use crate::common::{ChannelCount, SampleRate};
+use crate::{math, Sample, Source};
+
+// Frames analysed at once, about 43 ms at 48 kHz.
+const FFT_SIZE: usize = 2048;
+// Number of blocks that overlap every frame.
+const OVERLAP: usize = 4;
+const HOP: usize = FFT_SIZE / OVERLAP;
+// Frames between a frame going in and coming out again.
+const DELAY: usize = FFT_SIZE;
+// Sum of the squared Hann window over the overlapping blocks.
+const WINDOW_GAIN: f32 = 1.5;
+
+/// Internal function that builds a `PitchShift` object.
+pub fn pitch_shift<I>(input: I, semitones: f32) -> PitchShift<I>
+where
+    I: Source,
+    I::Item: Sample + FromSample<f32>,
+{
+    PitchShift {
+        input,
+        ratio: 2f32.powf(semitones / 12.0),
+        vocoder: Vocoder::new(),
+        channels: Vec::new(),
+        pos: 0,
+        delay_left: DELAY,
+        flush_left: DELAY,
+        frame: Vec::new(),
+        frame_pos: 0,
+    }
+}
+
+/// Changes the pitch of a sound without changing its duration, see
+/// [`Source::pitch_shift`].
+#[derive(Clone)]
+pub struct PitchShift<I>
+where
+    I: Source,
+    I::Item: Sample + FromSample<f32>,
+{
+    input: I,
+    ratio: f32,
+    vocoder: Vocoder,
+    channels: Vec<Channel>,
+    // Frames of the current hop that were read.
+    pos: usize,
+    // Frames of delay that still have to be dropped from the output.
+    delay_left: usize,
+    // Frames of silence that still have to be fed in once the input ended, to play the
+    // frames still in the blocks.
+    flush_left: usize,
+    // The frame being played.
+    frame: Vec<I::Item>,
+    frame_pos: usize,
+}
+
+impl<I> fmt::Debug for PitchShift<I>
+where
+    I: Source + fmt::Debug,
+    I::Item: Sample + FromSample<f32>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PitchShift")
+            .field("input", &self.input)
+            .field("ratio", &self.ratio)
+            .finish_non_exhaustive()
+    }
+}
+
+// The FFTs and the buffers shared by all channels.
+#[derive(Clone)]
+struct Vocoder {
+    forward: Arc<dyn RealToComplex<f32>>,
+    inverse: Arc<dyn ComplexToReal<f32>>,
+    window: Vec<f32>,
+    block: Vec<f32>,
+    spectrum: Vec<Complex<f32>>,
+    // Magnitude and frequency in bins of every bin after shifting.
+    magnitudes: Vec<f32>,
+    frequencies: Vec<f32>,
+}
+
+// The blocks and phases of one channel.
+#[derive(Clone)]
+struct Channel {
+    input: Vec<f32>,
+    // The frames that are ready to be played.
+    output: Vec<f32>,
+    // Sum of the overlapping blocks that were transformed back.
+    sum: Vec<f32>,
+    last_phase: Vec<f32>,
+    phase: Vec<f32>,
+}
+
+impl Channel {
+    fn new() -> Self {
+        Channel {
+            input: vec![0.0; FFT_SIZE],
+            output: vec![0.0; HOP],
+            sum: vec![0.0; FFT_SIZE],
+            last_phase: vec![0.0; FFT_SIZE / 2 + 1],
+            phase: vec![0.0; FFT_SIZE / 2 + 1],
+        }
+    }
+}
+
+impl Vocoder {
+    fn new() -> Self {
+        let mut planner = RealFftPlanner::<f32>::new();
+        let forward = planner.plan_fft_forward(FFT_SIZE);
+        let inverse = planner.plan_fft_inverse(FFT_SIZE);
+        // The periodic Hann window is the symmetric one that is one frame longer.
+        let window = (0..FFT_SIZE)
+            .map(|frame| WindowFunction::Hann.gain(frame, FFT_SIZE + 1))
+            .collect();
+        Vocoder {
+            block: forward.make_input_vec(),
+            spectrum: forward.make_output_vec(),
+            magnitudes: vec![0.0; FFT_SIZE / 2 + 1],
+            frequencies: vec![0.0; FFT_SIZE / 2 + 1],
+            forward,
+            inverse,
+            window,
+        }
+    }
+
+    // Shifts the block of `channel` and moves it on by one hop.
+    fn process(&mut self, channel: &mut Channel, ratio: f32) {
+        // Phase a bin advances by per hop for every bin it is above zero.
+        let advance = TAU / OVERLAP as f32;
+
+        for ((block, input), window) in self.block.iter_mut().zip(&channel.input).zip(&self.window)
+        {
+            *block = input * window;
+        }
+        self.forward
+            .process(&mut self.block, &mut self.spectrum)
+            .expect("the buffers are made by the FFT");
+
+        self.magnitudes.fill(0.0);
+        self.frequencies.fill(0.0);
+        for (bin, value) in self.spectrum.iter().enumerate() {
+            let phase = value.arg();
+            let mut deviation = phase - channel.last_phase[bin] - bin as f32 * advance;
+            channel.last_phase[bin] = phase;
+            deviation -= TAU * (deviation / TAU).round();
+            // The frequency in bins of what is in this bin, from its change in phase.
+            let frequency = bin as f32 + deviation / advance;
+
+            let target = (bin as f32 * ratio).round() as usize;
+            if let Some(magnitude) = self.magnitudes.get_mut(target) {
+                *magnitude += value.norm();
+                self.frequencies[target] = frequency * ratio;
+            }
+        }
+
+        for (bin, value) in self.spectrum.iter_mut().enumerate() {
+            let deviation = (self.frequencies[bin] - bin as f32) * advance;
+            let phase = &mut channel.phase[bin];
+            *phase = (*phase + bin as f32 * advance + deviation).rem_euclid(TAU);
+            *value = Complex::from_polar(self.magnitudes[bin], *phase);
+        }
+        // The zero and Nyquist frequency have no phase in a real signal.
+        self.spectrum[0].im = 0.0;
+        self.spectrum[FFT_SIZE / 2].im = 0.0;
+        self.inverse
+            .process(&mut self.spectrum, &mut self.block)
+            .expect("the buffers are made by the FFT");
+
+        let scale = 1.0 / (FFT_SIZE as f32 * WINDOW_GAIN);
+        for ((sum, block), window) in channel.sum.iter_mut().zip(&self.block).zip(&self.window) {
+            *sum += block * window * scale;
+        }
+        channel.output.copy_from_slice(&channel.sum[..HOP]);
+        channel.sum.copy_within(HOP.., 0);
+        channel.sum[FFT_SIZE - HOP..].fill(0.0);
+        channel.input.copy_within(HOP.., 0);
+    }
+}
+
+impl<I> PitchShift<I>
+where
+    I: Source,
+    I::Item: Sample + FromSample<f32>,
+{
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+
+    // Reads the next frame and fills `frame` with the shifted frame that comes out.
+    // Returns `false` once all frames were played.
+    fn next_frame(&mut self) -> bool {
+        loop {
+            self.frame.clear();
+            self.frame_pos = 0;
+            let channels = self.input.channels() as usize;
+            if self.channels.len() != channels {
+                self.channels = vec![Channel::new(); channels];
+            }
+
+            let mut read = 0;
+            for channel in &mut self.channels {
+                let sample = match self.input.next() {
+                    Some(sample) => {
+                        read += 1;
+                        sample.to_f32()
+                    }
+                    None => 0.0,
+                };
+                channel.input[FFT_SIZE - HOP + self.pos] = sample;
+                let output = channel.output[self.pos];
+                self.frame.push(I::Item::from_sample_(output));
+            }
+            if read == 0 {
+                if self.flush_left == 0 {
+                    self.frame.clear();
+                    return false;
+                }
+                self.flush_left -= 1;
+            }
+
+            self.pos += 1;
+            if self.pos == HOP {
+                for channel in &mut self.channels {
+                    self.vocoder.process(channel, self.ratio);
+                }
+                self.pos = 0;
+            }
+
+            if self.delay_left > 0 {
+                self.delay_left -= 1;
+            } else {
+                return true;
+            }
+        }
+    }
+
+    // Samples still to be played besides the ones of the input.
+    fn buffered(&self) -> usize {
+        let frames = self.flush_left - self.delay_left;
+        frames * self.input.channels() as usize + self.frame.len() - self.frame_pos
+    }
+}
+
+impl<I> Iterator for PitchShift<I>
+where
+    I: Source,
+    I::Item: Sample + FromSample<f32>,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        if self.ratio == 1.0 {
+            return self.input.next();
+        }
+        if self.frame_pos >= self.frame.len() && !self.next_frame() {
+            return None;
+        }
+        let sample = self.frame[self.frame_pos];
+        self.frame_pos += 1;
+        Some(sample)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.input.size_hint();
+        (
+            lower.saturating_add(self.buffered()),
+            upper.and_then(|upper| upper.checked_add(self.buffered())),
+        )
+    }
+}
+
+impl<I> ExactSizeIterator for PitchShift<I>
+where
+    I: Source + ExactSizeIterator,
+    I::Item: Sample + FromSample<f32>,
+{
+}
+
+impl<I> Source for PitchShift<I>
+where
+    I: Source,
+    I::Item: Sample + FromSample<f32>,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        if self.ratio == 1.0 {
+            self.input.current_span_len()
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        // A frame only comes out once `DELAY` frames after it were read.
+        let delay = math::frames_to_duration(DELAY as u64, self.input.sample_rate());
+        self.input.latency() + delay
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.channels.clear();
+        self.pos = 0;
+        self.delay_left = DELAY;
+        self.flush_left = DELAY;
+        self.frame.clear();
+        self.frame_pos = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::buffer::SamplesBuffer;
+    use crate::source::{SineWave, Source};
+
+    fn loudest_bin(spectrum: &[f32]) -> usize {
+        (0..spectrum.len())
+            .max_by(|&a, &b| spectrum[a].total_cmp(&spectrum[b]))
+            .unwrap()
+    }
+
+    #[test]
+    fn reports_delay_as_latency() {
+        let source = SamplesBuffer::new(1, 48000, vec![0.0f32; 100]).pitch_shift(3.0);
+        assert_eq!(source.latency(), Duration::from_nanos(42_666_667));
+    }
+
+    #[test]
+    fn octave_up_doubles_frequency_and_keeps_length() {
+        let shifted: Vec<f32> = SineWave::new(440.0)
+            .take_duration(Duration::from_secs(1))
+            .pitch_shift(12.0)
+            .collect();
+        assert_eq!(shifted.len(), 48000);
+
+        // Bins are 10 Hz apart.
+        let spectrum = SamplesBuffer::new(1, 48000, shifted).power_spectrum(4800);
+        assert_eq!(loudest_bin(&spectrum), 88);
+    }
+
+    #[test]
+    fn shifts_channels_apart_and_restarts_on_seek() {
+        let samples: Vec<f32> = SineWave::new(400.0)
+            .zip(SineWave::new(1000.0))
+            .take(24000)
+            .flat_map(|(left, right)| [left, right])
+            .collect();
+        let mut shifted = SamplesBuffer::new(2, 48000, samples).pitch_shift(-12.0);
+        let first: Vec<f32> = shifted.by_ref().collect();
+        assert_eq!(first.len(), 48000);
+
+        shifted.try_seek(Duration::ZERO).unwrap();
+        let again: Vec<f32> = shifted.collect();
+        assert_eq!(again, first);
+
+        let channel = |index: usize| {
+            let samples: Vec<f32> = first.iter().skip(index).step_by(2).copied().collect();
+            SamplesBuffer::new(1, 48000, samples).power_spectrum(4800)
+        };
+        assert_eq!(loudest_bin(&channel(0)), 20);
+        assert_eq!(loudest_bin(&channel(1)), 50);
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{Sample, Source};
+
+/// Internal function that builds a `PlanarChunks` object.
+pub fn planar_chunks<I>(input: I, frames: usize) -> PlanarChunks<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    assert!(frames > 0, "a chunk needs at least one frame");
+    PlanarChunks { input, frames }
+}
+
+/// Iterator over blocks of a source with one buffer per channel, see
+/// [`Source::planar_chunks`].
+#[derive(Clone, Debug)]
+pub struct PlanarChunks<I> {
+    input: I,
+    frames: usize,
+}
+
+impl<I> PlanarChunks<I> {
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+}
+
+impl<I> Iterator for PlanarChunks<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = Vec<Vec<f32>>;
+
+    fn next(&mut self) -> Option<Vec<Vec<f32>>> {
+        let channels = self.input.channels() as usize;
+        // Chunks end at span boundaries so the format stays the same within a chunk.
+        let frames = match self.input.current_span_len() {
+            Some(0) | None => self.frames,
+            Some(len) => self.frames.min(len.div_ceil(channels)),
+        };
+
+        let mut chunk = vec![Vec::with_capacity(frames); channels];
+        'frames: for _ in 0..frames {
+            for (channel, buffer) in chunk.iter_mut().enumerate() {
+                match self.input.next() {
+                    Some(sample) => buffer.push(sample.to_f32()),
+                    None => {
+                        // Drop the incomplete frame.
+                        for buffer in &mut chunk[..channel] {
+                            buffer.pop();
+                        }
+                        break 'frames;
+                    }
+                }
+            }
+        }
+
+        if chunk[0].is_empty() {
+            None
+        } else {
+            Some(chunk)
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let samples_per_chunk = self.frames * self.input.channels() as usize;
+        let (lower, upper) = self.input.size_hint();
+        (
+            lower / samples_per_chunk,
+            upper.map(|upper| upper.div_ceil(samples_per_chunk)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::buffer::SamplesBuffer;
+    use crate::Source;
+
+    #[test]
+    fn splits_channels() {
+        let input = SamplesBuffer::new(
+            2,
+            48000,
+            vec![1.0, -1.0, 2.0, -2.0, 3.0, -3.0, 4.0, -4.0, 5.0, -5.0],
+        );
+        let chunks: Vec<Vec<Vec<f32>>> = input.planar_chunks(2).collect();
+        assert_eq!(
+            chunks,
+            vec![
+                vec![vec![1.0, 2.0], vec![-1.0, -2.0]],
+                vec![vec![3.0, 4.0], vec![-3.0, -4.0]],
+                vec![vec![5.0], vec![-5.0]],
+            ]
+        );
+    }
+}
@@ -0,0 +1,195 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::math;
+use crate::{Sample, Source};
+
+/// Internal function that builds a `Preloaded` object.
+pub fn preload<I>(mut input: I, duration: Duration) -> Preloaded<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    let channels = input.channels();
+    let sample_rate = input.sample_rate();
+    let frames = math::duration_to_frames(duration, sample_rate);
+    let len = usize::try_from(frames)
+        .unwrap_or(usize::MAX)
+        .saturating_mul(channels as usize);
+
+    let mut buffer = VecDeque::with_capacity(len.min(1 << 20));
+    // Stop early where the format changes, all buffered samples share one format.
+    while buffer.len() < len && (input.channels(), input.sample_rate()) == (channels, sample_rate) {
+        match input.next() {
+            Some(sample) => buffer.push_back(sample),
+            None => break,
+        }
+    }
+
+    Preloaded {
+        input,
+        buffer,
+        channels,
+        sample_rate,
+    }
+}
+
+/// Plays samples decoded ahead of time before the rest of the source, see
+/// [`Source::preload`].
+#[derive(Clone, Debug)]
+pub struct Preloaded<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    input: I,
+    buffer: VecDeque<I::Item>,
+    // Format of the samples in `buffer`.
+    channels: ChannelCount,
+    sample_rate: SampleRate,
+}
+
+impl<I> Preloaded<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    /// Returns the number of preloaded samples that have not been played yet.
+    #[inline]
+    pub fn buffered_len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Returns a reference to the inner source.
+    ///
+    /// The inner source is ahead of playback by the samples still buffered.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source. Samples that are still buffered are lost.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+}
+
+impl<I> Iterator for Preloaded<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        match self.buffer.pop_front() {
+            Some(sample) => Some(sample),
+            None => self.input.next(),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let buffered = self.buffer.len();
+        let (lower, upper) = self.input.size_hint();
+        (
+            lower.saturating_add(buffered),
+            upper.and_then(|upper| upper.checked_add(buffered)),
+        )
+    }
+}
+
+impl<I> ExactSizeIterator for Preloaded<I>
+where
+    I: Source + ExactSizeIterator,
+    I::Item: Sample,
+{
+}
+
+impl<I> Source for Preloaded<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        if self.buffer.is_empty() {
+            self.input.current_span_len()
+        } else {
+            Some(self.buffer.len())
+        }
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        if self.buffer.is_empty() {
+            self.input.channels()
+        } else {
+            self.channels
+        }
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        if self.buffer.is_empty() {
+            self.input.sample_rate()
+        } else {
+            self.sample_rate
+        }
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.buffer.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::sync_channel;
+    use std::time::Duration;
+
+    use crate::buffer::SamplesBuffer;
+    use crate::Source;
+
+    #[test]
+    fn decodes_ahead_on_construction() {
+        let samples: Vec<f32> = (0..400).map(|n| n as f32).collect();
+        // The tap sees every sample taken from the input.
+        let (tx, rx) = sync_channel(1000);
+        let input = SamplesBuffer::new(2, 1000, samples.clone()).tap(tx);
+
+        let mut preloaded = input.preload(Duration::from_millis(50));
+        assert_eq!(rx.try_iter().count(), 100);
+        assert_eq!(preloaded.buffered_len(), 100);
+
+        let first: Vec<f32> = preloaded.by_ref().take(100).collect();
+        assert_eq!(first, samples[..100]);
+        assert_eq!(rx.try_iter().count(), 0);
+
+        let rest: Vec<f32> = preloaded.collect();
+        assert_eq!(rest, samples[100..]);
+    }
+}
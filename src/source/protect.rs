@@ -0,0 +1,331 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::math;
+use crate::{Sample, Source};
+
+// Time over which the gain is lowered ahead of a peak, so it never steps down.
+const LOOKAHEAD_TIME: Duration = Duration::from_millis(2);
+// Time the gain stays down after a peak, so low notes do not make it ripple.
+const HOLD_TIME: Duration = Duration::from_millis(10);
+// Time for the gain to recover 63% of the way once the hold ended.
+const RELEASE_TIME: f32 = 0.1;
+// Reduction of the gain that is small enough to leave the signal untouched again.
+const NEGLIGIBLE: f32 = 1e-4;
+
+/// Internal function that builds a `Protect` object.
+pub fn protect<I>(input: I, headroom_db: f32) -> Protect<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    Protect {
+        input,
+        ceiling: 10f32.powf(-headroom_db / 20.0),
+        reduction: 0.0,
+        hold_left: 0,
+        timing: None,
+        delayed: VecDeque::new(),
+        ahead: VecDeque::new(),
+        frame_left: 0,
+    }
+}
+
+/// Lowers the gain only while a sound would exceed its headroom, see
+/// [`Source::protect`].
+#[derive(Clone, Debug)]
+pub struct Protect<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    input: I,
+    ceiling: f32,
+    // How far the gain is below `1.0`. Kept instead of the gain itself, which could
+    // not get close enough to `1.0` in steps this small.
+    reduction: f32,
+    // Frames left before the gain starts to recover.
+    hold_left: u64,
+    // Lookahead and hold in frames and release per frame for the sample rate they
+    // were computed for.
+    timing: Option<(usize, u64, f32, SampleRate)>,
+    // Samples read ahead of the output, starting with what is left of the frame
+    // being played.
+    delayed: VecDeque<I::Item>,
+    // Length of and reduction needed by every frame in `delayed` after the one
+    // being played.
+    ahead: VecDeque<(usize, f32)>,
+    // Samples of the frame being played still in `delayed`.
+    frame_left: usize,
+}
+
+impl<I> Protect<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    /// Returns the gain applied right now, `1.0` while the sound is left untouched.
+    #[inline]
+    pub fn gain(&self) -> f32 {
+        1.0 - self.reduction
+    }
+
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+
+    fn timing(&mut self) -> (usize, u64, f32) {
+        let sample_rate = self.input.sample_rate();
+        match self.timing {
+            Some((lookahead, hold, release, rate)) if rate == sample_rate => {
+                (lookahead, hold, release)
+            }
+            _ => {
+                let lookahead = lookahead_frames(sample_rate) as usize;
+                let hold = math::duration_to_frames(HOLD_TIME, sample_rate);
+                let release = (-1.0 / (RELEASE_TIME * sample_rate as f32)).exp();
+                self.timing = Some((lookahead, hold, release, sample_rate));
+                (lookahead, hold, release)
+            }
+        }
+    }
+
+    // Reads a frame into the delay line. Returns `false` if the input has ended.
+    fn read_frame(&mut self) -> bool {
+        let mut len = 0;
+        let mut peak = 0.0f32;
+        for _ in 0..self.input.channels() {
+            let Some(sample) = self.input.next() else {
+                break;
+            };
+            peak = peak.max(sample.to_f32().abs());
+            self.delayed.push_back(sample);
+            len += 1;
+        }
+        if len == 0 {
+            return false;
+        }
+        let needed = if peak > self.ceiling {
+            1.0 - self.ceiling / peak
+        } else {
+            0.0
+        };
+        self.ahead.push_back((len, needed));
+        true
+    }
+
+    // Moves on to the next frame and sets the gain for it. Returns `false` once every
+    // frame was played.
+    fn next_frame(&mut self) -> bool {
+        let (lookahead, hold, release) = self.timing();
+        while self.ahead.len() <= lookahead && self.read_frame() {}
+        let Some((len, needed)) = self.ahead.pop_front() else {
+            return false;
+        };
+        self.frame_left = len;
+
+        // Lowers the gain just fast enough for every frame read ahead to reach the
+        // reduction it needs by the time it is played.
+        let attack = self
+            .ahead
+            .iter()
+            .enumerate()
+            .map(|(frames, &(_, later))| (later - self.reduction) / (frames + 2) as f32)
+            .fold(needed - self.reduction, f32::max);
+        if attack > 0.0 {
+            self.reduction = (self.reduction + attack).max(needed);
+            self.hold_left = hold;
+        } else if needed > 0.0 && needed >= self.reduction {
+            self.hold_left = hold;
+        } else if self.hold_left > 0 {
+            self.hold_left -= 1;
+        } else if self.reduction > 0.0 {
+            self.reduction *= release;
+            if self.reduction < NEGLIGIBLE {
+                self.reduction = 0.0;
+            }
+        }
+        true
+    }
+
+    fn reset(&mut self) {
+        self.delayed.clear();
+        self.ahead.clear();
+        self.frame_left = 0;
+        self.reduction = 0.0;
+        self.hold_left = 0;
+    }
+}
+
+fn lookahead_frames(sample_rate: SampleRate) -> u64 {
+    math::duration_to_frames(LOOKAHEAD_TIME, sample_rate).max(1)
+}
+
+impl<I> Iterator for Protect<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        if self.frame_left == 0 && !self.next_frame() {
+            return None;
+        }
+        self.frame_left -= 1;
+        let sample = self.delayed.pop_front()?;
+        if self.reduction > 0.0 {
+            Some(sample.amplify(self.gain()))
+        } else {
+            Some(sample)
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.input.size_hint();
+        let buffered = self.delayed.len();
+        (
+            lower.saturating_add(buffered),
+            upper.and_then(|upper| upper.checked_add(buffered)),
+        )
+    }
+}
+
+impl<I> ExactSizeIterator for Protect<I>
+where
+    I: Source + ExactSizeIterator,
+    I::Item: Sample,
+{
+}
+
+impl<I> Source for Protect<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        self.input
+            .current_span_len()
+            .map(|len| len.saturating_add(self.delayed.len()))
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        let sample_rate = self.input.sample_rate();
+        self.input.latency() + math::frames_to_duration(lookahead_frames(sample_rate), sample_rate)
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.reset();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+    use std::time::Duration;
+
+    use crate::buffer::SamplesBuffer;
+    use crate::Source;
+
+    fn tone(amplitude: impl Fn(usize) -> f32) -> SamplesBuffer<f32> {
+        let samples = (0..96000)
+            .map(|n| amplitude(n) * (2.0 * PI * 440.0 * n as f32 / 48000.0).sin())
+            .collect::<Vec<_>>();
+        SamplesBuffer::new(1, 48000, samples)
+    }
+
+    #[test]
+    fn leaves_signal_in_range_untouched() {
+        let quiet = tone(|_| 0.5);
+        let protected: Vec<f32> = quiet.clone().protect(3.0).collect();
+        assert_eq!(protected, quiet.collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn attenuates_burst_just_enough() {
+        let ceiling = 10f32.powf(-3.0 / 20.0);
+        // 20 ms at full scale in the middle of a quiet tone.
+        let burst = |n: usize| {
+            if (24000..24960).contains(&n) {
+                1.0
+            } else {
+                0.5
+            }
+        };
+        let input: Vec<f32> = tone(burst).collect();
+        let mut protected = tone(burst).protect(3.0);
+        let output: Vec<f32> = protected.by_ref().collect();
+
+        let peak = output.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        assert!(peak <= ceiling, "peak {peak} above ceiling {ceiling}");
+        assert!(
+            peak > ceiling * 0.999,
+            "peak {peak} lowered more than needed"
+        );
+        // Untouched until the lookahead reaches the burst and again once the gain
+        // recovered.
+        assert_eq!(output[..24000 - 96], input[..24000 - 96]);
+        assert_eq!(output[72000..], input[72000..]);
+        assert_eq!(protected.gain(), 1.0);
+    }
+
+    #[test]
+    fn ramps_gain_down_ahead_of_peak() {
+        let ceiling = 10f32.powf(-3.0 / 20.0);
+        // A step from half to full scale at frame 1000, with a 96 frame lookahead.
+        let step = |n: usize| if n < 1000 { 0.5 } else { 1.0 };
+        let input = SamplesBuffer::new(1, 48000, (0..2000).map(step).collect::<Vec<f32>>());
+        let protected = input.protect(3.0);
+        assert_eq!(protected.latency(), Duration::from_millis(2));
+        let gains: Vec<f32> = protected
+            .enumerate()
+            .map(|(n, sample)| sample / step(n))
+            .collect();
+
+        assert!(gains[..1000 - 96].iter().all(|&gain| gain == 1.0));
+        assert!((gains[1000] - ceiling).abs() < 1e-6);
+        // Lowered in equal steps instead of all at once.
+        let most = (1.0 - ceiling) / 96.0;
+        for (n, pair) in gains.windows(2).enumerate() {
+            assert!(pair[0] - pair[1] <= most * 1.001, "step at frame {n}");
+        }
+    }
+}
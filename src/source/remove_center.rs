@@ -0,0 +1,264 @@
+use std::f32::consts::TAU;
+use std::time::Duration;
+
+use dasp_sample::FromSample;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Internal function that builds a `RemoveCenter` object.
+pub fn remove_center<I>(input: I) -> RemoveCenter<I>
+where
+    I: Source,
+    I::Item: Sample + FromSample<f32>,
+{
+    RemoveCenter {
+        input,
+        bands: None,
+        mono: false,
+        coefficients: (0.0, 0.0),
+        coefficients_rate: 0,
+        low: 0.0,
+        below_high: 0.0,
+        right: None,
+    }
+}
+
+/// Removes what is panned to the center of a stereo sound, see
+/// [`Source::remove_center`].
+#[derive(Clone, Debug)]
+pub struct RemoveCenter<I>
+where
+    I: Source,
+    I::Item: Sample + FromSample<f32>,
+{
+    input: I,
+    // Frequencies below the first and above the second are kept in the center.
+    bands: Option<(f32, f32)>,
+    mono: bool,
+    // Smoothing of the one-pole filters splitting off the low and the high band.
+    coefficients: (f32, f32),
+    // Sample rate the coefficients were computed for.
+    coefficients_rate: SampleRate,
+    // States of the filters: the center below the low band edge and below the high
+    // band edge.
+    low: f32,
+    below_high: f32,
+    // Right sample of the current frame that still has to be played.
+    right: Option<I::Item>,
+}
+
+impl<I> RemoveCenter<I>
+where
+    I: Source,
+    I::Item: Sample + FromSample<f32>,
+{
+    /// Keeps the center below `low_hz` and above `high_hz`, so bass and cymbals,
+    /// which are usually in the center as well, stay in the mix.
+    #[inline]
+    pub fn with_preserved_bands(mut self, low_hz: f32, high_hz: f32) -> Self {
+        self.bands = Some((low_hz.max(0.0), high_hz.max(low_hz)));
+        self.coefficients_rate = 0;
+        self
+    }
+
+    /// Plays the result as one channel instead of two.
+    #[inline]
+    pub fn with_mono_output(mut self) -> Self {
+        self.mono = true;
+        self
+    }
+
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+
+    fn is_stereo(&self) -> bool {
+        self.input.channels() == 2
+    }
+
+    // Part of the center that is kept.
+    fn preserved(&mut self, center: f32) -> f32 {
+        let Some((low_hz, high_hz)) = self.bands else {
+            return 0.0;
+        };
+        let sample_rate = self.input.sample_rate();
+        if self.coefficients_rate != sample_rate {
+            let coefficient = |hz: f32| 1.0 - (-TAU * hz / sample_rate as f32).exp();
+            self.coefficients = (coefficient(low_hz), coefficient(high_hz));
+            self.coefficients_rate = sample_rate;
+        }
+        self.low += self.coefficients.0 * (center - self.low);
+        self.below_high += self.coefficients.1 * (center - self.below_high);
+        self.low + center - self.below_high
+    }
+
+    fn output_len(&self, input_len: usize) -> usize {
+        let extra = self.right.is_some() as usize;
+        if self.mono && self.is_stereo() {
+            (input_len / 2).saturating_add(extra)
+        } else {
+            input_len.saturating_add(extra)
+        }
+    }
+}
+
+impl<I> Iterator for RemoveCenter<I>
+where
+    I: Source,
+    I::Item: Sample + FromSample<f32>,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        if let Some(right) = self.right.take() {
+            return Some(right);
+        }
+        // Only stereo sounds have a center to remove.
+        if !self.is_stereo() {
+            return self.input.next();
+        }
+
+        let left = self.input.next()?.to_f32();
+        let right = self.input.next()?.to_f32();
+        // Halved like the center, so a sound panned hard to one side keeps its level
+        // relative to the preserved bands. Both channels play it in phase.
+        let side = (left - right) / 2.0;
+        let kept = self.preserved((left + right) / 2.0);
+        let output = I::Item::from_sample_(side + kept);
+        if !self.mono {
+            self.right = Some(output);
+        }
+        Some(output)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.input.size_hint();
+        (
+            self.output_len(lower),
+            upper.map(|upper| self.output_len(upper)),
+        )
+    }
+}
+
+impl<I> ExactSizeIterator for RemoveCenter<I>
+where
+    I: Source + ExactSizeIterator,
+    I::Item: Sample + FromSample<f32>,
+{
+}
+
+impl<I> Source for RemoveCenter<I>
+where
+    I: Source,
+    I::Item: Sample + FromSample<f32>,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        self.input
+            .current_span_len()
+            .map(|len| self.output_len(len))
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        if self.mono && self.is_stereo() {
+            1
+        } else {
+            self.input.channels()
+        }
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.right = None;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::buffer::SamplesBuffer;
+    use crate::source::SineWave;
+    use crate::Source;
+
+    fn stereo(left_gain: f32, right_gain: f32) -> SamplesBuffer<f32> {
+        let samples = SineWave::new(1000.0)
+            .take(48000)
+            .flat_map(|s| [s * left_gain, s * right_gain])
+            .collect::<Vec<f32>>();
+        SamplesBuffer::new(2, 48000, samples)
+    }
+
+    fn energy(source: impl Source<Item = f32>) -> f32 {
+        source.map(|s| s * s).sum()
+    }
+
+    #[test]
+    fn cancels_center_and_keeps_sides() {
+        let center = stereo(0.5, 0.5);
+        assert!(energy(center.remove_center()) < 1e-6);
+
+        // Half of a sound panned hard left is played on both channels, in phase.
+        let hard_left = stereo(0.5, 0.0);
+        let original = energy(hard_left.clone());
+        let removed = hard_left.remove_center();
+        assert_eq!(removed.channels(), 2);
+        let removed: Vec<f32> = removed.collect();
+        assert!(removed.chunks(2).all(|frame| frame[0] == frame[1]));
+        let removed_energy: f32 = removed.iter().map(|s| s * s).sum();
+        assert!((removed_energy - original / 2.0).abs() < original * 1e-3);
+
+        let mono = stereo(0.5, 0.0).remove_center().with_mono_output();
+        assert_eq!(mono.channels(), 1);
+        assert_eq!(mono.size_hint(), (48000, Some(48000)));
+        assert!((energy(mono) - original / 4.0).abs() < original * 1e-3);
+    }
+
+    #[test]
+    fn preserves_bands() {
+        // 1 kHz lies between the bands and is removed, a wide enough band keeps it.
+        let removed = stereo(0.5, 0.5)
+            .remove_center()
+            .with_preserved_bands(50.0, 16000.0);
+        assert!(energy(removed) < energy(stereo(0.5, 0.5)) * 0.05);
+
+        let kept = stereo(0.5, 0.5)
+            .remove_center()
+            .with_preserved_bands(10000.0, 20000.0);
+        assert!(energy(kept) > energy(stereo(0.5, 0.5)) * 0.5);
+    }
+}
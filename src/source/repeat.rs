@@ -4,6 +4,7 @@ use crate::source::buffered::Buffered;
 
 use super::SeekError;
 use crate::common::{ChannelCount, SampleRate};
+use crate::math;
 use crate::{Sample, Source};
 
 /// Internal function that builds a `Repeat` object.
@@ -87,6 +88,11 @@ where
         None
     }
 
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.inner.latency()
+    }
+
     #[inline]
     fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
         self.inner.try_seek(pos)
@@ -106,3 +112,160 @@ where
         }
     }
 }
+
+/// Internal function that builds a `RepeatCrossfade` object.
+pub fn repeat_crossfade<I>(input: I, crossfade: Duration) -> RepeatCrossfade<I::Item>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    let channels = input.channels();
+    let sample_rate = input.sample_rate();
+    let mut samples: Vec<I::Item> = input.collect();
+
+    let frames = samples.len() / channels as usize;
+    samples.truncate(frames * channels as usize);
+    // The crossfade can not overlap the head of the next loop.
+    let crossfade_frames =
+        (math::duration_to_frames(crossfade, sample_rate) as usize).min(frames / 2);
+    RepeatCrossfade {
+        samples,
+        channels,
+        sample_rate,
+        crossfade_len: crossfade_frames * channels as usize,
+        pos: 0,
+    }
+}
+
+/// A source that repeats the given source and crossfades its end into its beginning, see
+/// [`Source::repeat_crossfade`].
+#[derive(Clone, Debug)]
+pub struct RepeatCrossfade<S> {
+    samples: Vec<S>,
+    channels: ChannelCount,
+    sample_rate: SampleRate,
+    // Number of samples at the end that are blended with the beginning.
+    crossfade_len: usize,
+    // Position in `samples` of the next sample.
+    pos: usize,
+}
+
+impl<S> Iterator for RepeatCrossfade<S>
+where
+    S: Sample,
+{
+    type Item = S;
+
+    #[inline]
+    fn next(&mut self) -> Option<S> {
+        let sample = *self.samples.get(self.pos)?;
+        let fade_start = self.samples.len() - self.crossfade_len;
+        let sample = if self.pos < fade_start {
+            sample
+        } else {
+            // Blends the end into the beginning.
+            let offset = self.pos - fade_start;
+            let frame = offset / self.channels as usize;
+            let frames = self.crossfade_len / self.channels as usize;
+            let gain = frame as f32 / frames as f32;
+            sample
+                .amplify(1.0 - gain)
+                .saturating_add(self.samples[offset].amplify(gain))
+        };
+
+        self.pos += 1;
+        if self.pos == self.samples.len() {
+            // The beginning was already played during the crossfade.
+            self.pos = self.crossfade_len;
+        }
+        Some(sample)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.samples.is_empty() {
+            (0, Some(0))
+        } else {
+            (usize::MAX, None)
+        }
+    }
+}
+
+impl<S> Source for RepeatCrossfade<S>
+where
+    S: Sample,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.channels
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.sample_rate
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        let channels = self.channels as usize;
+        let frames = self.samples.len() / channels;
+        let crossfade_frames = self.crossfade_len / channels;
+        let frame = math::duration_to_frames(pos, self.sample_rate) as usize;
+        // Every loop after the first starts after the crossfaded beginning.
+        let frame = if frame < frames {
+            frame
+        } else if frames > 0 {
+            crossfade_frames + (frame - frames) % (frames - crossfade_frames)
+        } else {
+            0
+        };
+        self.pos = frame * channels;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::buffer::SamplesBuffer;
+    use crate::Source;
+
+    #[test]
+    fn crossfades_at_boundary() {
+        let ramp: Vec<f32> = (0..100).map(|i| i as f32).collect();
+        let looped = SamplesBuffer::new(1, 1000, ramp).repeat_crossfade(Duration::from_millis(10));
+        let samples: Vec<f32> = looped.take(280).collect();
+
+        assert_eq!(samples[..90], (0..90).map(|i| i as f32).collect::<Vec<_>>());
+        // The last 10 samples fade from the tail (90..100) into the head (0..10).
+        for k in 0..10 {
+            let gain = k as f32 / 10.0;
+            let expected = (90 + k) as f32 * (1.0 - gain) + k as f32 * gain;
+            assert!((samples[90 + k] - expected).abs() < 1e-4);
+        }
+        assert!(samples[91] < samples[90] && samples[99] < 20.0);
+        // The next loop continues after the head that was faded in.
+        assert_eq!(samples[100], 10.0);
+        assert_eq!(samples[179], 89.0);
+        assert_eq!(samples[190], 10.0);
+    }
+
+    #[test]
+    fn without_crossfade_repeats() {
+        let looped =
+            SamplesBuffer::new(1, 1000, vec![1.0f32, 2.0]).repeat_crossfade(Duration::ZERO);
+        let samples: Vec<f32> = looped.take(6).collect();
+        assert_eq!(samples, vec![1.0, 2.0, 1.0, 2.0, 1.0, 2.0]);
+    }
+}
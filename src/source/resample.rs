@@ -0,0 +1,806 @@
+use std::fmt;
+use std::num::NonZero;
+use std::time::Duration;
+
+use dasp_sample::{FromSample, Sample as DaspSample, ToSample};
+use rubato::{
+    calculate_cutoff, FastFixedIn, PolynomialDegree, SincFixedIn, SincInterpolationParameters,
+    SincInterpolationType, VecResampler,
+};
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Number of input frames that are converted at once by default.
+const DEFAULT_CHUNK_SIZE: usize = 1024;
+
+/// Window function used to shape the sinc filter of a resampler, or blocks of a
+/// sound with [`Source::apply_window`].
+///
+/// Windows further down the list suppress aliasing better at the cost of a
+/// wider transition band.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WindowFunction {
+    /// Hann window.
+    Hann,
+    /// Squared Hann window.
+    Hann2,
+    /// Blackman window.
+    Blackman,
+    /// Squared Blackman window.
+    Blackman2,
+    /// Blackman-Harris window.
+    BlackmanHarris,
+    /// Squared Blackman-Harris window.
+    BlackmanHarris2,
+}
+
+impl WindowFunction {
+    /// Returns the value of a symmetric window of `len` frames at `frame`, zero at
+    /// both ends for the Hann and Blackman windows.
+    pub(crate) fn gain(self, frame: usize, len: usize) -> f32 {
+        if len < 2 {
+            return 1.0;
+        }
+        let x = std::f32::consts::TAU * frame as f32 / (len - 1) as f32;
+        let hann = || 0.5 - 0.5 * x.cos();
+        let blackman = || 0.42 - 0.5 * x.cos() + 0.08 * (2.0 * x).cos();
+        let blackman_harris =
+            || 0.35875 - 0.48829 * x.cos() + 0.14128 * (2.0 * x).cos() - 0.01168 * (3.0 * x).cos();
+        match self {
+            WindowFunction::Hann => hann(),
+            WindowFunction::Hann2 => hann().powi(2),
+            WindowFunction::Blackman => blackman(),
+            WindowFunction::Blackman2 => blackman().powi(2),
+            WindowFunction::BlackmanHarris => blackman_harris(),
+            WindowFunction::BlackmanHarris2 => blackman_harris().powi(2),
+        }
+    }
+}
+
+impl From<WindowFunction> for rubato::WindowFunction {
+    fn from(window: WindowFunction) -> Self {
+        match window {
+            WindowFunction::Hann => rubato::WindowFunction::Hann,
+            WindowFunction::Hann2 => rubato::WindowFunction::Hann2,
+            WindowFunction::Blackman => rubato::WindowFunction::Blackman,
+            WindowFunction::Blackman2 => rubato::WindowFunction::Blackman2,
+            WindowFunction::BlackmanHarris => rubato::WindowFunction::BlackmanHarris,
+            WindowFunction::BlackmanHarris2 => rubato::WindowFunction::BlackmanHarris2,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Interpolation {
+    Linear,
+    Sinc {
+        len: usize,
+        oversampling_factor: usize,
+        window: WindowFunction,
+        cubic: bool,
+    },
+}
+
+/// Quality settings of a [`Resample`] source.
+///
+/// Better quality costs more CPU time: [`fast`](ResampleConfig::fast) interpolates
+/// linearly between samples, while [`balanced`](ResampleConfig::balanced) and
+/// [`accurate`](ResampleConfig::accurate) use increasingly long windowed sinc filters
+/// that remove the aliasing linear interpolation introduces.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ResampleConfig {
+    interpolation: Interpolation,
+    chunk_size: usize,
+}
+
+impl ResampleConfig {
+    /// Linear interpolation, cheap but with audible aliasing on bright material.
+    pub fn fast() -> ResampleConfig {
+        ResampleConfig {
+            interpolation: Interpolation::Linear,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        }
+    }
+
+    /// A 64 tap sinc filter, good enough for most playback.
+    pub fn balanced() -> ResampleConfig {
+        ResampleConfig {
+            interpolation: Interpolation::Sinc {
+                len: 64,
+                oversampling_factor: 128,
+                window: WindowFunction::Blackman2,
+                cubic: false,
+            },
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        }
+    }
+
+    /// A 256 tap sinc filter for when quality matters more than CPU time.
+    pub fn accurate() -> ResampleConfig {
+        ResampleConfig {
+            interpolation: Interpolation::Sinc {
+                len: 256,
+                oversampling_factor: 256,
+                window: WindowFunction::BlackmanHarris2,
+                cubic: true,
+            },
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        }
+    }
+
+    /// A sinc filter with `len` taps shaped by `window`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is zero.
+    pub fn sinc(len: usize, window: WindowFunction) -> ResampleConfig {
+        assert!(len > 0, "the sinc filter needs at least one tap");
+        ResampleConfig {
+            interpolation: Interpolation::Sinc {
+                len,
+                oversampling_factor: 256,
+                window,
+                cubic: true,
+            },
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        }
+    }
+
+    /// Sets the number of input frames converted at once. Larger chunks are
+    /// slightly more efficient but make each conversion step take longer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero.
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> ResampleConfig {
+        assert!(chunk_size > 0, "chunk size must be at least one frame");
+        self.chunk_size = chunk_size;
+        self
+    }
+
+    // Output frames by which the converted signal lags behind the input. The
+    // interpolators place output frame `n` at input frame `(n + 1) / ratio - 1`, the
+    // polynomial one adds another three input frames of latency on top. Rubato's own
+    // `output_delay` does not account for either.
+    fn delay(&self, from: SampleRate, to: SampleRate) -> usize {
+        let ratio = to as f64 / from as f64;
+        let latency = match self.interpolation {
+            Interpolation::Linear => 3.0,
+            Interpolation::Sinc { .. } => 0.0,
+        };
+        ((latency + 1.0) * ratio - 1.0).round().max(0.0) as usize
+    }
+
+    fn build(
+        &self,
+        from: SampleRate,
+        to: SampleRate,
+        channels: ChannelCount,
+    ) -> Box<dyn VecResampler<f32>> {
+        let ratio = to as f64 / from as f64;
+        let channels = channels as usize;
+        match self.interpolation {
+            Interpolation::Linear => Box::new(
+                FastFixedIn::new(
+                    ratio,
+                    1.0,
+                    PolynomialDegree::Linear,
+                    self.chunk_size,
+                    channels,
+                )
+                .expect("resample ratio is finite and positive"),
+            ),
+            Interpolation::Sinc {
+                len,
+                oversampling_factor,
+                window,
+                cubic,
+            } => {
+                let window = window.into();
+                let parameters = SincInterpolationParameters {
+                    sinc_len: len,
+                    f_cutoff: calculate_cutoff(len, window),
+                    oversampling_factor,
+                    interpolation: if cubic {
+                        SincInterpolationType::Cubic
+                    } else {
+                        SincInterpolationType::Linear
+                    },
+                    window,
+                };
+                Box::new(
+                    SincFixedIn::new(ratio, 1.0, parameters, self.chunk_size, channels)
+                        .expect("resample ratio is finite and positive"),
+                )
+            }
+        }
+    }
+}
+
+impl Default for ResampleConfig {
+    fn default() -> Self {
+        ResampleConfig::balanced()
+    }
+}
+
+/// Internal function that builds a `Resample` object.
+pub fn resample<I>(input: I, target_rate: SampleRate, config: ResampleConfig) -> Resample<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    resample_hinted(input, target_rate, config, 0)
+}
+
+/// Internal function that builds a `Resample` object at `factor` times the rate of
+/// the input.
+pub fn oversample<I>(input: I, factor: NonZero<u32>, config: ResampleConfig) -> Resample<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    let target_rate = input.sample_rate().saturating_mul(factor.get());
+    resample(input, target_rate, config)
+}
+
+/// Internal function that builds a `Resample` object at the rate of the input
+/// divided by `factor`, but at least 1 Hz.
+pub fn downsample<I>(input: I, factor: NonZero<u32>, config: ResampleConfig) -> Resample<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    let target_rate = (input.sample_rate() / factor.get()).max(1);
+    resample(input, target_rate, config)
+}
+
+/// Internal function that builds a `Resample` object with input buffers for
+/// `expected_chunk` frames.
+pub fn resample_hinted<I>(
+    input: I,
+    target_rate: SampleRate,
+    config: ResampleConfig,
+    expected_chunk: usize,
+) -> Resample<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    assert!(target_rate > 0, "target sample rate must be positive");
+    let mut resample = Resample {
+        from_rate: input.sample_rate(),
+        channels: input.channels(),
+        span_left: input.current_span_len(),
+        input,
+        config,
+        target_rate,
+        resampler: None,
+        expected_chunk,
+        input_buffer: Vec::new(),
+        output_buffer: Vec::new(),
+        output_start: 0,
+        output_frames: 0,
+        output_pos: 0,
+        delay_left: 0,
+        frames_in: 0,
+        frames_out: 0,
+        format_changed: false,
+        input_ended: false,
+    };
+    resample.start_span();
+    resample
+}
+
+/// Converts a source to another sample rate, see [`Source::resample`].
+pub struct Resample<I> {
+    input: I,
+    config: ResampleConfig,
+    target_rate: SampleRate,
+    // Format of the input span that is being converted.
+    from_rate: SampleRate,
+    channels: ChannelCount,
+    // Samples left in the current input span.
+    span_left: Option<usize>,
+    // `None` when the input already has the target rate.
+    resampler: Option<Box<dyn VecResampler<f32>>>,
+    // Frames the input buffers are allocated for up front.
+    expected_chunk: usize,
+    input_buffer: Vec<Vec<f32>>,
+    output_buffer: Vec<Vec<f32>>,
+    // Frames of `output_buffer` that are ready to be played, starting at `output_start`.
+    output_start: usize,
+    output_frames: usize,
+    // Index of the next sample to play, counted in samples from `output_start`.
+    output_pos: usize,
+    // Frames of filter delay that still have to be dropped from the output.
+    delay_left: usize,
+    // Frames read from the input and frames played since the resampler was created.
+    frames_in: u64,
+    frames_out: u64,
+    // The input changed its channel count or sample rate, the resampler has to be
+    // flushed and rebuilt.
+    format_changed: bool,
+    input_ended: bool,
+}
+
+impl<I> fmt::Debug for Resample<I>
+where
+    I: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Resample")
+            .field("input", &self.input)
+            .field("config", &self.config)
+            .field("from_rate", &self.from_rate)
+            .field("target_rate", &self.target_rate)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<I> Resample<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+
+    // Sets up conversion for the format the input currently has.
+    fn start_span(&mut self) {
+        self.from_rate = self.input.sample_rate();
+        self.channels = self.input.channels();
+        self.span_left = self.input.current_span_len();
+        self.format_changed = false;
+        self.frames_in = 0;
+        self.frames_out = 0;
+        self.output_frames = 0;
+        self.output_pos = 0;
+
+        if self.from_rate == self.target_rate {
+            self.resampler = None;
+            return;
+        }
+        let resampler = self
+            .config
+            .build(self.from_rate, self.target_rate, self.channels);
+        self.delay_left = self.config.delay(self.from_rate, self.target_rate);
+        let capacity = self.expected_chunk.max(resampler.input_frames_max());
+        self.input_buffer = (0..self.channels)
+            .map(|_| Vec::with_capacity(capacity))
+            .collect();
+        self.output_buffer = resampler.output_buffer_allocate(true);
+        self.resampler = Some(resampler);
+    }
+
+    // Returns the next input sample of the current span, or `None` once the span or
+    // the input has ended.
+    fn next_input_sample(&mut self) -> Option<I::Item> {
+        if self.format_changed || self.input_ended {
+            return None;
+        }
+        if self.span_left == Some(0) {
+            if self.input.sample_rate() != self.from_rate || self.input.channels() != self.channels
+            {
+                self.format_changed = true;
+                return None;
+            }
+            self.span_left = self.input.current_span_len();
+        }
+
+        let sample = self.input.next();
+        match sample {
+            Some(_) => self.span_left = self.span_left.map(|left| left.saturating_sub(1)),
+            None => self.input_ended = true,
+        }
+        sample
+    }
+
+    // Number of output frames the input read so far converts to.
+    fn expected_frames_out(&self, frames_in: u64) -> u64 {
+        frames_out(frames_in, self.from_rate, self.target_rate)
+    }
+
+    fn buffered(&self) -> usize {
+        self.output_frames * self.channels as usize - self.output_pos
+    }
+
+    // Whether everything the current input span converts to has been played.
+    fn at_span_end(&self) -> bool {
+        if self.span_left != Some(0) && !self.format_changed {
+            return false;
+        }
+        self.resampler.is_none()
+            || (self.buffered() == 0 && self.frames_out >= self.expected_frames_out(self.frames_in))
+    }
+
+    // Output samples the next input span converts to, once the current one was played.
+    fn next_span_len(&self) -> Option<usize> {
+        let len = self.input.current_span_len()?;
+        let (channels, from_rate) = (self.input.channels(), self.input.sample_rate());
+        if from_rate == self.target_rate {
+            return Some(len);
+        }
+        let frames_in = (len / channels.max(1) as usize) as u64;
+        let frames = if channels == self.channels && from_rate == self.from_rate {
+            // The resampler carries on into the next span.
+            self.expected_frames_out(self.frames_in + frames_in)
+                .saturating_sub(self.frames_out)
+        } else {
+            frames_out(frames_in, from_rate, self.target_rate)
+        };
+        usize::try_from(frames.saturating_mul(channels as u64)).ok()
+    }
+
+    // Converts the next chunk of input. Returns `false` once the current span has been
+    // converted completely.
+    fn convert_chunk(&mut self) -> bool {
+        let Some(mut resampler) = self.resampler.take() else {
+            return false;
+        };
+        let channels = self.channels as usize;
+        let wanted = resampler.input_frames_next();
+
+        for channel in &mut self.input_buffer {
+            channel.clear();
+        }
+        let mut frames = 0;
+        'frames: while frames < wanted {
+            for channel in 0..channels {
+                match self.next_input_sample() {
+                    Some(sample) => self.input_buffer[channel].push(sample.to_f32()),
+                    None => {
+                        // Drop the incomplete frame.
+                        for partial in &mut self.input_buffer[..channel] {
+                            partial.pop();
+                        }
+                        break 'frames;
+                    }
+                }
+            }
+            frames += 1;
+        }
+        self.frames_in += frames as u64;
+
+        let expected = self.expected_frames_out(self.frames_in);
+        if frames < wanted && self.frames_out >= expected {
+            // The span has ended and all of it has been played.
+            self.resampler = Some(resampler);
+            return false;
+        }
+
+        let result = if frames == wanted {
+            resampler.process_into_buffer(&self.input_buffer, &mut self.output_buffer, None)
+        } else if frames > 0 {
+            resampler.process_partial_into_buffer(
+                Some(&self.input_buffer),
+                &mut self.output_buffer,
+                None,
+            )
+        } else {
+            resampler.process_partial_into_buffer(None, &mut self.output_buffer, None)
+        };
+        let (_, produced) = result.expect("buffers are sized by the resampler");
+        self.resampler = Some(resampler);
+
+        let skipped = produced.min(self.delay_left);
+        self.delay_left -= skipped;
+        let available = (produced - skipped) as u64;
+        let frames = available.min(expected - self.frames_out);
+
+        self.output_start = skipped;
+        self.output_frames = frames as usize;
+        self.output_pos = 0;
+        self.frames_out += frames;
+        true
+    }
+}
+
+// Number of output frames `frames_in` frames convert to.
+fn frames_out(frames_in: u64, from_rate: SampleRate, to_rate: SampleRate) -> u64 {
+    // Hours of input at a high ratio overflow `u64` before the division.
+    let (from, to) = (from_rate as u128, to_rate as u128);
+    let frames = (frames_in as u128 * to).div_ceil(from);
+    u64::try_from(frames).unwrap_or(u64::MAX)
+}
+
+// Converts through the float type of `S`, which every sample type converts from and to,
+// so resampling needs no `FromSample<f32>` bound.
+#[inline]
+fn from_f32<S: DaspSample>(sample: f32) -> S {
+    S::Float::from_sample_(sample).to_sample_()
+}
+
+impl<I> Iterator for Resample<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        loop {
+            if self.resampler.is_none() {
+                if let Some(sample) = self.next_input_sample() {
+                    return Some(sample);
+                }
+            } else {
+                let channels = self.channels as usize;
+                if self.output_pos < self.output_frames * channels {
+                    let frame = self.output_start + self.output_pos / channels;
+                    let sample = self.output_buffer[self.output_pos % channels][frame];
+                    self.output_pos += 1;
+                    return Some(from_f32(sample));
+                }
+                if self.convert_chunk() {
+                    continue;
+                }
+            }
+
+            if self.format_changed {
+                self.start_span();
+            } else {
+                return None;
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.resampler.is_none() {
+            return self.input.size_hint();
+        }
+
+        let channels = self.channels as u64;
+        let buffered = self.buffered() as u64;
+        let remaining = |input_samples: usize| {
+            let frames_in = self
+                .frames_in
+                .saturating_add(input_samples as u64 / channels);
+            let frames = self
+                .expected_frames_out(frames_in)
+                .saturating_sub(self.frames_out);
+            let samples = frames.saturating_mul(channels).saturating_add(buffered);
+            usize::try_from(samples).unwrap_or(usize::MAX)
+        };
+
+        let (lower, upper) = self.input.size_hint();
+        let lower = match self.span_left {
+            Some(span_left) => lower.min(span_left),
+            None => lower,
+        };
+        (remaining(lower), upper.map(remaining))
+    }
+}
+
+impl<I> Source for Resample<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    // A span ends wherever an input span ends, so a change of the channel count is
+    // seen in time.
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        if self.at_span_end() {
+            return self.next_span_len();
+        }
+        let span_left = self.span_left?;
+        if self.resampler.is_none() {
+            return Some(span_left);
+        }
+        let channels = self.channels as usize;
+        let frames_in = self.frames_in + (span_left / channels) as u64;
+        let frames = self
+            .expected_frames_out(frames_in)
+            .saturating_sub(self.frames_out);
+        usize::try_from(frames.saturating_mul(channels as u64))
+            .ok()
+            .map(|samples| samples.saturating_add(self.buffered()))
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        if self.at_span_end() {
+            self.input.channels()
+        } else {
+            self.channels
+        }
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.target_rate
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.input_ended = false;
+        self.start_span();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZero;
+
+    use super::{downsample, oversample, resample, resample_hinted, ResampleConfig};
+    use crate::buffer::SamplesBuffer;
+    use crate::Source;
+
+    fn sine(rate: u32, freq: f32, frames: usize) -> SamplesBuffer<f32> {
+        let data = (0..frames)
+            .map(|i| (2.0 * std::f32::consts::PI * freq * i as f32 / rate as f32).sin())
+            .collect::<Vec<_>>();
+        SamplesBuffer::new(1, rate, data)
+    }
+
+    // Reports far more samples than any real file has.
+    struct Huge;
+
+    impl Iterator for Huge {
+        type Item = f32;
+
+        fn next(&mut self) -> Option<f32> {
+            Some(0.0)
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (usize::MAX - 1, Some(usize::MAX - 1))
+        }
+    }
+
+    impl Source for Huge {
+        fn current_span_len(&self) -> Option<usize> {
+            None
+        }
+
+        fn channels(&self) -> crate::ChannelCount {
+            1
+        }
+
+        fn sample_rate(&self) -> crate::SampleRate {
+            44100
+        }
+
+        fn total_duration(&self) -> Option<std::time::Duration> {
+            None
+        }
+    }
+
+    #[test]
+    fn size_hint_saturates() {
+        let resampled = resample(Huge, 96000, ResampleConfig::fast());
+        assert_eq!(resampled.size_hint(), (usize::MAX, Some(usize::MAX)));
+    }
+
+    #[test]
+    fn converts_length_and_rate() {
+        for config in [
+            ResampleConfig::fast(),
+            ResampleConfig::balanced(),
+            ResampleConfig::accurate(),
+        ] {
+            let resampled = resample(sine(44100, 440.0, 44100), 48000, config);
+            assert_eq!(resampled.sample_rate(), 48000);
+            assert_eq!(resampled.count(), 48000);
+        }
+    }
+
+    #[test]
+    fn keeps_pitch_and_channels() {
+        let rate = 16000;
+        let data = (0..rate as usize)
+            .flat_map(|i| {
+                let t = i as f32 / rate as f32;
+                let sample = (2.0 * std::f32::consts::PI * 1000.0 * t).sin();
+                [sample, -sample]
+            })
+            .collect::<Vec<_>>();
+        let resampled = resample(
+            SamplesBuffer::new(2, rate, data),
+            48000,
+            ResampleConfig::balanced(),
+        );
+        assert_eq!(resampled.channels(), 2);
+
+        let samples: Vec<f32> = resampled.collect();
+        assert_eq!(samples.len(), 2 * 48000);
+        // The channels stay in antiphase.
+        assert!(samples
+            .chunks(2)
+            .all(|frame| (frame[0] + frame[1]).abs() < 1e-3));
+        // Away from the edges the output matches a 1 kHz sine at the new rate.
+        for (i, frame) in samples.chunks(2).enumerate().skip(1000).take(1000) {
+            let t = i as f32 / 48000.0;
+            let expected = (2.0 * std::f32::consts::PI * 1000.0 * t).sin();
+            assert!((frame[0] - expected).abs() < 0.01, "frame {i}");
+        }
+    }
+
+    #[test]
+    fn oversample_doubles_rate_and_keeps_pitch() {
+        let factor = NonZero::new(2).unwrap();
+        let oversampled = oversample(
+            sine(44100, 1000.0, 44100),
+            factor,
+            ResampleConfig::balanced(),
+        );
+        assert_eq!(oversampled.sample_rate(), 88200);
+
+        let samples: Vec<f32> = oversampled.collect();
+        assert_eq!(samples.len(), 88200);
+        for (i, &sample) in samples.iter().enumerate().skip(1000).take(1000) {
+            let t = i as f32 / 88200.0;
+            let expected = (2.0 * std::f32::consts::PI * 1000.0 * t).sin();
+            assert!((sample - expected).abs() < 0.01, "frame {i}");
+        }
+
+        // Going back down restores the original rate.
+        let restored = downsample(
+            oversample(sine(44100, 1000.0, 4410), factor, ResampleConfig::fast()),
+            factor,
+            ResampleConfig::fast(),
+        );
+        assert_eq!(restored.sample_rate(), 44100);
+        assert_eq!(restored.count(), 4410);
+    }
+
+    #[test]
+    fn downsample_keeps_rate_positive() {
+        let factor = NonZero::new(100_000).unwrap();
+        let downsampled = downsample(sine(44100, 440.0, 4410), factor, ResampleConfig::fast());
+        assert_eq!(downsampled.sample_rate(), 1);
+    }
+
+    #[test]
+    fn hint_presizes_buffers_only() {
+        let plain: Vec<f32> =
+            resample(sine(44100, 440.0, 4410), 48000, ResampleConfig::fast()).collect();
+        let hinted = resample_hinted(
+            sine(44100, 440.0, 4410),
+            48000,
+            ResampleConfig::fast(),
+            4096,
+        );
+        assert!(hinted.input_buffer[0].capacity() >= 4096);
+        assert_eq!(hinted.collect::<Vec<f32>>(), plain);
+    }
+
+    #[test]
+    fn passes_through_matching_rate() {
+        let source = sine(48000, 440.0, 100);
+        let expected: Vec<f32> = source.clone().collect();
+        let resampled: Vec<f32> = resample(source, 48000, ResampleConfig::default()).collect();
+        assert_eq!(resampled, expected);
+    }
+}
@@ -0,0 +1,154 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Internal function that builds a `Retrigger` object.
+pub fn retriggerable<I>(input: I) -> (Retrigger<I>, RetriggerHandle)
+where
+    I: Source,
+    I::Item: Sample,
+{
+    let handle = RetriggerHandle {
+        pending: Arc::new(AtomicBool::new(false)),
+    };
+    let source = Retrigger {
+        input,
+        handle: handle.clone(),
+        channel: 0,
+    };
+    (source, handle)
+}
+
+/// Restarts a [`Retrigger`] source from anywhere, for example from the thread
+/// handling input.
+#[derive(Clone, Debug)]
+pub struct RetriggerHandle {
+    pending: Arc<AtomicBool>,
+}
+
+impl RetriggerHandle {
+    /// Makes the source play from the start again, beginning with the next frame.
+    #[inline]
+    pub fn retrigger(&self) {
+        self.pending.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Restarts from the beginning on command, see [`Source::retriggerable`].
+#[derive(Clone, Debug)]
+pub struct Retrigger<I> {
+    input: I,
+    handle: RetriggerHandle,
+    // Channel of the next sample.
+    channel: usize,
+}
+
+impl<I> Retrigger<I> {
+    /// Returns a handle that restarts the source.
+    #[inline]
+    pub fn handle(&self) -> RetriggerHandle {
+        self.handle.clone()
+    }
+
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+}
+
+impl<I> Iterator for Retrigger<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        if self.channel == 0 && self.handle.pending.swap(false, Ordering::Relaxed) {
+            // A source that can not seek keeps playing where it is.
+            let _ = self.input.try_seek(Duration::ZERO);
+        }
+        let sample = self.input.next()?;
+        self.channel = (self.channel + 1) % self.input.channels() as usize;
+        Some(sample)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<I> Source for Retrigger<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.channel = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::buffer::SamplesBuffer;
+    use crate::Source;
+
+    #[test]
+    fn restarts_from_first_sample() {
+        let samples: Vec<f32> = (0..100).map(|i| i as f32).collect();
+        let (mut source, handle) = SamplesBuffer::new(2, 1000, samples).retriggerable();
+        assert_eq!(source.by_ref().take(31).last(), Some(30.0));
+
+        // Finishes the frame before jumping back.
+        handle.retrigger();
+        let played: Vec<f32> = source.take(4).collect();
+        assert_eq!(played, [31.0, 0.0, 1.0, 2.0]);
+    }
+}
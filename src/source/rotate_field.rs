@@ -0,0 +1,236 @@
+use std::time::Duration;
+
+use super::{SeekError, SpeakerLayout};
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Internal function that builds a `RotateField` object.
+pub fn rotate_field<I>(input: I, angle: f32) -> RotateField<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    RotateField {
+        input,
+        angle,
+        matrix: None,
+        input_frame: Vec::new(),
+        frame: Vec::new(),
+        pos: 0,
+    }
+}
+
+/// Turns a multichannel sound field around the listener, see [`Source::rotate_field`].
+#[derive(Clone, Debug)]
+pub struct RotateField<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    input: I,
+    angle: f32,
+    // Gain from every input channel to every output channel, row per output channel,
+    // and the channel count it was computed for. `None` until the next frame.
+    matrix: Option<(Vec<f32>, ChannelCount)>,
+    // Current input frame, kept to reuse its allocation.
+    input_frame: Vec<I::Item>,
+    // Current output frame and the index of its next sample.
+    frame: Vec<I::Item>,
+    pos: usize,
+}
+
+impl<I> RotateField<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    /// Returns the angle the field is rotated by, in radians clockwise.
+    #[inline]
+    pub fn angle(&self) -> f32 {
+        self.angle
+    }
+
+    /// Sets the angle the field is rotated by, in radians clockwise. Takes effect at
+    /// the next frame.
+    #[inline]
+    pub fn set_angle(&mut self, angle: f32) {
+        self.angle = angle;
+        self.matrix = None;
+    }
+
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+
+    fn update_matrix(&mut self) {
+        let channels = self.input.channels();
+        if !matches!(self.matrix, Some((_, c)) if c == channels) {
+            self.matrix = Some((rotation_matrix(channels, self.angle), channels));
+        }
+    }
+
+    fn next_frame(&mut self) -> Option<()> {
+        let channels = self.input.channels() as usize;
+        let first = self.input.next()?;
+        self.input_frame.clear();
+        self.input_frame.push(first);
+        self.input_frame
+            .extend(self.input.by_ref().take(channels - 1));
+
+        self.update_matrix();
+        let (matrix, _) = self.matrix.as_ref().expect("just computed");
+        let input = &self.input_frame;
+        self.frame.clear();
+        self.frame
+            .extend(matrix.chunks(channels).take(input.len()).map(|row| {
+                row.iter()
+                    .zip(input)
+                    .filter(|(&gain, _)| gain != 0.0)
+                    .fold(I::Item::zero_value(), |sum, (&gain, &sample)| {
+                        sum.saturating_add(sample.amplify(gain))
+                    })
+            }));
+        self.pos = 0;
+        Some(())
+    }
+}
+
+// Moves the content of every speaker `angle` further clockwise, panning it between
+// the two speakers closest to its new direction. Channel counts without a known
+// speaker layout, and the LFE channel, are left in place.
+fn rotation_matrix(channels: ChannelCount, angle: f32) -> Vec<f32> {
+    let channels = channels as usize;
+    let mut matrix = vec![0.0; channels * channels];
+    let layout = SpeakerLayout::from_channels(channels as ChannelCount);
+    for input in 0..channels {
+        let azimuth = layout.and_then(|layout| layout.azimuths()[input]);
+        match (layout, azimuth) {
+            (Some(layout), Some(azimuth)) => {
+                for (output, gain) in layout.pan_gains(azimuth + angle).into_iter().enumerate() {
+                    matrix[output * channels + input] = gain;
+                }
+            }
+            _ => matrix[input * channels + input] = 1.0,
+        }
+    }
+    matrix
+}
+
+impl<I> Iterator for RotateField<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        if self.pos >= self.frame.len() {
+            self.next_frame()?;
+        }
+        let sample = self.frame[self.pos];
+        self.pos += 1;
+        Some(sample)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let buffered = self.frame.len() - self.pos;
+        let (lower, upper) = self.input.size_hint();
+        (
+            lower.saturating_add(buffered),
+            upper.and_then(|upper| upper.checked_add(buffered)),
+        )
+    }
+}
+
+impl<I> ExactSizeIterator for RotateField<I>
+where
+    I: Source + ExactSizeIterator,
+    I::Item: Sample,
+{
+}
+
+impl<I> Source for RotateField<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        let buffered = self.frame.len() - self.pos;
+        self.input
+            .current_span_len()
+            .map(|len| len.saturating_add(buffered))
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.frame.clear();
+        self.pos = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use crate::buffer::SamplesBuffer;
+    use crate::Source;
+
+    #[test]
+    fn quarter_turn_moves_front_left_to_front_right() {
+        let frames = [[1.0f32, 0.0, 0.0, 0.0], [0.5, 0.0, 0.0, 0.0]];
+        let source = SamplesBuffer::new(4, 1000, frames.concat()).rotate_field(FRAC_PI_2);
+        let samples: Vec<f32> = source.collect();
+        assert_eq!(samples.len(), 8);
+        for (frame, expected) in samples.chunks(4).zip([1.0, 0.5]) {
+            assert!(frame[0].abs() < 1e-6, "{frame:?}");
+            assert!((frame[1] - expected).abs() < 1e-6, "{frame:?}");
+            assert!(frame[2].abs() < 1e-6 && frame[3].abs() < 1e-6, "{frame:?}");
+        }
+
+        // Turning back moves front right content to front left.
+        let mut source = SamplesBuffer::new(4, 1000, vec![0.0f32, 1.0, 0.0, 0.0]).rotate_field(0.0);
+        source.set_angle(-FRAC_PI_2);
+        let frame: Vec<f32> = source.collect();
+        assert!((frame[0] - 1.0).abs() < 1e-6, "{frame:?}");
+        assert!(frame[1..].iter().all(|s| s.abs() < 1e-6), "{frame:?}");
+    }
+}
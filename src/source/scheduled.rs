@@ -0,0 +1,254 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::math;
+use crate::{Sample, Source};
+
+/// Internal function that builds a `Scheduled` object.
+pub fn start_at<I>(input: I, clock: AudioClock, frame: u64) -> Scheduled<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    Scheduled {
+        input,
+        clock,
+        start: frame,
+        first_frame: None,
+        frame: 0,
+        channel: 0,
+    }
+}
+
+/// A timeline shared by sources that start at exact frames, see [`Source::start_at`].
+///
+/// The clock counts the frames played by the sources scheduled on it. Sources that
+/// play side by side, for example in the same [`Mixer`](crate::mixer::Mixer), move
+/// it forward together. A source scheduled later joins the timeline at the frame it
+/// has reached by then.
+///
+/// The clock can also be moved by hand with [`advance`](AudioClock::advance), for
+/// example to step a sound frame by frame in a test, see
+/// [`Source::with_external_clock`].
+#[derive(Clone, Debug, Default)]
+pub struct AudioClock {
+    frames: Arc<AtomicU64>,
+}
+
+impl AudioClock {
+    /// Creates a clock at frame zero.
+    #[inline]
+    pub fn new() -> AudioClock {
+        AudioClock::default()
+    }
+
+    /// Returns the number of frames played on the clock so far.
+    #[inline]
+    pub fn now(&self) -> u64 {
+        self.frames.load(Ordering::Relaxed)
+    }
+
+    /// Moves the clock `frames` frames forward.
+    #[inline]
+    pub fn advance(&self, frames: u64) {
+        self.frames.fetch_add(frames, Ordering::Relaxed);
+    }
+
+    // Moves the clock forward to `frame` unless it is already past it.
+    #[inline]
+    fn reach(&self, frame: u64) {
+        self.frames.fetch_max(frame, Ordering::Relaxed);
+    }
+}
+
+/// Plays silence until its [`AudioClock`] reaches a frame, see [`Source::start_at`].
+#[derive(Clone, Debug)]
+pub struct Scheduled<I> {
+    input: I,
+    clock: AudioClock,
+    // Frame of the clock at which the sound starts.
+    start: u64,
+    // Frame of the clock when the first sample was played.
+    first_frame: Option<u64>,
+    // Frame of the clock the current frame is played at.
+    frame: u64,
+    // Channel of the next sample.
+    channel: usize,
+}
+
+impl<I> Scheduled<I> {
+    /// Returns the clock the source follows.
+    #[inline]
+    pub fn clock(&self) -> &AudioClock {
+        &self.clock
+    }
+
+    /// Returns the frame of the clock at which the sound starts.
+    #[inline]
+    pub fn start_frame(&self) -> u64 {
+        self.start
+    }
+
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+
+    // Frames of silence left before the sound starts.
+    fn frames_until_start(&self) -> u64 {
+        let frame = match self.first_frame {
+            Some(_) => self.frame,
+            None => self.clock.now(),
+        };
+        self.start.saturating_sub(frame)
+    }
+}
+
+impl<I> Scheduled<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    // Samples of silence left before the sound starts.
+    fn silence_len(&self) -> usize {
+        let channels = self.input.channels() as u64;
+        let samples = (self.frames_until_start() * channels).saturating_sub(self.channel as u64);
+        usize::try_from(samples).unwrap_or(usize::MAX)
+    }
+}
+
+impl<I> Iterator for Scheduled<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        if self.first_frame.is_none() {
+            self.frame = self.clock.now();
+            self.first_frame = Some(self.frame);
+        }
+        let sample = if self.frame < self.start {
+            I::Item::zero_value()
+        } else {
+            self.input.next()?
+        };
+        self.channel += 1;
+        if self.channel >= self.input.channels() as usize {
+            self.channel = 0;
+            self.frame += 1;
+            self.clock.reach(self.frame);
+        }
+        Some(sample)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let silence = self.silence_len();
+        let (lower, upper) = self.input.size_hint();
+        (
+            lower.saturating_add(silence),
+            upper.and_then(|upper| upper.checked_add(silence)),
+        )
+    }
+}
+
+impl<I> ExactSizeIterator for Scheduled<I>
+where
+    I: Source + ExactSizeIterator,
+    I::Item: Sample,
+{
+}
+
+impl<I> Source for Scheduled<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        match self.silence_len() {
+            0 => self.input.current_span_len(),
+            silence => Some(silence),
+        }
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        let first_frame = self.first_frame.unwrap_or_else(|| self.clock.now());
+        let silence = self.start.saturating_sub(first_frame);
+        self.input
+            .total_duration()
+            .map(|duration| duration + math::frames_to_duration(silence, self.sample_rate()))
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
+    // Seeks within the sound, the frame at which it starts does not change.
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::buffer::SamplesBuffer;
+    use crate::mixer;
+    use crate::source::AudioClock;
+    use crate::Source;
+
+    #[test]
+    fn sources_start_at_their_frames() {
+        let clock = AudioClock::new();
+        let (controller, mixer) = mixer::mixer::<f32>(2, 1000);
+        let first = SamplesBuffer::new(2, 1000, vec![1.0f32; 20]).start_at(clock.clone(), 10);
+        assert_eq!(first.size_hint(), (40, Some(40)));
+        controller.add(first);
+        controller.add(SamplesBuffer::new(2, 1000, vec![2.0f32; 20]).start_at(clock.clone(), 25));
+
+        let frames: Vec<f32> = mixer
+            .take(100)
+            .collect::<Vec<_>>()
+            .chunks(2)
+            .map(|frame| frame[0])
+            .collect();
+        assert!(frames[..10].iter().all(|&s| s == 0.0));
+        assert!(frames[10..20].iter().all(|&s| s == 1.0));
+        assert!(frames[20..25].iter().all(|&s| s == 0.0));
+        assert!(frames[25..35].iter().all(|&s| s == 2.0));
+        assert!(frames[35..].iter().all(|&s| s == 0.0));
+        assert_eq!(clock.now(), 35);
+    }
+}
@@ -0,0 +1,264 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::math;
+use crate::{Sample, Source};
+
+/// Internal function that builds a `SmoothTransitions` object.
+pub fn smooth_transitions<I>(input: I, fade: Duration) -> SmoothTransitions<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    let format = (input.channels(), input.sample_rate());
+    SmoothTransitions {
+        input,
+        fade,
+        format,
+        tail: VecDeque::new(),
+        tail_format: format,
+        fade_out_len: 0,
+        peeked: None,
+        fade_in_len: 0,
+        fade_in_left: 0,
+    }
+}
+
+/// Dips the volume where the channel count or sample rate changes, see
+/// [`Source::smooth_transitions`].
+#[derive(Clone, Debug)]
+pub struct SmoothTransitions<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    input: I,
+    fade: Duration,
+    // Format of the samples last read from the input.
+    format: (ChannelCount, SampleRate),
+    // End of a span read ahead to find out the format of the next span.
+    tail: VecDeque<I::Item>,
+    tail_format: (ChannelCount, SampleRate),
+    // Number of samples in `tail` faded out, zero if the next span has the same format.
+    fade_out_len: usize,
+    // First sample of the span after `tail`.
+    peeked: Option<I::Item>,
+    fade_in_len: usize,
+    fade_in_left: usize,
+}
+
+impl<I> SmoothTransitions<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+
+    fn fade_len(&self, (channels, sample_rate): (ChannelCount, SampleRate)) -> usize {
+        math::duration_to_frames(self.fade, sample_rate) as usize * channels as usize
+    }
+
+    fn start_fade_in(&mut self) {
+        self.fade_in_len = self.fade_len(self.format);
+        self.fade_in_left = self.fade_in_len;
+    }
+
+    // Reads the rest of the current span and the first sample of the next one.
+    fn read_tail(&mut self, len: usize) {
+        self.tail_format = self.format;
+        self.tail.extend(self.input.by_ref().take(len));
+        self.peeked = self.input.next();
+        let next_format = (self.input.channels(), self.input.sample_rate());
+        if self.peeked.is_some() && next_format != self.tail_format {
+            self.fade_out_len = self.tail.len();
+            self.format = next_format;
+            self.start_fade_in();
+        } else {
+            self.fade_out_len = 0;
+        }
+    }
+}
+
+impl<I> Iterator for SmoothTransitions<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        if self.tail.is_empty() && self.peeked.is_none() {
+            if let Some(len) = self.input.current_span_len() {
+                if len > 0 && len <= self.fade_len(self.format) {
+                    self.read_tail(len);
+                }
+            }
+        }
+
+        if let Some(sample) = self.tail.pop_front() {
+            if self.tail.len() >= self.fade_out_len {
+                return Some(sample);
+            }
+            let channels = self.tail_format.0 as usize;
+            let frames = self.fade_out_len.div_ceil(channels);
+            let frames_left = self.tail.len() / channels;
+            return Some(sample.amplify(frames_left as f32 / frames as f32));
+        }
+
+        let sample = match self.peeked.take() {
+            Some(sample) => sample,
+            None => {
+                let sample = self.input.next()?;
+                // Without a known span length the change is only noticed afterwards.
+                let format = (self.input.channels(), self.input.sample_rate());
+                if format != self.format {
+                    self.format = format;
+                    self.start_fade_in();
+                }
+                sample
+            }
+        };
+
+        if self.fade_in_left == 0 {
+            return Some(sample);
+        }
+        let channels = self.format.0 as usize;
+        let frame = (self.fade_in_len - self.fade_in_left) / channels;
+        self.fade_in_left -= 1;
+        let frames = self.fade_in_len / channels;
+        Some(sample.amplify(frame as f32 / frames as f32))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let buffered = self.tail.len() + self.peeked.is_some() as usize;
+        let (lower, upper) = self.input.size_hint();
+        (
+            lower.saturating_add(buffered),
+            upper.and_then(|upper| upper.checked_add(buffered)),
+        )
+    }
+}
+
+impl<I> ExactSizeIterator for SmoothTransitions<I>
+where
+    I: Source + ExactSizeIterator,
+    I::Item: Sample,
+{
+}
+
+impl<I> Source for SmoothTransitions<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        if !self.tail.is_empty() {
+            return Some(self.tail.len());
+        }
+        let peeked = self.peeked.is_some() as usize;
+        self.input
+            .current_span_len()
+            .map(|len| len.saturating_add(peeked))
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        if self.tail.is_empty() {
+            self.input.channels()
+        } else {
+            self.tail_format.0
+        }
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        if self.tail.is_empty() {
+            self.input.sample_rate()
+        } else {
+            self.tail_format.1
+        }
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.format = (self.input.channels(), self.input.sample_rate());
+        self.tail.clear();
+        self.fade_out_len = 0;
+        self.peeked = None;
+        self.fade_in_left = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::buffer::SamplesBuffer;
+    use crate::source::from_iter;
+    use crate::Source;
+
+    #[test]
+    fn dips_at_format_change() {
+        // 100 ms of mono at 1 kHz followed by 100 ms of stereo at 2 kHz.
+        let input = from_iter([
+            SamplesBuffer::new(1, 1000, vec![1.0f32; 100]),
+            SamplesBuffer::new(2, 2000, vec![1.0f32; 400]),
+        ]);
+        let smoothed = input.smooth_transitions(Duration::from_millis(10));
+        let samples: Vec<f32> = smoothed.collect();
+        assert_eq!(samples.len(), 500);
+
+        // The last 10 ms before the change fade out, the first 10 ms after it fade in.
+        assert!(samples[..90].iter().all(|&s| s == 1.0));
+        assert!(samples[90..100].windows(2).all(|w| w[1] < w[0]));
+        assert_eq!(samples[99], 0.0);
+        assert_eq!(samples[100], 0.0);
+        assert_eq!(samples[101], 0.0);
+        assert!(samples[100..140].windows(2).all(|w| w[1] >= w[0]));
+        assert!(samples[139] < 1.0);
+        assert!(samples[140..].iter().all(|&s| s == 1.0));
+    }
+
+    #[test]
+    fn same_format_is_untouched() {
+        let input = from_iter([
+            SamplesBuffer::new(2, 1000, vec![1.0f32; 20]),
+            SamplesBuffer::new(2, 1000, vec![1.0f32; 20]),
+        ]);
+        let smoothed = input.smooth_transitions(Duration::from_millis(10));
+        assert!(smoothed.collect::<Vec<f32>>().iter().all(|&s| s == 1.0));
+    }
+}
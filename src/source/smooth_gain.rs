@@ -0,0 +1,211 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Internal function that builds a `SmoothGain` object.
+pub fn smooth_gain<I>(input: I, time_constant: Duration) -> SmoothGain<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    SmoothGain {
+        input,
+        target: GainHandle {
+            gain: Arc::new(AtomicU32::new(1.0f32.to_bits())),
+        },
+        gain: 1.0,
+        time_constant,
+        coeff: None,
+        channel: 0,
+    }
+}
+
+/// Sets the gain of a [`SmoothGain`] from anywhere, for example from the thread
+/// running the user interface.
+#[derive(Clone, Debug)]
+pub struct GainHandle {
+    // Bits of the `f32` gain the source moves towards.
+    gain: Arc<AtomicU32>,
+}
+
+impl GainHandle {
+    /// Returns the gain the source moves towards.
+    #[inline]
+    pub fn gain(&self) -> f32 {
+        f32::from_bits(self.gain.load(Ordering::Relaxed))
+    }
+
+    /// Sets the gain the source moves towards. `1.0` leaves the sound unchanged.
+    #[inline]
+    pub fn set_gain(&self, gain: f32) {
+        self.gain.store(gain.to_bits(), Ordering::Relaxed);
+    }
+}
+
+/// Glides to every new gain instead of jumping, see [`Source::smooth_gain`].
+#[derive(Clone, Debug)]
+pub struct SmoothGain<I> {
+    input: I,
+    target: GainHandle,
+    // Gain applied to the current frame.
+    gain: f32,
+    time_constant: Duration,
+    // Smoothing coefficient and the sample rate it was computed for.
+    coeff: Option<(f32, SampleRate)>,
+    // Channel of the next sample.
+    channel: usize,
+}
+
+impl<I> SmoothGain<I> {
+    /// Returns a handle that changes the gain while the source plays.
+    #[inline]
+    pub fn handle(&self) -> GainHandle {
+        self.target.clone()
+    }
+
+    /// Sets the gain the source moves towards, see [`GainHandle::set_gain`].
+    #[inline]
+    pub fn set_gain(&mut self, gain: f32) {
+        self.target.set_gain(gain);
+    }
+
+    /// Returns the gain applied to the last frame.
+    #[inline]
+    pub fn current_gain(&self) -> f32 {
+        self.gain
+    }
+
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+}
+
+impl<I> SmoothGain<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    fn start_frame(&mut self) {
+        let rate = self.input.sample_rate();
+        let coeff = match self.coeff {
+            Some((coeff, coeff_rate)) if coeff_rate == rate => coeff,
+            _ => {
+                // Covers 63% of the distance to the target every `time_constant`.
+                let frames = self.time_constant.as_secs_f32() * rate as f32;
+                let coeff = 1.0 - (-1.0 / frames).exp();
+                self.coeff = Some((coeff, rate));
+                coeff
+            }
+        };
+        self.gain += coeff * (self.target.gain() - self.gain);
+    }
+}
+
+impl<I> Iterator for SmoothGain<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        let sample = self.input.next()?;
+        if self.channel == 0 {
+            self.start_frame();
+        }
+        self.channel = (self.channel + 1) % self.input.channels() as usize;
+        Some(sample.amplify(self.gain))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for SmoothGain<I>
+where
+    I: Source + ExactSizeIterator,
+    I::Item: Sample,
+{
+}
+
+impl<I> Source for SmoothGain<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.channel = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::buffer::SamplesBuffer;
+    use crate::Source;
+
+    #[test]
+    fn ramps_to_new_gain() {
+        let mut source =
+            SamplesBuffer::new(1, 1000, vec![1.0f32; 1000]).smooth_gain(Duration::from_millis(10));
+        assert!(source.by_ref().take(100).all(|s| s == 1.0));
+
+        source.handle().set_gain(0.0);
+        let samples: Vec<f32> = source.take(100).collect();
+        assert!(samples[0] > 0.85);
+        assert!(samples.windows(2).all(|w| w[1] < w[0]));
+        // One time constant later 1/e of the step is left.
+        assert!((samples[9] - (-1.0f32).exp()).abs() < 1e-3);
+        assert!(samples[99] < 1e-4);
+    }
+}
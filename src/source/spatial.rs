@@ -1,3 +1,4 @@
+use std::f32::consts::{PI, TAU};
 use std::time::Duration;
 
 use super::SeekError;
@@ -122,8 +123,256 @@ where
         self.input.total_duration()
     }
 
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
     #[inline]
     fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
         self.input.try_seek(pos)
     }
 }
+
+/// Arrangement of the speakers a [`SpatialSurround`] source plays on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpeakerLayout {
+    /// Front left and front right.
+    Stereo,
+    /// Front left, front right, rear left and rear right.
+    Quad,
+    /// Front left, front right, center, LFE, surround left and surround right.
+    Surround51,
+}
+
+impl SpeakerLayout {
+    /// Returns the number of channels of the layout.
+    pub fn channels(self) -> ChannelCount {
+        self.azimuths().len() as ChannelCount
+    }
+
+    /// Returns the direction of each speaker in channel order, in radians clockwise
+    /// from straight ahead. The LFE channel has no direction.
+    pub fn azimuths(self) -> &'static [Option<f32>] {
+        const DEG: f32 = PI / 180.0;
+        match self {
+            SpeakerLayout::Stereo => &[Some(-30.0 * DEG), Some(30.0 * DEG)],
+            SpeakerLayout::Quad => &[
+                Some(-45.0 * DEG),
+                Some(45.0 * DEG),
+                Some(-135.0 * DEG),
+                Some(135.0 * DEG),
+            ],
+            SpeakerLayout::Surround51 => &[
+                Some(-30.0 * DEG),
+                Some(30.0 * DEG),
+                Some(0.0),
+                None,
+                Some(-110.0 * DEG),
+                Some(110.0 * DEG),
+            ],
+        }
+    }
+
+    // The layout with `channels` channels, if there is one.
+    pub(crate) fn from_channels(channels: ChannelCount) -> Option<SpeakerLayout> {
+        [
+            SpeakerLayout::Stereo,
+            SpeakerLayout::Quad,
+            SpeakerLayout::Surround51,
+        ]
+        .into_iter()
+        .find(|layout| layout.channels() == channels)
+    }
+
+    // Constant power gains that place a sound at `azimuth` between the two speakers
+    // closest to it.
+    pub(crate) fn pan_gains(self, azimuth: f32) -> Vec<f32> {
+        let azimuths = self.azimuths();
+        let mut gains = vec![0.0; azimuths.len()];
+        let clockwise = |from: f32, to: f32| (to - from).rem_euclid(TAU);
+
+        // The speaker at or counter-clockwise from the sound, and the next one clockwise.
+        let directional = || {
+            azimuths
+                .iter()
+                .enumerate()
+                .filter_map(|(channel, azimuth)| Some((channel, (*azimuth)?)))
+        };
+        let Some((left, left_azimuth)) = directional()
+            .min_by(|a, b| clockwise(a.1, azimuth).total_cmp(&clockwise(b.1, azimuth)))
+        else {
+            return gains;
+        };
+        let (right, right_azimuth) = directional()
+            .filter(|&(channel, _)| channel != left)
+            .min_by(|a, b| clockwise(left_azimuth, a.1).total_cmp(&clockwise(left_azimuth, b.1)))
+            .unwrap_or((left, left_azimuth));
+
+        let span = clockwise(left_azimuth, right_azimuth);
+        let t = if span > 0.0 {
+            clockwise(left_azimuth, azimuth) / span
+        } else {
+            0.0
+        };
+        gains[left] = (t * PI / 2.0).cos();
+        gains[right] += (t * PI / 2.0).sin();
+        gains
+    }
+}
+
+/// A spatial audio source for surround speaker setups. The underlying source is
+/// transformed to mono and panned between the two speakers of the [`SpeakerLayout`]
+/// closest to the direction of the emitter.
+///
+/// The listener is at the origin looking towards negative z, with positive x to
+/// the right and positive y up.
+#[derive(Clone)]
+pub struct SpatialSurround<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    input: ChannelVolume<I>,
+    layout: SpeakerLayout,
+}
+
+impl<I> SpatialSurround<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    /// Builds a new `SpatialSurround` with the emitter at `emitter_position`.
+    pub fn new(input: I, layout: SpeakerLayout, emitter_position: [f32; 3]) -> SpatialSurround<I> {
+        let mut ret = SpatialSurround {
+            input: ChannelVolume::new(input, vec![0.0; layout.channels() as usize]),
+            layout,
+        };
+        ret.set_position(emitter_position);
+        ret
+    }
+
+    /// Sets the position of the emitter relative to the listener.
+    pub fn set_position(&mut self, emitter_position: [f32; 3]) {
+        let [x, _, z] = emitter_position;
+        let azimuth = x.atan2(-z);
+        let dist_modifier = (1.0 / dist_sq([0.0; 3], emitter_position)).min(1.0);
+        for (channel, gain) in self.layout.pan_gains(azimuth).into_iter().enumerate() {
+            self.input.set_volume(channel, gain * dist_modifier);
+        }
+    }
+
+    /// Returns the speaker layout the source plays on.
+    pub fn layout(&self) -> SpeakerLayout {
+        self.layout
+    }
+}
+
+impl<I> Iterator for SpatialSurround<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        self.input.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for SpatialSurround<I>
+where
+    I: Source + ExactSizeIterator,
+    I::Item: Sample,
+{
+}
+
+impl<I> Source for SpatialSurround<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SpatialSurround, SpeakerLayout};
+    use crate::buffer::SamplesBuffer;
+    use crate::Source;
+
+    fn channel_energy(source: SpatialSurround<SamplesBuffer<f32>>) -> Vec<f32> {
+        let channels = source.channels() as usize;
+        let mut energy = vec![0.0; channels];
+        for (i, sample) in source.enumerate() {
+            energy[i % channels] += sample * sample;
+        }
+        energy
+    }
+
+    #[test]
+    fn quad_front_right() {
+        let input = SamplesBuffer::new(1, 48000, vec![0.5f32; 100]);
+        let source = SpatialSurround::new(input, SpeakerLayout::Quad, [1.0, 0.0, -1.0]);
+        assert_eq!(source.channels(), 4);
+
+        let energy = channel_energy(source);
+        assert!(energy[1] > 0.0);
+        assert!(energy[1] > 100.0 * (energy[0] + energy[2] + energy[3]));
+    }
+
+    #[test]
+    fn quad_between_front_speakers() {
+        let input = SamplesBuffer::new(1, 48000, vec![0.5f32; 100]);
+        let source = SpatialSurround::new(input, SpeakerLayout::Quad, [0.0, 0.0, -1.0]);
+
+        let energy = channel_energy(source);
+        assert!((energy[0] - energy[1]).abs() < 1e-3);
+        assert!(energy[2] < 1e-6 && energy[3] < 1e-6);
+    }
+
+    #[test]
+    fn surround51_skips_lfe() {
+        let input = SamplesBuffer::new(1, 48000, vec![0.5f32; 100]);
+        let source = SpatialSurround::new(input, SpeakerLayout::Surround51, [0.0, 0.0, -1.0]);
+        assert_eq!(source.channels(), 6);
+
+        let energy = channel_energy(source);
+        assert_eq!(energy[3], 0.0);
+        assert!(energy[2] > 100.0 * (energy[0] + energy[1] + energy[4] + energy[5]));
+    }
+}
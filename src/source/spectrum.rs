@@ -0,0 +1,90 @@
+use realfft::RealFftPlanner;
+
+use super::WindowFunction;
+use crate::{Sample, Source};
+
+/// Internal function that measures the spectrum of a source, see
+/// [`Source::power_spectrum`].
+pub fn power_spectrum<I>(mut input: I, fft_size: usize) -> Vec<f32>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    assert!(fft_size > 0, "the FFT needs at least one frame");
+    let fft = RealFftPlanner::<f32>::new().plan_fft_forward(fft_size);
+    // The periodic Hann window is the symmetric one that is one frame longer.
+    let window: Vec<f32> = (0..fft_size)
+        .map(|frame| WindowFunction::Hann.gain(frame, fft_size + 1))
+        .collect();
+    // Doubled because only the positive frequencies are returned.
+    let scale = 2.0 / window.iter().sum::<f32>().powi(2);
+
+    let mut block = fft.make_input_vec();
+    let mut spectrum = fft.make_output_vec();
+    let mut power = vec![0.0; spectrum.len()];
+    let mut blocks = 0;
+    let mut filled = 0;
+    loop {
+        let channels = input.channels() as usize;
+        let mut sum = 0.0;
+        let mut count = 0;
+        for sample in input.by_ref().take(channels) {
+            sum += sample.to_f32();
+            count += 1;
+        }
+        if count > 0 {
+            block[filled] = sum / count as f32 * window[filled];
+            filled += 1;
+        }
+        if filled == fft_size || (count == 0 && filled > 0) {
+            block[filled..].fill(0.0);
+            fft.process(&mut block, &mut spectrum)
+                .expect("the buffers are made by the FFT");
+            for (power, bin) in power.iter_mut().zip(&spectrum) {
+                *power += bin.norm_sqr() * scale;
+            }
+            blocks += 1;
+            filled = 0;
+        }
+        if count == 0 {
+            break;
+        }
+    }
+
+    if blocks > 0 {
+        for power in &mut power {
+            *power /= blocks as f32;
+        }
+    }
+    // The zero and Nyquist frequency have no negative counterpart.
+    power[0] /= 2.0;
+    if fft_size & 1 == 0 {
+        power[fft_size / 2] /= 2.0;
+    }
+    power
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::source::{Function, SignalGenerator};
+    use crate::Source;
+
+    #[test]
+    fn sine_peaks_at_its_bin() {
+        let sine = SignalGenerator::new(48000, 1000.0, Function::Sine)
+            .amplify(0.5)
+            .take_duration(Duration::from_secs(1));
+        // Bins are 100 Hz apart.
+        let spectrum = sine.power_spectrum(480);
+        assert_eq!(spectrum.len(), 241);
+
+        assert!((spectrum[10] - 0.125).abs() < 1e-3);
+        for (bin, power) in spectrum.iter().enumerate() {
+            if !(9..=11).contains(&bin) {
+                assert!(*power < 1e-6, "bin {bin} has power {power}");
+            }
+        }
+    }
+}
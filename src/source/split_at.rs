@@ -0,0 +1,58 @@
+use std::time::Duration;
+
+use crate::buffer::SamplesBuffer;
+use crate::math;
+use crate::{Sample, Source};
+
+/// Internal function that splits a source in two, see [`Source::split_at`].
+pub fn split_at<I>(input: I, point: Duration) -> (SamplesBuffer<I::Item>, SamplesBuffer<I::Item>)
+where
+    I: Source,
+    I::Item: Sample,
+{
+    let channels = input.channels();
+    let sample_rate = input.sample_rate();
+    let mut samples: Vec<I::Item> = input.collect();
+
+    let frames = math::duration_to_frames(point, sample_rate);
+    let split = usize::try_from(frames)
+        .unwrap_or(usize::MAX)
+        .saturating_mul(channels as usize)
+        .min(samples.len());
+    let after = samples.split_off(split);
+    (
+        SamplesBuffer::new(channels, sample_rate, samples),
+        SamplesBuffer::new(channels, sample_rate, after),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::buffer::SamplesBuffer;
+    use crate::Source;
+
+    #[test]
+    fn halves_concatenate_to_original() {
+        let counter: Vec<f32> = (0..2000).map(|n| n as f32).collect();
+        let input = SamplesBuffer::new(2, 1000, counter.clone());
+
+        let (before, after) = input.split_at(Duration::from_millis(500));
+        assert_eq!(before.total_duration(), Some(Duration::from_millis(500)));
+        assert_eq!(after.total_duration(), Some(Duration::from_millis(500)));
+        assert_eq!(after.channels(), 2);
+        assert_eq!(after.sample_rate(), 1000);
+
+        let joined: Vec<f32> = before.chain(after).collect();
+        assert_eq!(joined, counter);
+    }
+
+    #[test]
+    fn point_past_end() {
+        let input = SamplesBuffer::new(1, 1000, vec![1.0f32; 10]);
+        let (before, after) = input.split_at(Duration::from_secs(1));
+        assert_eq!(before.count(), 10);
+        assert_eq!(after.count(), 0);
+    }
+}
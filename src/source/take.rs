@@ -171,17 +171,18 @@ where
 
     #[inline]
     fn total_duration(&self) -> Option<Duration> {
-        if let Some(duration) = self.input.total_duration() {
-            if duration < self.requested_duration {
-                Some(duration)
-            } else {
-                Some(self.requested_duration)
-            }
-        } else {
-            None
+        // Without a known duration the input is assumed to last at least as long.
+        match self.input.total_duration() {
+            Some(duration) => Some(duration.min(self.requested_duration)),
+            None => Some(self.requested_duration),
         }
     }
 
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
     #[inline]
     fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
         self.input.try_seek(pos)
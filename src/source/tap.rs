@@ -0,0 +1,151 @@
+use std::sync::mpsc::{SyncSender, TrySendError};
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Internal function that builds a `Tap` object.
+pub fn tap<I>(input: I, tx: SyncSender<I::Item>) -> Tap<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    Tap {
+        input,
+        tx: Some(tx),
+    }
+}
+
+/// Sends a copy of every sample to a channel, see [`Source::tap`].
+#[derive(Clone, Debug)]
+pub struct Tap<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    input: I,
+    // `None` once the receiver is gone.
+    tx: Option<SyncSender<I::Item>>,
+}
+
+impl<I> Tap<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+}
+
+impl<I> Iterator for Tap<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        let sample = self.input.next()?;
+        if let Some(tx) = &self.tx {
+            // Never block the audio thread, drop the sample if the channel is full.
+            if let Err(TrySendError::Disconnected(_)) = tx.try_send(sample) {
+                self.tx = None;
+            }
+        }
+        Some(sample)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for Tap<I>
+where
+    I: Source + ExactSizeIterator,
+    I::Item: Sample,
+{
+}
+
+impl<I> Source for Tap<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::sync_channel;
+
+    use crate::buffer::SamplesBuffer;
+    use crate::Source;
+
+    #[test]
+    fn receiver_gets_the_samples() {
+        let samples = vec![1.0f32, -0.5, 0.25, 0.0];
+        let (tx, rx) = sync_channel(16);
+        let played: Vec<f32> = SamplesBuffer::new(1, 1000, samples.clone())
+            .tap(tx)
+            .collect();
+        assert_eq!(played, samples);
+        assert_eq!(rx.try_iter().collect::<Vec<f32>>(), samples);
+    }
+
+    #[test]
+    fn full_channel_drops_samples() {
+        let (tx, rx) = sync_channel(2);
+        let played = SamplesBuffer::new(1, 1000, vec![1.0f32; 100])
+            .tap(tx)
+            .count();
+        assert_eq!(played, 100);
+        assert_eq!(rx.try_iter().count(), 2);
+    }
+}
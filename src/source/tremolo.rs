@@ -0,0 +1,170 @@
+use std::f32::consts::TAU;
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Internal function that builds a `Tremolo` object.
+pub fn tremolo<I>(input: I, rate_hz: f32, depth: f32) -> Tremolo<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    Tremolo {
+        input,
+        rate_hz,
+        depth: depth.clamp(0.0, 1.0),
+        phase: 0.0,
+        gain: 1.0,
+        channel: 0,
+    }
+}
+
+/// Makes the volume of a sound rise and fall periodically, see [`Source::tremolo`].
+#[derive(Clone, Debug)]
+pub struct Tremolo<I> {
+    input: I,
+    rate_hz: f32,
+    depth: f32,
+    // Position of the LFO in its cycle, between 0 and 1.
+    phase: f32,
+    // Gain for the current frame.
+    gain: f32,
+    // Channel of the next input sample.
+    channel: usize,
+}
+
+impl<I> Tremolo<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    /// Changes how many times per second the volume dips and comes back.
+    #[inline]
+    pub fn set_rate(&mut self, rate_hz: f32) {
+        self.rate_hz = rate_hz;
+    }
+
+    /// Changes how deep the volume dips, `1.0` goes down to silence.
+    #[inline]
+    pub fn set_depth(&mut self, depth: f32) {
+        self.depth = depth.clamp(0.0, 1.0);
+    }
+
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+
+    fn start_frame(&mut self) {
+        self.gain = 1.0 - self.depth * (0.5 - 0.5 * (TAU * self.phase).cos());
+        // Advanced by the sample rate of every frame, so the rate holds across spans
+        // with different sample rates.
+        self.phase = (self.phase + self.rate_hz / self.input.sample_rate() as f32).fract();
+    }
+}
+
+impl<I> Iterator for Tremolo<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        let sample = self.input.next()?;
+        if self.channel == 0 {
+            self.start_frame();
+        }
+        self.channel = (self.channel + 1) % self.input.channels() as usize;
+        Some(sample.amplify(self.gain))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for Tremolo<I>
+where
+    I: Source + ExactSizeIterator,
+    I::Item: Sample,
+{
+}
+
+impl<I> Source for Tremolo<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.phase = (pos.as_secs_f64() * self.rate_hz as f64).fract() as f32;
+        self.channel = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::buffer::SamplesBuffer;
+    use crate::Source;
+
+    #[test]
+    fn envelope_repeats_at_rate() {
+        let input = SamplesBuffer::new(2, 1000, vec![1.0f32; 2000]);
+        let frames: Vec<f32> = input.tremolo(4.0, 1.0).collect();
+        let envelope: Vec<f32> = frames.chunks(2).map(|f| f[0]).collect();
+        assert!(frames.chunks(2).all(|f| f[0] == f[1]));
+
+        // Full volume at the start of each 250 ms cycle, silent halfway through.
+        for cycle in 0..4 {
+            let start = cycle * 250;
+            assert!((envelope[start] - 1.0).abs() < 1e-4, "cycle {cycle}");
+            assert!(envelope[start + 125] < 1e-4, "cycle {cycle}");
+            assert!((envelope[start + 62] - 0.5).abs() < 0.02, "cycle {cycle}");
+        }
+    }
+}
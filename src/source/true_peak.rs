@@ -0,0 +1,579 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use dasp_sample::FromSample;
+
+use super::resample::{resample, Resample, ResampleConfig};
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::math;
+use crate::{Sample, Source};
+
+// Time over which the gain is lowered ahead of a peak.
+const LOOKAHEAD_TIME: f32 = 0.001;
+// Time for the gain to recover 63% of the way after a peak was limited.
+const RELEASE_TIME: f32 = 0.05;
+
+// Oversampling used by the platform presets, the factor ITU-R BS.1770 measures true
+// peaks with.
+const PLATFORM_OVERSAMPLE: usize = 4;
+
+/// Streaming platform whose true peak ceiling a sound is limited to, see
+/// [`Source::limit_true_peak_for_platform`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StreamingPlatform {
+    /// Spotify, -1 dBTP.
+    Spotify,
+    /// YouTube, -1 dBTP.
+    YouTube,
+    /// Apple Music, -1 dBTP as asked for by Apple Digital Masters.
+    AppleMusic,
+    /// Amazon Music, -2 dBTP.
+    AmazonMusic,
+}
+
+impl StreamingPlatform {
+    /// Returns the highest true peak the platform accepts, in dBTP.
+    pub fn true_peak_ceiling_db(self) -> f32 {
+        match self {
+            StreamingPlatform::Spotify
+            | StreamingPlatform::YouTube
+            | StreamingPlatform::AppleMusic => -1.0,
+            StreamingPlatform::AmazonMusic => -2.0,
+        }
+    }
+}
+
+/// How a [`TruePeakLimit`] lowers the gain of the channels of a sound, see
+/// [`TruePeakLimit::with_channel_link`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LinkMode {
+    /// A peak on any channel lowers all of them by the same amount, which keeps the
+    /// stereo image in place.
+    #[default]
+    Coupled,
+    /// Every channel is limited on its own. This is more transparent for channels
+    /// that carry unrelated content, but a peak on one side shifts the image.
+    Independent,
+}
+
+/// Internal function that builds a `TruePeakLimit` object for a platform.
+pub fn limit_true_peak_for_platform<I>(input: I, platform: StreamingPlatform) -> TruePeakLimit<I>
+where
+    I: Source,
+    I::Item: Sample + FromSample<f32>,
+{
+    true_peak_limit(input, platform.true_peak_ceiling_db(), PLATFORM_OVERSAMPLE)
+}
+
+/// Internal function that builds a `TruePeakLimit` object.
+pub fn true_peak_limit<I>(input: I, ceiling_db: f32, oversample: usize) -> TruePeakLimit<I>
+where
+    I: Source,
+    I::Item: Sample + FromSample<f32>,
+{
+    assert!(oversample > 0, "the oversampling factor must be at least 1");
+    let sample_rate = input.sample_rate();
+    let config = ResampleConfig::balanced();
+    let upsampled = resample(input, sample_rate * oversample as SampleRate, config);
+    let limiter = OversampledLimiter::new(upsampled, ceiling_db);
+    TruePeakLimit {
+        input: resample(limiter, sample_rate, config),
+    }
+}
+
+/// Limits the peaks between samples, see [`Source::true_peak_limit`].
+pub struct TruePeakLimit<I> {
+    input: Resample<OversampledLimiter<I>>,
+}
+
+impl<I> std::fmt::Debug for TruePeakLimit<I>
+where
+    I: Source + std::fmt::Debug,
+    I::Item: Sample + FromSample<f32>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TruePeakLimit")
+            .field("input", self.inner_limiter().input.inner())
+            .field("ceiling", &self.inner_limiter().ceiling)
+            .field("link", &self.inner_limiter().link)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<I> TruePeakLimit<I>
+where
+    I: Source,
+    I::Item: Sample + FromSample<f32>,
+{
+    fn inner_limiter(&self) -> &OversampledLimiter<I> {
+        self.input.inner()
+    }
+
+    /// Chooses whether a peak on one channel lowers the gain of the others, the
+    /// default is [`LinkMode::Coupled`].
+    #[inline]
+    pub fn with_channel_link(mut self, link: LinkMode) -> Self {
+        self.input.inner_mut().set_link(link);
+        self
+    }
+
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        self.input.inner().input.inner()
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        self.input.inner_mut().input.inner_mut()
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input.into_inner().input.into_inner()
+    }
+}
+
+impl<I> Iterator for TruePeakLimit<I>
+where
+    I: Source,
+    I::Item: Sample + FromSample<f32>,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        self.input.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<I> Source for TruePeakLimit<I>
+where
+    I: Source,
+    I::Item: Sample + FromSample<f32>,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)
+    }
+}
+
+// Limits the oversampled signal so no sample exceeds the ceiling. By default all
+// channels share the gain to keep the stereo image in place.
+//
+// Sudden gain changes would add content above the original Nyquist frequency that
+// pushes the peaks right back up when converting down. So the gain is the average of
+// the lowest gains required over the next `lookahead` frames, that ramps down ahead
+// of a peak and is still low enough for every frame.
+struct OversampledLimiter<I> {
+    input: Resample<I>,
+    channels: usize,
+    ceiling: f32,
+    release_coeff: f32,
+    lookahead: usize,
+    link: LinkMode,
+    // Samples read but not yet played.
+    delayed: VecDeque<f32>,
+    // Index of the next frame to read. Counts the `lookahead - 1` frames of silence
+    // assumed before the input and the silent frames used to flush at the end.
+    frames_read: u64,
+    // Gains computed for the silence before the input that still have to be dropped.
+    gains_to_skip: usize,
+    // One gain for all channels when coupled, else one for every channel.
+    trackers: Vec<GainTracker>,
+    // Gains of one frame, one for every tracker.
+    frame_gains: Vec<f32>,
+    // The frame being played, already limited.
+    frame: Vec<f32>,
+    frame_pos: usize,
+}
+
+// Follows the gain of the channels that share it.
+#[derive(Default)]
+struct GainTracker {
+    // Gains for the frames at the front of `delayed`.
+    gains: VecDeque<f32>,
+    // Required gains over the last `lookahead` frames that might still be the lowest.
+    required: VecDeque<(u64, f32)>,
+    // The last `lookahead` held gains and their sum.
+    held: VecDeque<f32>,
+    held_sum: f64,
+}
+
+impl GainTracker {
+    fn reset(&mut self, lookahead: usize) {
+        self.gains.clear();
+        self.required.clear();
+        self.held.clear();
+        self.held.resize(lookahead, 1.0);
+        self.held_sum = lookahead as f64;
+    }
+
+    // Adds the gain frame `index` needs. Once the gains required over the lookahead
+    // of the frame `lookahead - 1` before it are known, its gain is computed.
+    fn push_required(&mut self, index: u64, gain: f32, lookahead: usize, release_coeff: f32) {
+        while self.required.back().is_some_and(|&(_, g)| g >= gain) {
+            self.required.pop_back();
+        }
+        self.required.push_back((index, gain));
+        let Some(first) = (index + 1).checked_sub(lookahead as u64) else {
+            return;
+        };
+        while self.required.front().is_some_and(|&(i, _)| i < first) {
+            self.required.pop_front();
+        }
+
+        // The gain for frame `first` is now known.
+        let lowest = self.required.front().map_or(1.0, |&(_, g)| g);
+        let previous = *self.held.back().expect("`held` is never empty");
+        let released = 1.0 - (1.0 - previous) * release_coeff;
+        let held = lowest.min(released);
+        self.held_sum += (held - self.held.pop_front().expect("`held` is never empty")) as f64;
+        self.held.push_back(held);
+        self.gains
+            .push_back((self.held_sum / lookahead as f64) as f32);
+    }
+}
+
+impl<I> OversampledLimiter<I>
+where
+    I: Source,
+    I::Item: Sample + FromSample<f32>,
+{
+    fn new(input: Resample<I>, ceiling_db: f32) -> Self {
+        let sample_rate = input.sample_rate() as f32;
+        let lookahead = ((LOOKAHEAD_TIME * sample_rate).round() as usize).max(1);
+        let mut limiter = OversampledLimiter {
+            channels: input.channels() as usize,
+            input,
+            ceiling: 10f32.powf(ceiling_db / 20.0),
+            release_coeff: (-1.0 / (RELEASE_TIME * sample_rate)).exp(),
+            lookahead,
+            link: LinkMode::Coupled,
+            delayed: VecDeque::new(),
+            frames_read: 0,
+            gains_to_skip: 0,
+            trackers: Vec::new(),
+            frame_gains: Vec::new(),
+            frame: Vec::new(),
+            frame_pos: 0,
+        };
+        limiter.reset();
+        limiter
+    }
+
+    fn set_link(&mut self, link: LinkMode) {
+        self.link = link;
+        self.reset();
+    }
+
+    // Index of the tracker following the gain of `channel`.
+    fn tracker_of(&self, channel: usize) -> usize {
+        match self.link {
+            LinkMode::Coupled => 0,
+            LinkMode::Independent => channel,
+        }
+    }
+
+    fn reset(&mut self) {
+        let trackers = match self.link {
+            LinkMode::Coupled => 1,
+            LinkMode::Independent => self.channels.max(1),
+        };
+        self.trackers.resize_with(trackers, GainTracker::default);
+        for tracker in &mut self.trackers {
+            tracker.reset(self.lookahead);
+        }
+        self.delayed.clear();
+        self.frames_read = 0;
+        self.frame.clear();
+        self.frame_pos = 0;
+        for _ in 1..self.lookahead {
+            self.frame_gains.clear();
+            self.frame_gains.resize(trackers, 1.0);
+            self.push_required();
+        }
+        self.gains_to_skip = self.lookahead - 1;
+    }
+
+    // Reads one frame and puts the gains it needs in `frame_gains`. Returns `false`
+    // if the input has ended.
+    fn read_frame(&mut self) -> bool {
+        self.frame_gains.clear();
+        self.frame_gains.resize(self.trackers.len(), 1.0);
+        for channel in 0..self.channels {
+            match self.input.next() {
+                Some(sample) => {
+                    let sample = sample.to_f32();
+                    let tracker = self.tracker_of(channel);
+                    let gain = &mut self.frame_gains[tracker];
+                    if sample.abs() > self.ceiling {
+                        *gain = gain.min(self.ceiling / sample.abs());
+                    }
+                    self.delayed.push_back(sample);
+                }
+                None => {
+                    // Drop the incomplete frame.
+                    self.delayed.truncate(self.delayed.len() - channel);
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    // Passes the gains in `frame_gains` of the frame read last to the trackers.
+    fn push_required(&mut self) {
+        let index = self.frames_read;
+        self.frames_read += 1;
+        for (tracker, &gain) in self.trackers.iter_mut().zip(&self.frame_gains) {
+            tracker.push_required(index, gain, self.lookahead, self.release_coeff);
+        }
+    }
+
+    // Limits the next frame. Returns `false` if the input has ended.
+    fn next_frame(&mut self) -> bool {
+        self.frame.clear();
+        self.frame_pos = 0;
+        loop {
+            if self.trackers[0].gains.is_empty() {
+                if !self.read_frame() {
+                    if self.delayed.is_empty() {
+                        return false;
+                    }
+                    // Flush the frames still waiting for their lookahead.
+                    self.frame_gains.clear();
+                    self.frame_gains.resize(self.trackers.len(), 1.0);
+                }
+                self.push_required();
+                continue;
+            }
+            self.frame_gains.clear();
+            for tracker in &mut self.trackers {
+                let gain = tracker.gains.pop_front().expect("trackers are in step");
+                self.frame_gains.push(gain);
+            }
+            if self.gains_to_skip == 0 {
+                break;
+            }
+            self.gains_to_skip -= 1;
+        }
+        for channel in 0..self.channels {
+            let gain = self.frame_gains[self.tracker_of(channel)];
+            let sample = self.delayed.pop_front().unwrap_or_default();
+            self.frame
+                .push((sample * gain).clamp(-self.ceiling, self.ceiling));
+        }
+        true
+    }
+}
+
+impl<I> Iterator for OversampledLimiter<I>
+where
+    I: Source,
+    I::Item: Sample + FromSample<f32>,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        if self.frame_pos >= self.frame.len() && !self.next_frame() {
+            return None;
+        }
+        let sample = self.frame[self.frame_pos];
+        self.frame_pos += 1;
+        Some(I::Item::from_sample_(sample))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let buffered = self.frame.len() - self.frame_pos + self.delayed.len();
+        let (lower, upper) = self.input.size_hint();
+        (
+            lower.saturating_add(buffered),
+            upper.and_then(|upper| upper.checked_add(buffered)),
+        )
+    }
+}
+
+impl<I> Source for OversampledLimiter<I>
+where
+    I: Source,
+    I::Item: Sample + FromSample<f32>,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        let buffered = self.frame.len() - self.frame_pos + self.delayed.len();
+        self.input
+            .current_span_len()
+            .map(|len| len.saturating_add(buffered))
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        let lookahead = math::frames_to_duration(self.lookahead as u64, self.input.sample_rate());
+        self.input.latency() + lookahead
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.reset();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+    use std::time::Duration;
+
+    use crate::buffer::SamplesBuffer;
+    use crate::source::{
+        FadeIn, LinkMode, ResampleConfig, StreamingPlatform, TruePeakLimit, UniformSourceIterator,
+    };
+    use crate::Source;
+
+    // A quarter sample rate sine shifted by 45 degrees, every sample lands halfway
+    // between the zero crossings and the true peaks. Fades in to avoid the overshoot
+    // of starting at full level.
+    fn inter_sample_peaks() -> FadeIn<SamplesBuffer<f32>> {
+        let samples = (0..4800)
+            .map(|n| (PI / 2.0 * n as f32 + PI / 4.0).sin())
+            .collect::<Vec<_>>();
+        SamplesBuffer::new(1, 48000, samples).fade_in(Duration::from_millis(2))
+    }
+
+    fn true_peak(source: impl Source<Item = f32>) -> f32 {
+        source
+            .resample(192000, ResampleConfig::accurate())
+            .fold(0.0, |peak, s| peak.max(s.abs()))
+    }
+
+    #[test]
+    fn limits_peaks_between_samples() {
+        let ceiling = 10f32.powf(-1.0 / 20.0);
+        assert!(inter_sample_peaks().all(|s| s.abs() < ceiling));
+        assert!(true_peak(inter_sample_peaks()) > 0.99);
+
+        let limited = inter_sample_peaks().true_peak_limit(-1.0, 4);
+        assert_eq!(limited.sample_rate(), 48000);
+        assert_eq!(limited.count(), 4800);
+        let peak = true_peak(inter_sample_peaks().true_peak_limit(-1.0, 4));
+        assert!(peak <= ceiling, "true peak {peak} above ceiling {ceiling}");
+    }
+
+    #[test]
+    fn platform_presets() {
+        assert_eq!(StreamingPlatform::Spotify.true_peak_ceiling_db(), -1.0);
+        assert_eq!(StreamingPlatform::YouTube.true_peak_ceiling_db(), -1.0);
+        assert_eq!(StreamingPlatform::AppleMusic.true_peak_ceiling_db(), -1.0);
+        assert_eq!(StreamingPlatform::AmazonMusic.true_peak_ceiling_db(), -2.0);
+
+        let ceiling = 10f32.powf(-2.0 / 20.0);
+        let limited =
+            inter_sample_peaks().limit_true_peak_for_platform(StreamingPlatform::AmazonMusic);
+        let peak = true_peak(limited);
+        assert!(peak <= ceiling, "true peak {peak} above ceiling {ceiling}");
+    }
+
+    #[test]
+    fn independent_link_leaves_quiet_channel_alone() {
+        // A loud tone on the left, a quiet one on the right.
+        let stereo = || {
+            let samples = (0..9600)
+                .flat_map(|n| {
+                    let tone = (2.0 * PI * 1000.0 * n as f32 / 48000.0).sin();
+                    [tone, 0.25 * tone]
+                })
+                .collect::<Vec<_>>();
+            SamplesBuffer::new(2, 48000, samples).fade_in(Duration::from_millis(2))
+        };
+        // Peak of the right channel after the limiter settled.
+        let right_peak = |source: TruePeakLimit<_>| {
+            let samples = source.collect::<Vec<f32>>();
+            samples[4800..]
+                .iter()
+                .skip(1)
+                .step_by(2)
+                .fold(0.0f32, |peak, s| peak.max(s.abs()))
+        };
+
+        let coupled = right_peak(stereo().true_peak_limit(-6.0, 4));
+        let independent = right_peak(
+            stereo()
+                .true_peak_limit(-6.0, 4)
+                .with_channel_link(LinkMode::Independent),
+        );
+        assert!(coupled < 0.15, "coupled right channel peaks at {coupled}");
+        assert!(
+            (independent - 0.25).abs() < 0.01,
+            "independent right channel peaks at {independent}"
+        );
+    }
+
+    #[test]
+    fn reports_lookahead_as_latency() {
+        let source = inter_sample_peaks().amplify(0.5);
+        assert_eq!(source.latency(), Duration::ZERO);
+
+        let chain = source
+            .true_peak_limit(-1.0, 4)
+            .fade_out(Duration::from_millis(10));
+        assert_eq!(chain.latency(), Duration::from_millis(1));
+
+        // Also through buffering and the conversions of a mixer.
+        let buffered = chain.buffered();
+        assert_eq!(buffered.latency(), Duration::from_millis(1));
+        let uniform = UniformSourceIterator::<_, i16>::new(buffered, 1, 96000);
+        assert_eq!(uniform.latency(), Duration::from_millis(1));
+    }
+}
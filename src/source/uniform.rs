@@ -5,7 +5,7 @@ use dasp_sample::FromSample;
 
 use super::SeekError;
 use crate::common::{ChannelCount, SampleRate};
-use crate::conversions::{ChannelCountConverter, DataConverter, SampleRateConverter};
+use crate::conversions::{ChannelCountConverter, DataConverter, Latency, SampleRateConverter};
 use crate::{Sample, Source};
 
 /// An iterator that reads from a `Source` and converts the samples to a
@@ -138,6 +138,13 @@ where
         self.total_duration
     }
 
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.inner
+            .as_ref()
+            .map_or(Duration::ZERO, |input| input.latency())
+    }
+
     #[inline]
     fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
         if let Some(input) = self.inner.as_mut() {
@@ -166,6 +173,17 @@ impl<I> Take<I> {
     }
 }
 
+impl<I> Latency for Take<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.iter.latency()
+    }
+}
+
 impl<I> Iterator for Take<I>
 where
     I: Iterator,
@@ -0,0 +1,261 @@
+use std::collections::VecDeque;
+use std::f32::consts::TAU;
+use std::time::Duration;
+
+use super::{SeekError, SpeakerLayout};
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+// Delays of the left and right surround channel. Different delays keep the two
+// from sounding like a single phantom source behind the listener.
+const SURROUND_LEFT_DELAY_MS: usize = 10;
+const SURROUND_RIGHT_DELAY_MS: usize = 15;
+// Corner frequency of the low-pass that feeds the LFE channel.
+const LFE_CUTOFF_HZ: f32 = 120.0;
+
+/// Internal function that builds a `SurroundUpmix` object.
+pub fn upmix_surround<I>(input: I, layout: SpeakerLayout) -> SurroundUpmix<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    SurroundUpmix {
+        input,
+        layout,
+        frame: Vec::with_capacity(layout.channels() as usize),
+        pos: 0,
+        history: VecDeque::new(),
+        lfe: I::Item::zero_value(),
+    }
+}
+
+/// Spreads a stereo sound over a surround speaker setup, see
+/// [`Source::upmix_surround`].
+#[derive(Clone, Debug)]
+pub struct SurroundUpmix<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    input: I,
+    layout: SpeakerLayout,
+    // Current output frame and the index of its next sample.
+    frame: Vec<I::Item>,
+    pos: usize,
+    // Recent differences between left and right, newest last.
+    history: VecDeque<I::Item>,
+    // State of the LFE low-pass.
+    lfe: I::Item,
+}
+
+impl<I> SurroundUpmix<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    /// Returns the speaker layout the source plays on.
+    #[inline]
+    pub fn layout(&self) -> SpeakerLayout {
+        self.layout
+    }
+
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+
+    // Difference `frames` frames ago, silence before the start.
+    fn delayed_difference(&self, frames: usize) -> I::Item {
+        self.history
+            .len()
+            .checked_sub(frames + 1)
+            .map_or(I::Item::zero_value(), |index| self.history[index])
+    }
+
+    fn next_frame(&mut self) -> Option<()> {
+        let channels = self.input.channels() as usize;
+        let rate = self.input.sample_rate();
+        let left = self.input.next()?;
+        let right = if channels > 1 {
+            self.input.next().unwrap_or(left)
+        } else {
+            left
+        };
+        // Channels beyond the first two are dropped.
+        for _ in 2..channels {
+            self.input.next();
+        }
+
+        self.frame.clear();
+        self.pos = 0;
+        self.frame.extend([left, right]);
+        if self.layout == SpeakerLayout::Stereo {
+            return Some(());
+        }
+
+        let sum = left.saturating_add(right);
+        let difference = left.saturating_add(right.amplify(-1.0));
+        let left_delay = rate as usize * SURROUND_LEFT_DELAY_MS / 1000;
+        let right_delay = rate as usize * SURROUND_RIGHT_DELAY_MS / 1000;
+        self.history.push_back(difference);
+        while self.history.len() > left_delay.max(right_delay) + 1 {
+            self.history.pop_front();
+        }
+        // Opposite polarity on the right decorrelates the surrounds further.
+        let surround_left = self.delayed_difference(left_delay);
+        let surround_right = self.delayed_difference(right_delay).amplify(-1.0);
+
+        if self.layout == SpeakerLayout::Surround51 {
+            let alpha = 1.0 - (-TAU * LFE_CUTOFF_HZ / rate as f32).exp();
+            self.lfe = self
+                .lfe
+                .amplify(1.0 - alpha)
+                .saturating_add(sum.amplify(alpha));
+            self.frame.extend([sum, self.lfe]);
+        }
+        self.frame.extend([surround_left, surround_right]);
+        Some(())
+    }
+
+    fn output_len(&self, input_len: usize) -> usize {
+        let in_channels = self.input.channels() as usize;
+        let out_channels = self.layout.channels() as usize;
+        input_len
+            .div_ceil(in_channels)
+            .saturating_mul(out_channels)
+            .saturating_add(self.frame.len() - self.pos)
+    }
+}
+
+impl<I> Iterator for SurroundUpmix<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        if self.pos == self.frame.len() {
+            self.next_frame()?;
+        }
+        let sample = self.frame[self.pos];
+        self.pos += 1;
+        Some(sample)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.input.size_hint();
+        (
+            self.output_len(lower),
+            upper.map(|upper| self.output_len(upper)),
+        )
+    }
+}
+
+impl<I> ExactSizeIterator for SurroundUpmix<I>
+where
+    I: Source + ExactSizeIterator,
+    I::Item: Sample,
+{
+}
+
+impl<I> Source for SurroundUpmix<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        self.input
+            .current_span_len()
+            .map(|len| self.output_len(len))
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.layout.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.frame.clear();
+        self.pos = 0;
+        self.history.clear();
+        self.lfe = I::Item::zero_value();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::buffer::SamplesBuffer;
+    use crate::source::SpeakerLayout;
+    use crate::Source;
+
+    #[test]
+    fn derives_center_and_surrounds() {
+        let samples: Vec<f32> = (0..2000)
+            .map(|i| {
+                let frame = (i / 2) as f32;
+                if i & 1 == 0 {
+                    (frame * 0.05).sin() * 0.5
+                } else {
+                    (frame * 0.03).cos() * 0.25
+                }
+            })
+            .collect();
+        let input = SamplesBuffer::new(2, 1000, samples.clone());
+        let upmix = input.upmix_surround(SpeakerLayout::Surround51);
+        assert_eq!(upmix.channels(), 6);
+        assert_eq!(upmix.size_hint(), (6000, Some(6000)));
+
+        let output: Vec<f32> = upmix.collect();
+        let frames: Vec<&[f32]> = output.chunks(6).collect();
+        let stereo: Vec<&[f32]> = samples.chunks(2).collect();
+        assert_eq!(frames.len(), 1000);
+        for (frame, input) in frames.iter().zip(&stereo) {
+            assert_eq!(&frame[..2], *input);
+            assert_eq!(frame[2], input[0] + input[1]);
+        }
+
+        // 10 and 15 ms at 1000 Hz, the right surround with opposite polarity.
+        let difference = |frame: usize| stereo[frame][0] - stereo[frame][1];
+        for (i, frame) in frames.iter().enumerate() {
+            let left = if i >= 10 { difference(i - 10) } else { 0.0 };
+            let right = if i >= 15 { -difference(i - 15) } else { 0.0 };
+            assert_eq!(frame[4], left);
+            assert_eq!(frame[5], right);
+        }
+    }
+}
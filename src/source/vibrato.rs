@@ -0,0 +1,217 @@
+use std::f32::consts::TAU;
+use std::time::Duration;
+
+use dasp_sample::FromSample;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Internal function that builds a `Vibrato` object.
+pub fn vibrato<I>(input: I, rate_hz: f32, depth_ms: f32) -> Vibrato<I>
+where
+    I: Source,
+    I::Item: Sample + FromSample<f32>,
+{
+    Vibrato {
+        input,
+        rate_hz,
+        depth_ms: depth_ms.max(0.0),
+        phase: 0.0,
+        delay: 0.0,
+        lines: Vec::new(),
+        line_rate: 0,
+        write: 0,
+        channel: 0,
+    }
+}
+
+/// Makes the pitch of a sound wobble up and down, see [`Source::vibrato`].
+#[derive(Clone, Debug)]
+pub struct Vibrato<I> {
+    input: I,
+    rate_hz: f32,
+    depth_ms: f32,
+    // Position of the LFO in its cycle, between 0 and 1.
+    phase: f32,
+    // Delay of the current frame in frames, can be between two frames.
+    delay: f32,
+    // Past samples of every channel, a ring buffer long enough for the deepest delay.
+    lines: Vec<Vec<f32>>,
+    // Sample rate the delay lines were sized for.
+    line_rate: SampleRate,
+    // Position in the delay lines the current frame is written to.
+    write: usize,
+    // Channel of the next input sample.
+    channel: usize,
+}
+
+impl<I> Vibrato<I>
+where
+    I: Source,
+    I::Item: Sample + FromSample<f32>,
+{
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+
+    // Restarts the delay lines with silence, sized for the current format.
+    fn reset_lines(&mut self) {
+        self.line_rate = self.input.sample_rate();
+        let depth = self.depth_ms * self.line_rate as f32 / 1000.0;
+        // One frame more for the interpolation and one for the frame being written.
+        let len = depth.ceil() as usize + 2;
+        self.lines = vec![vec![0.0; len]; self.input.channels() as usize];
+        self.write = 0;
+    }
+
+    fn start_frame(&mut self) {
+        let channels = self.input.channels() as usize;
+        if self.lines.len() != channels || self.input.sample_rate() != self.line_rate {
+            self.reset_lines();
+        }
+        self.write = (self.write + 1) % self.lines[0].len();
+        let depth = self.depth_ms * self.line_rate as f32 / 1000.0;
+        self.delay = depth * (0.5 - 0.5 * (TAU * self.phase).cos());
+        self.phase = (self.phase + self.rate_hz / self.line_rate as f32).fract();
+    }
+
+    // Stores `sample` and returns the sample of the same channel `delay` frames ago.
+    fn delay_sample(&mut self, sample: f32) -> f32 {
+        let line = &mut self.lines[self.channel];
+        let len = line.len();
+        line[self.write] = sample;
+
+        let frames = self.delay.floor();
+        let fraction = self.delay - frames;
+        let newer = (self.write + len - frames as usize) % len;
+        let older = (newer + len - 1) % len;
+        line[newer] + (line[older] - line[newer]) * fraction
+    }
+}
+
+impl<I> Iterator for Vibrato<I>
+where
+    I: Source,
+    I::Item: Sample + FromSample<f32>,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        let sample = self.input.next()?;
+        if self.channel == 0 {
+            self.start_frame();
+        }
+        let delayed = self.delay_sample(sample.to_f32());
+        self.channel = (self.channel + 1) % self.lines.len();
+        Some(I::Item::from_sample_(delayed))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for Vibrato<I>
+where
+    I: Source + ExactSizeIterator,
+    I::Item: Sample + FromSample<f32>,
+{
+}
+
+impl<I> Source for Vibrato<I>
+where
+    I: Source,
+    I::Item: Sample + FromSample<f32>,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.phase = (pos.as_secs_f64() * self.rate_hz as f64).fract() as f32;
+        self.lines.clear();
+        self.channel = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::TAU;
+    use std::time::Duration;
+
+    use crate::buffer::SamplesBuffer;
+    use crate::Source;
+
+    // A ramp on the left and the same ramp falling on the right, interpolating between
+    // its samples gives back the exact position that was read.
+    fn ramps() -> SamplesBuffer<f32> {
+        let samples: Vec<f32> = (0..1000).flat_map(|n| [n as f32, -(n as f32)]).collect();
+        SamplesBuffer::new(2, 1000, samples)
+    }
+
+    #[test]
+    fn delay_follows_lfo_on_every_channel() {
+        let output: Vec<f32> = ramps().vibrato(2.0, 10.0).collect();
+        assert_eq!(output.len(), 2000);
+
+        // Past the first 10 ms the line is full and the read position is the frame
+        // minus the delay, which swings between 0 and 10 frames twice per second.
+        for (frame, samples) in output.chunks(2).enumerate().skip(10) {
+            let delay = 10.0 * (0.5 - 0.5 * (TAU * 2.0 * frame as f32 / 1000.0).cos());
+            let expected = frame as f32 - delay;
+            assert!((samples[0] - expected).abs() < 1e-3, "frame {frame}");
+            assert!((samples[1] + expected).abs() < 1e-3, "frame {frame}");
+        }
+    }
+
+    #[test]
+    fn seeking_clears_delay_lines() {
+        let mut vibrato = ramps().vibrato(2.0, 10.0);
+        let first: Vec<f32> = vibrato.by_ref().take(600).collect();
+
+        vibrato.try_seek(Duration::ZERO).unwrap();
+        let again: Vec<f32> = vibrato.take(600).collect();
+        assert_eq!(again, first);
+    }
+}
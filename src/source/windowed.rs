@@ -0,0 +1,140 @@
+use std::time::Duration;
+
+use super::{SeekError, WindowFunction};
+use crate::common::{ChannelCount, SampleRate};
+use crate::math;
+use crate::{Sample, Source};
+
+/// Internal function that builds a `Windowed` object.
+pub fn windowed<I>(input: I, window: WindowFunction, block: usize) -> Windowed<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    assert!(block > 0, "a block needs at least one frame");
+    Windowed {
+        input,
+        window,
+        block,
+        pos: 0,
+    }
+}
+
+/// Multiplies every block of a source with a window function, see
+/// [`Source::apply_window`].
+#[derive(Clone, Debug)]
+pub struct Windowed<I> {
+    input: I,
+    window: WindowFunction,
+    block: usize,
+    // Position of the next sample in its block, in samples.
+    pos: usize,
+}
+
+impl<I> Windowed<I> {
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+}
+
+impl<I> Iterator for Windowed<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        let sample = self.input.next()?;
+        let channels = self.input.channels() as usize;
+        let gain = self.window.gain(self.pos / channels, self.block);
+        self.pos = (self.pos + 1) % (self.block * channels);
+        Some(sample.amplify(gain))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for Windowed<I>
+where
+    I: Source + ExactSizeIterator,
+    I::Item: Sample,
+{
+}
+
+impl<I> Source for Windowed<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn latency(&self) -> Duration {
+        self.input.latency()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        // Blocks stay aligned to the start of the source.
+        let frame = math::duration_to_frames(pos, self.input.sample_rate()) as usize;
+        self.pos = frame % self.block * self.input.channels() as usize;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::buffer::SamplesBuffer;
+    use crate::source::WindowFunction;
+    use crate::Source;
+
+    #[test]
+    fn hann_tapers_block_edges() {
+        let input = SamplesBuffer::new(2, 1000, vec![1.0f32; 2 * 64]);
+        let samples: Vec<f32> = input.apply_window(WindowFunction::Hann, 32).collect();
+        for block in samples.chunks(64) {
+            assert_eq!(block[..2], [0.0, 0.0]);
+            assert!(block[62].abs() < 1e-6 && block[63].abs() < 1e-6);
+            // Peaks in the middle of the block, the same on both channels.
+            assert!(block[30] > 0.99 && block[30] == block[31]);
+        }
+    }
+}
@@ -55,6 +55,33 @@ impl SpatialSink {
         self.positions.lock().unwrap().right_ear = pos;
     }
 
+    /// Turns the listener's head to look along `forward` with the top of the head
+    /// pointing along `up`.
+    ///
+    /// The ears are placed again around the point halfway between them, keeping their
+    /// distance. With `forward` along negative z and `up` along y the right ear is on
+    /// the positive x side, as in most game engines. Nothing changes if `forward` and
+    /// `up` point the same way.
+    pub fn set_listener_orientation(&self, forward: [f32; 3], up: [f32; 3]) {
+        let [fx, fy, fz] = forward;
+        let [ux, uy, uz] = up;
+        let right = [fy * uz - fz * uy, fz * ux - fx * uz, fx * uy - fy * ux];
+        let len = right.iter().map(|v| v * v).sum::<f32>().sqrt();
+        if len == 0.0 {
+            return;
+        }
+
+        let mut pos = self.positions.lock().unwrap();
+        let center: [f32; 3] = std::array::from_fn(|i| (pos.left_ear[i] + pos.right_ear[i]) / 2.0);
+        let half_width = (0..3)
+            .map(|i| (pos.right_ear[i] - center[i]).powi(2))
+            .sum::<f32>()
+            .sqrt();
+        let offset = right.map(|v| v / len * half_width);
+        pos.left_ear = std::array::from_fn(|i| center[i] - offset[i]);
+        pos.right_ear = std::array::from_fn(|i| center[i] + offset[i]);
+    }
+
     /// Appends a sound to the queue of sounds to play.
     #[inline]
     pub fn append<S>(&self, source: S)
@@ -221,3 +248,39 @@ impl SpatialSink {
         self.sink.get_pos()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::buffer::SamplesBuffer;
+    use crate::mixer;
+    use crate::source::Spatial;
+    use crate::SpatialSink;
+
+    // Returns the level of the left and right channel for a sound to the left of a
+    // listener looking along `forward`.
+    fn left_and_right(forward: [f32; 3]) -> (f32, f32) {
+        let (controller, _output) = mixer::mixer::<f32>(2, 1000);
+        let sink = SpatialSink::connect_new(
+            &controller,
+            [-1.0, 0.0, 0.0],
+            [-0.1, 0.0, 0.0],
+            [0.1, 0.0, 0.0],
+        );
+        sink.set_listener_orientation(forward, [0.0, 1.0, 0.0]);
+
+        let pos = sink.positions.lock().unwrap();
+        let input = SamplesBuffer::new(1, 1000, vec![1.0f32]);
+        let mut spatial = Spatial::new(input, pos.emitter_position, pos.left_ear, pos.right_ear);
+        (spatial.next().unwrap(), spatial.next().unwrap())
+    }
+
+    #[test]
+    fn turning_around_swaps_sides() {
+        let (left, right) = left_and_right([0.0, 0.0, -1.0]);
+        assert!((left - right).abs() > 0.1, "left {left}, right {right}");
+
+        // The ears changed places, so did the levels.
+        let turned = left_and_right([0.0, 0.0, 1.0]);
+        assert!((turned.0 - right).abs() < 1e-6 && (turned.1 - left).abs() < 1e-6);
+    }
+}
@@ -1,14 +1,19 @@
 use std::io::{Read, Seek};
 use std::marker::Sync;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
 use std::{error, fmt};
 
 use crate::common::{ChannelCount, SampleRate};
 use crate::decoder;
 use crate::mixer::{mixer, Mixer, MixerSource};
 use crate::sink::Sink;
+use crate::source::ResampleConfig;
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-use cpal::{BufferSize, FrameCount, Sample, SampleFormat, StreamConfig, SupportedBufferSize};
+use cpal::{
+    BufferSize, FrameCount, FromSample, SampleFormat, SizedSample, StreamConfig,
+    SupportedBufferSize,
+};
 
 const HZ_44100: SampleRate = 44_100;
 
@@ -17,6 +22,11 @@ const HZ_44100: SampleRate = 44_100;
 /// If this is dropped, playback will end, and the associated output stream will be disposed.
 pub struct OutputStream {
     mixer: Arc<Mixer<f32>>,
+    exclusive: bool,
+    device_name: Option<String>,
+    config: StreamConfigInfo,
+    // Bits of the `f32` gain applied to everything the stream plays.
+    master_volume: Arc<AtomicU32>,
     _stream: cpal::Stream,
 }
 
@@ -25,6 +35,77 @@ impl OutputStream {
     pub fn mixer(&self) -> Arc<Mixer<f32>> {
         self.mixer.clone()
     }
+
+    /// Whether the stream has exclusive access to the device, see
+    /// [`OutputStreamBuilder::with_exclusive_mode`].
+    pub fn is_exclusive(&self) -> bool {
+        self.exclusive
+    }
+
+    /// Returns the name of the device the stream plays on, or `None` if the device
+    /// could not report it.
+    ///
+    /// Useful to tell which device was picked when the stream was opened on the
+    /// default device, which can change while the program runs.
+    pub fn device_name(&self) -> Option<String> {
+        self.device_name.clone()
+    }
+
+    /// Returns the format the stream was opened with.
+    pub fn config(&self) -> StreamConfigInfo {
+        self.config
+    }
+
+    /// Returns the gain applied to everything the stream plays.
+    pub fn master_volume(&self) -> f32 {
+        f32::from_bits(self.master_volume.load(Ordering::Relaxed))
+    }
+
+    /// Sets the gain applied to everything the stream plays. `1.0` leaves the sound
+    /// unchanged.
+    pub fn set_master_volume(&self, gain: f32) {
+        self.master_volume.store(gain.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Sets the master volume from the position of a volume fader between `0.0` and
+    /// `1.0`.
+    ///
+    /// Loudness is not perceived linearly, with a linear gain most of the fader's
+    /// travel barely changes the volume. This maps the fader through a cubic curve
+    /// instead, so the middle of the fader sounds about half as loud as the top.
+    pub fn set_master_volume_perceptual(&self, fader: f32) {
+        self.set_master_volume(perceptual_gain(fader));
+    }
+}
+
+// Maps the position of a volume fader to a gain, see
+// `OutputStream::set_master_volume_perceptual`.
+fn perceptual_gain(fader: f32) -> f32 {
+    fader.clamp(0.0, 1.0).powi(3)
+}
+
+/// Format an [`OutputStream`] was opened with, see [`OutputStream::config`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct StreamConfigInfo {
+    /// Number of channels the device plays.
+    pub channels: ChannelCount,
+    /// Number of frames the device plays per second.
+    pub sample_rate: SampleRate,
+    /// Size of the buffers the device asks for.
+    pub buffer_size: BufferSize,
+    /// Format of the samples sent to the device.
+    pub sample_format: SampleFormat,
+}
+
+impl From<&OutputStreamConfig> for StreamConfigInfo {
+    fn from(config: &OutputStreamConfig) -> Self {
+        StreamConfigInfo {
+            channels: config.channel_count,
+            sample_rate: config.sample_rate,
+            buffer_size: config.buffer_size,
+            sample_format: config.sample_format,
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -33,8 +114,13 @@ struct OutputStreamConfig {
     sample_rate: SampleRate,
     buffer_size: BufferSize,
     sample_format: SampleFormat,
+    exclusive_mode: bool,
+    resample_config: Option<ResampleConfig>,
 }
 
+// Runs on every buffer the device pulls, see `OutputStreamBuilder::with_process_callback`.
+type ProcessCallback = Arc<Mutex<dyn FnMut(&mut [f32], ChannelCount) + Send>>;
+
 /// Convenience builder for audio output stream.
 /// It provides methods to configure several parameters of the audio output and opening default
 /// device. See examples for use-cases.
@@ -42,6 +128,7 @@ struct OutputStreamConfig {
 pub struct OutputStreamBuilder {
     device: Option<cpal::Device>,
     config: OutputStreamConfig,
+    process_callback: Option<ProcessCallback>,
 }
 
 impl Default for OutputStreamConfig {
@@ -51,6 +138,8 @@ impl Default for OutputStreamConfig {
             sample_rate: HZ_44100,
             buffer_size: BufferSize::Default,
             sample_format: SampleFormat::I8,
+            exclusive_mode: false,
+            resample_config: None,
         }
     }
 }
@@ -109,6 +198,57 @@ impl OutputStreamBuilder {
         self
     }
 
+    /// Requests exclusive access to the output device for lower latency, such as
+    /// WASAPI exclusive mode on Windows.
+    ///
+    /// Exclusive mode is not implemented yet, none of the audio backends expose
+    /// exclusive streams. When requested the stream is opened in shared mode, with a
+    /// warning if the `tracing` feature is enabled. The request is kept when
+    /// [`open_stream_or_fallback`](Self::open_stream_or_fallback) tries other configs,
+    /// so it takes effect once a backend supports it. Use
+    /// [`OutputStream::is_exclusive`] to check what was granted.
+    pub fn with_exclusive_mode(mut self, exclusive: bool) -> OutputStreamBuilder {
+        self.config.exclusive_mode = exclusive;
+        self
+    }
+
+    /// Sets how sounds are converted to the sample rate of the stream, for every sink
+    /// and source played on it.
+    ///
+    /// See [`Mixer::set_resample_config`]. Without this the mixer interpolates
+    /// linearly.
+    pub fn with_resample_config(mut self, config: ResampleConfig) -> OutputStreamBuilder {
+        self.config.resample_config = Some(config);
+        self
+    }
+
+    /// Sets a function that runs every time the device pulls a buffer of samples,
+    /// after everything has been mixed and the master volume applied.
+    ///
+    /// It receives the interleaved samples about to be played and the number of
+    /// channels, and can change them, for example to meter the output or to apply a
+    /// final effect. It runs on the audio thread, so it has to be fast and must not
+    /// block.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// let stream = rodio::OutputStreamBuilder::from_default_device()?
+    ///     .with_process_callback(|samples, _channels| {
+    ///         // Keeps the output from ever clipping.
+    ///         samples.iter_mut().for_each(|s| *s = s.clamp(-1.0, 1.0));
+    ///     })
+    ///     .open_stream()?;
+    /// # Ok::<(), rodio::StreamError>(())
+    /// ```
+    pub fn with_process_callback(
+        mut self,
+        callback: impl FnMut(&mut [f32], ChannelCount) + Send + 'static,
+    ) -> OutputStreamBuilder {
+        self.process_callback = Some(Arc::new(Mutex::new(callback)));
+        self
+    }
+
     /// Set available parameters from a CPAL supported config. You can ge list of
     /// such configurations for an output device using [crate::stream::supported_output_configs()]
     pub fn with_supported_config(
@@ -121,6 +261,7 @@ impl OutputStreamBuilder {
             // In case of supported range limit buffer size to avoid unexpectedly long playback delays.
             buffer_size: clamp_supported_buffer_size(config.buffer_size(), 1024),
             sample_format: config.sample_format(),
+            ..self.config
         };
         self
     }
@@ -139,7 +280,7 @@ impl OutputStreamBuilder {
     /// Open output stream using parameters configured so far.
     pub fn open_stream(&self) -> Result<OutputStream, StreamError> {
         let device = self.device.as_ref().expect("output device specified");
-        OutputStream::open(device, &self.config)
+        OutputStream::open(device, &self.config, self.process_callback.clone())
     }
 
     /// Try opening a new output stream with the builder's current stream configuration.
@@ -148,11 +289,11 @@ impl OutputStreamBuilder {
     /// If all attempts fail returns initial error.
     pub fn open_stream_or_fallback(&self) -> Result<OutputStream, StreamError> {
         let device = self.device.as_ref().expect("output device specified");
-        OutputStream::open(device, &self.config).or_else(|err| {
+        OutputStream::open(device, &self.config, self.process_callback.clone()).or_else(|err| {
             for supported_config in supported_output_configs(device)? {
-                if let Ok(handle) = Self::default()
+                if let Ok(handle) = self
+                    .fallback(&supported_config)
                     .with_device(device.clone())
-                    .with_supported_config(&supported_config)
                     .open_stream()
                 {
                     return Ok(handle);
@@ -162,6 +303,21 @@ impl OutputStreamBuilder {
         })
     }
 
+    // Builder for a config the device supports, keeping the settings that do not
+    // depend on the device.
+    fn fallback(&self, supported_config: &cpal::SupportedStreamConfig) -> OutputStreamBuilder {
+        let builder = Self {
+            device: None,
+            config: OutputStreamConfig {
+                exclusive_mode: self.config.exclusive_mode,
+                resample_config: self.config.resample_config,
+                ..OutputStreamConfig::default()
+            },
+            process_callback: self.process_callback.clone(),
+        };
+        builder.with_supported_config(supported_config)
+    }
+
     /// Try to open a new output stream for the default output device with its default configuration.
     /// Failing that attempt to open output stream with alternative configuration and/or non default
     /// output devices. Returns stream for first of the tried configurations that succeeds.
@@ -304,24 +460,56 @@ impl OutputStream {
     fn open(
         device: &cpal::Device,
         config: &OutputStreamConfig,
+        process_callback: Option<ProcessCallback>,
     ) -> Result<OutputStream, StreamError> {
-        let (controller, source) = mixer(config.channel_count, config.sample_rate);
-        Self::init_stream(device, config, source)
+        #[cfg(feature = "tracing")]
+        if config.exclusive_mode {
+            tracing::warn!(
+                "exclusive mode is not supported by the audio backend, using shared mode"
+            );
+        }
+        let (controller, source) = Self::new_mixer(config);
+        let master_volume = Arc::new(AtomicU32::new(1.0f32.to_bits()));
+        let gain = master_volume.clone();
+        let source =
+            source.map(move |sample| sample * f32::from_bits(gain.load(Ordering::Relaxed)));
+        let filler = BufferFiller {
+            samples: source,
+            buffer: Vec::new(),
+            channels: config.channel_count,
+            process_callback,
+        };
+        Self::init_stream(device, config, filler)
             .map_err(StreamError::BuildStreamError)
             .and_then(|stream| {
                 stream.play().map_err(StreamError::PlayStreamError)?;
                 Ok(Self {
                     _stream: stream,
                     mixer: controller,
+                    exclusive: false,
+                    device_name: device.name().ok(),
+                    config: config.into(),
+                    master_volume,
                 })
             })
     }
 
-    fn init_stream(
+    fn new_mixer(config: &OutputStreamConfig) -> (Arc<Mixer<f32>>, MixerSource<f32>) {
+        let (controller, source) = mixer(config.channel_count, config.sample_rate);
+        if let Some(resample_config) = config.resample_config {
+            controller.set_resample_config(resample_config);
+        }
+        (controller, source)
+    }
+
+    fn init_stream<I>(
         device: &cpal::Device,
         config: &OutputStreamConfig,
-        mut samples: MixerSource<f32>,
-    ) -> Result<cpal::Stream, cpal::BuildStreamError> {
+        mut filler: BufferFiller<I>,
+    ) -> Result<cpal::Stream, cpal::BuildStreamError>
+    where
+        I: Iterator<Item = f32> + Send + 'static,
+    {
         let error_callback = |err| {
             #[cfg(feature = "tracing")]
             tracing::error!("error initializing output stream: {err}");
@@ -330,118 +518,63 @@ impl OutputStream {
         };
         let sample_format = config.sample_format;
         let config = config.into();
+        macro_rules! build {
+            ($sample:ty) => {
+                device.build_output_stream::<$sample, _, _>(
+                    &config,
+                    move |data, _| filler.fill(data),
+                    error_callback,
+                    None,
+                )
+            };
+        }
         match sample_format {
-            cpal::SampleFormat::F32 => device.build_output_stream::<f32, _, _>(
-                &config,
-                move |data, _| {
-                    data.iter_mut()
-                        .for_each(|d| *d = samples.next().unwrap_or(0f32))
-                },
-                error_callback,
-                None,
-            ),
-            cpal::SampleFormat::F64 => device.build_output_stream::<f64, _, _>(
-                &config,
-                move |data, _| {
-                    data.iter_mut()
-                        .for_each(|d| *d = samples.next().map(Sample::from_sample).unwrap_or(0f64))
-                },
-                error_callback,
-                None,
-            ),
-            cpal::SampleFormat::I8 => device.build_output_stream::<i8, _, _>(
-                &config,
-                move |data, _| {
-                    data.iter_mut()
-                        .for_each(|d| *d = samples.next().map(Sample::from_sample).unwrap_or(0i8))
-                },
-                error_callback,
-                None,
-            ),
-            cpal::SampleFormat::I16 => device.build_output_stream::<i16, _, _>(
-                &config,
-                move |data, _| {
-                    data.iter_mut()
-                        .for_each(|d| *d = samples.next().map(Sample::from_sample).unwrap_or(0i16))
-                },
-                error_callback,
-                None,
-            ),
-            cpal::SampleFormat::I32 => device.build_output_stream::<i32, _, _>(
-                &config,
-                move |data, _| {
-                    data.iter_mut()
-                        .for_each(|d| *d = samples.next().map(Sample::from_sample).unwrap_or(0i32))
-                },
-                error_callback,
-                None,
-            ),
-            cpal::SampleFormat::I64 => device.build_output_stream::<i64, _, _>(
-                &config,
-                move |data, _| {
-                    data.iter_mut()
-                        .for_each(|d| *d = samples.next().map(Sample::from_sample).unwrap_or(0i64))
-                },
-                error_callback,
-                None,
-            ),
-            cpal::SampleFormat::U8 => device.build_output_stream::<u8, _, _>(
-                &config,
-                move |data, _| {
-                    data.iter_mut().for_each(|d| {
-                        *d = samples
-                            .next()
-                            .map(Sample::from_sample)
-                            .unwrap_or(u8::MAX / 2)
-                    })
-                },
-                error_callback,
-                None,
-            ),
-            cpal::SampleFormat::U16 => device.build_output_stream::<u16, _, _>(
-                &config,
-                move |data, _| {
-                    data.iter_mut().for_each(|d| {
-                        *d = samples
-                            .next()
-                            .map(Sample::from_sample)
-                            .unwrap_or(u16::MAX / 2)
-                    })
-                },
-                error_callback,
-                None,
-            ),
-            cpal::SampleFormat::U32 => device.build_output_stream::<u32, _, _>(
-                &config,
-                move |data, _| {
-                    data.iter_mut().for_each(|d| {
-                        *d = samples
-                            .next()
-                            .map(Sample::from_sample)
-                            .unwrap_or(u32::MAX / 2)
-                    })
-                },
-                error_callback,
-                None,
-            ),
-            cpal::SampleFormat::U64 => device.build_output_stream::<u64, _, _>(
-                &config,
-                move |data, _| {
-                    data.iter_mut().for_each(|d| {
-                        *d = samples
-                            .next()
-                            .map(Sample::from_sample)
-                            .unwrap_or(u64::MAX / 2)
-                    })
-                },
-                error_callback,
-                None,
-            ),
+            cpal::SampleFormat::F32 => build!(f32),
+            cpal::SampleFormat::F64 => build!(f64),
+            cpal::SampleFormat::I8 => build!(i8),
+            cpal::SampleFormat::I16 => build!(i16),
+            cpal::SampleFormat::I32 => build!(i32),
+            cpal::SampleFormat::I64 => build!(i64),
+            cpal::SampleFormat::U8 => build!(u8),
+            cpal::SampleFormat::U16 => build!(u16),
+            cpal::SampleFormat::U32 => build!(u32),
+            cpal::SampleFormat::U64 => build!(u64),
             _ => Err(cpal::BuildStreamError::StreamConfigNotSupported),
         }
     }
 }
 
+// Fills the buffers of the device from the mix.
+struct BufferFiller<I> {
+    samples: I,
+    // The next buffer in `f32`, before it is converted to the format of the device.
+    buffer: Vec<f32>,
+    channels: ChannelCount,
+    process_callback: Option<ProcessCallback>,
+}
+
+impl<I> BufferFiller<I>
+where
+    I: Iterator<Item = f32>,
+{
+    fn fill<T>(&mut self, data: &mut [T])
+    where
+        T: SizedSample + FromSample<f32>,
+    {
+        self.buffer.clear();
+        // Silence once the mix ran dry.
+        self.buffer
+            .extend((0..data.len()).map(|_| self.samples.next().unwrap_or(0.0)));
+        if let Some(callback) = &self.process_callback {
+            let mut callback = callback.lock().unwrap_or_else(PoisonError::into_inner);
+            callback(&mut self.buffer, self.channels);
+        }
+        for (out, &sample) in data.iter_mut().zip(&self.buffer) {
+            *out = T::from_sample(sample);
+        }
+    }
+}
+
 /// Return all formats supported by the device.
 fn supported_output_configs(
     device: &cpal::Device,
@@ -464,3 +597,150 @@ fn supported_output_configs(
         formats
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use super::{perceptual_gain, BufferFiller, OutputStream, OutputStreamBuilder};
+    use crate::buffer::SamplesBuffer;
+    use crate::mixer::mixer;
+    use crate::sink::Sink;
+    use crate::source::{Function, ResampleConfig, SignalGenerator, Source};
+
+    #[test]
+    fn perceptual_gain_curve() {
+        assert_eq!(perceptual_gain(0.0), 0.0);
+        assert_eq!(perceptual_gain(1.0), 1.0);
+        let gains: Vec<f32> = (0..=100)
+            .map(|n| perceptual_gain(n as f32 / 100.0))
+            .collect();
+        assert!(gains.windows(2).all(|w| w[1] > w[0]));
+        // Half way up the fader is well below half the gain.
+        assert!((0.1..0.2).contains(&perceptual_gain(0.5)));
+        assert_eq!(perceptual_gain(2.0), 1.0);
+    }
+
+    #[test]
+    fn resample_config_reaches_sinks() {
+        // RMS of what is left of a 30 kHz tone at 96 kHz played on a 48 kHz stream. The
+        // tone is above the highest frequency the stream can play, linear
+        // interpolation folds it back to an audible 18 kHz instead of removing it.
+        let leftover = |builder: OutputStreamBuilder| {
+            let (mixer, output) = OutputStream::new_mixer(&builder.config);
+            let sink = Sink::connect_new(&mixer);
+            sink.append(
+                SignalGenerator::new(96000, 30000.0, Function::Sine)
+                    .take_duration(Duration::from_secs(1)),
+            );
+            // Skip the start of the tone while the resampler fills up.
+            let samples: Vec<f32> = output.skip(4800).take(24000).collect();
+            (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+        };
+        let builder = || {
+            OutputStreamBuilder::default()
+                .with_channels(1)
+                .with_sample_rate(48000)
+        };
+
+        let linear = leftover(builder());
+        let accurate = leftover(builder().with_resample_config(ResampleConfig::accurate()));
+        assert!(linear > 0.1, "linear interpolation left {linear}");
+        assert!(accurate < 0.01, "accurate resampling left {accurate}");
+    }
+
+    #[test]
+    fn fallback_keeps_device_independent_settings() {
+        let builder = OutputStreamBuilder::default()
+            .with_exclusive_mode(true)
+            .with_resample_config(ResampleConfig::accurate());
+        let supported = cpal::SupportedStreamConfig::new(
+            1,
+            cpal::SampleRate(22050),
+            cpal::SupportedBufferSize::Unknown,
+            cpal::SampleFormat::I16,
+        );
+
+        let fallback = builder.fallback(&supported);
+        assert_eq!(fallback.config.channel_count, 1);
+        assert_eq!(fallback.config.sample_rate, 22050);
+        assert!(fallback.config.exclusive_mode);
+        assert!(fallback.config.resample_config.is_some());
+    }
+
+    #[test]
+    fn plays_integer_sources() {
+        let builder = OutputStreamBuilder::default()
+            .with_channels(1)
+            .with_sample_rate(48000);
+        let (mixer, output) = OutputStream::new_mixer(&builder.config);
+        mixer.add_converted(SamplesBuffer::new(
+            1,
+            48000,
+            vec![i16::MAX / 2, i16::MIN / 4, 0],
+        ));
+
+        let samples: Vec<f32> = output.take(3).collect();
+        assert!((samples[0] - 0.5).abs() < 1e-3);
+        assert!((samples[1] + 0.25).abs() < 1e-3);
+        assert_eq!(samples[2], 0.0);
+    }
+
+    #[test]
+    fn process_callback_changes_output() {
+        let (mixer, source) = mixer::<f32>(2, 48000);
+        mixer.add(SamplesBuffer::new(
+            2,
+            48000,
+            vec![0.5f32, -0.5, 0.25, -0.25],
+        ));
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let seen_by_callback = seen.clone();
+        let callback = move |samples: &mut [f32], channels| {
+            assert_eq!(channels, 2);
+            seen_by_callback.lock().unwrap().extend_from_slice(samples);
+            samples.iter_mut().for_each(|s| *s /= 2.0);
+        };
+        let mut filler = BufferFiller {
+            samples: source,
+            buffer: Vec::new(),
+            channels: 2,
+            process_callback: Some(Arc::new(Mutex::new(callback))),
+        };
+
+        let mut output = [0i16; 6];
+        filler.fill(&mut output);
+        assert_eq!(*seen.lock().unwrap(), [0.5, -0.5, 0.25, -0.25, 0.0, 0.0]);
+        assert_eq!(output, [8192, -8192, 4096, -4096, 0, 0]);
+    }
+
+    #[test]
+    #[ignore = "needs an audio output device"]
+    fn reports_opened_config() {
+        let Ok(stream) = OutputStreamBuilder::open_default_stream() else {
+            return; // No usable output device
+        };
+        let config = stream.config();
+        assert!(config.channels > 0);
+        assert!(config.sample_rate > 0);
+        assert_eq!(stream.mixer().channels(), config.channels);
+        assert_eq!(stream.mixer().sample_rate(), config.sample_rate);
+        if let Some(name) = stream.device_name() {
+            assert!(!name.is_empty());
+        }
+    }
+
+    #[test]
+    #[ignore = "needs an audio output device"]
+    fn exclusive_mode_opens_or_errors() {
+        let Ok(builder) = OutputStreamBuilder::from_default_device() else {
+            return; // No usable output device
+        };
+        let builder = builder.with_exclusive_mode(true);
+        match builder.open_stream() {
+            Ok(stream) => assert!(!stream.is_exclusive()),
+            Err(err) => assert!(!err.to_string().is_empty()),
+        }
+    }
+}
@@ -0,0 +1,13 @@
+#![cfg(feature = "symphonia-aiff")]
+use rodio::Source;
+use std::io::BufReader;
+
+#[test]
+fn test_aiff_new_aiff() {
+    // 16 bit mono, the first 100 ms of beep.wav
+    let file = std::fs::File::open("assets/beep.aiff").unwrap();
+    let mut decoder = rodio::Decoder::new_aiff(BufReader::new(file)).unwrap();
+    assert_eq!(decoder.channels(), 1);
+    assert_eq!(decoder.sample_rate(), 44100);
+    assert!(decoder.any(|x| x != 0)); // Assert not all zeros
+}
@@ -0,0 +1,13 @@
+#![cfg(feature = "symphonia-caf")]
+use rodio::Source;
+use std::io::BufReader;
+
+#[test]
+fn test_caf_new_caf() {
+    // 16 bit mono linear PCM, the first 100 ms of beep.wav
+    let file = std::fs::File::open("assets/beep.caf").unwrap();
+    let mut decoder = rodio::Decoder::new_caf(BufReader::new(file)).unwrap();
+    assert_eq!(decoder.channels(), 1);
+    assert_eq!(decoder.sample_rate(), 44100);
+    assert!(decoder.any(|x| x != 0)); // Assert not all zeros
+}
@@ -26,3 +26,34 @@ fn test_flac_encodings() {
         assert_eq!(decoder.total_duration(), Some(Duration::from_secs(3)));
     }
 }
+
+#[cfg(all(feature = "flac", not(feature = "symphonia-flac")))]
+#[test]
+fn test_flac_probe() {
+    use std::io::BufReader;
+
+    let open = || BufReader::new(std::fs::File::open("assets/audacity24bit_level5.flac").unwrap());
+    let info = rodio::Decoder::probe(open()).unwrap();
+    let decoder = rodio::Decoder::new(open()).unwrap();
+    assert_eq!(info.codec, "flac");
+    assert_eq!(info.channels, decoder.channels());
+    assert_eq!(info.sample_rate, decoder.sample_rate());
+    assert_eq!(info.total_duration, Some(Duration::from_secs(3)));
+    assert_eq!(info.bits_per_sample, Some(24));
+}
+
+#[cfg(feature = "symphonia-flac")]
+#[test]
+fn test_flac_chapters() {
+    use std::io::BufReader;
+    use std::time::Duration;
+
+    // Cue sheet with tracks at 0, 0.25 and 0.5 seconds.
+    let file = std::fs::File::open("assets/chapters.flac").unwrap();
+    let decoder = rodio::Decoder::new(BufReader::new(file)).unwrap();
+    let chapters = decoder.chapters();
+    assert_eq!(chapters.len(), 3);
+    assert_eq!(chapters[0].start, Duration::ZERO);
+    assert_eq!(chapters[1].start, Duration::from_millis(250));
+    assert_eq!(chapters[0].title, None);
+}
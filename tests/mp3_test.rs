@@ -0,0 +1,121 @@
+#[cfg(feature = "symphonia-mp3")]
+#[test]
+fn test_mp3_into_inner() {
+    use std::io::{Cursor, Seek, SeekFrom};
+
+    let data = std::fs::read("assets/music.mp3").unwrap();
+    let len = data.len() as u64;
+    let mut decoder = rodio::Decoder::new(Cursor::new(data)).unwrap();
+    assert!(decoder.by_ref().take(1000).any(|x| x != 0));
+
+    let mut cursor = decoder.into_inner();
+    let pos = cursor.position();
+    assert!(pos > 0 && pos <= len, "reader stopped at {pos} of {len}");
+
+    cursor.seek(SeekFrom::Start(0)).unwrap();
+    let mut decoder = rodio::Decoder::new(cursor).unwrap();
+    assert!(decoder.any(|x| x != 0));
+}
+
+#[cfg(feature = "symphonia-mp3")]
+#[test]
+fn test_mp3_bytes_with_mime_hint() {
+    use rodio::Source;
+
+    let data = std::fs::read("assets/music.mp3").unwrap();
+    let mut decoder = rodio::Decoder::from_bytes_with_hint(data, "audio/mpeg").unwrap();
+    assert_eq!(decoder.channels(), 2);
+    assert!(decoder.any(|x| x != 0));
+}
+
+#[cfg(feature = "symphonia-mp3")]
+#[test]
+fn test_mp3_probe() {
+    use rodio::Source;
+    use std::fs::File;
+
+    let info = rodio::Decoder::probe(File::open("assets/music.mp3").unwrap()).unwrap();
+    let decoder = rodio::Decoder::new(File::open("assets/music.mp3").unwrap()).unwrap();
+    assert_eq!(info.codec, "mp3");
+    assert_eq!(info.channels, decoder.channels());
+    assert_eq!(info.sample_rate, decoder.sample_rate());
+    assert!(info.total_duration.is_some());
+    assert_eq!(info.total_duration, decoder.total_duration());
+}
+
+#[cfg(any(feature = "symphonia-mp3", feature = "minimp3"))]
+#[test]
+fn test_mp3_seek_mode() {
+    use rodio::decoder::{DecoderBuilder, SeekMode};
+    use std::fs::File;
+    use std::time::Duration;
+
+    let target = Duration::from_millis(1234);
+    let seek_error = |mode| {
+        let mut decoder = DecoderBuilder::new(File::open("assets/music.mp3").unwrap())
+            .with_seek_mode(mode)
+            .build()
+            .unwrap();
+        let landed = decoder.try_seek_reported(target).unwrap();
+        landed.abs_diff(target)
+    };
+
+    let nearest = seek_error(SeekMode::Nearest);
+    let fastest = seek_error(SeekMode::Fastest);
+    assert!(nearest < Duration::from_millis(1));
+    assert!(
+        nearest < fastest,
+        "nearest {nearest:?}, fastest {fastest:?}"
+    );
+}
+
+#[cfg(any(feature = "symphonia-mp3", feature = "minimp3"))]
+#[test]
+fn test_mp3_range_with_fastest_seek_mode() {
+    use rodio::decoder::{DecoderBuilder, SeekMode};
+    use std::fs::File;
+    use std::time::Duration;
+
+    let range = |mode| {
+        DecoderBuilder::new(File::open("assets/music.mp3").unwrap())
+            .with_seek_mode(mode)
+            .with_range(Duration::from_millis(1234), Duration::from_millis(1734))
+            .build()
+            .unwrap()
+            .collect::<Vec<_>>()
+    };
+
+    // The fast seek lands before the start of the range, the samples up to it are
+    // dropped. The first frames decode differently without the ones before them.
+    let nearest = range(SeekMode::Nearest);
+    let fastest = range(SeekMode::Fastest);
+    assert_eq!(fastest.len(), nearest.len());
+    let tail = nearest.len() - 1000;
+    assert_eq!(fastest[tail..], nearest[tail..]);
+}
+
+#[cfg(feature = "symphonia-mp3")]
+#[test]
+fn test_mp3_decode_error_count() {
+    use std::io::Cursor;
+
+    let mut data = std::fs::read("assets/music.mp3").unwrap();
+    let clean = rodio::Decoder::new(Cursor::new(data.clone())).unwrap();
+    assert_eq!(clean.decode_error_count(), 0);
+    let clean_len = clean.count();
+
+    // Garbles the side information of a frame halfway through the file.
+    let frame = (data.len() / 2..data.len() - 1)
+        .find(|&i| data[i] == 0xFF && data[i + 1] == 0xFB)
+        .unwrap();
+    data[frame + 4..frame + 40].fill(0xFF);
+
+    let mut decoder = rodio::Decoder::new(Cursor::new(data)).unwrap();
+    let len = decoder.by_ref().count();
+    assert_eq!(decoder.decode_error_count(), 1);
+    // Only the damaged frame is lost.
+    assert!(
+        len < clean_len && len > clean_len * 99 / 100,
+        "{len} of {clean_len}"
+    );
+}
@@ -12,3 +12,33 @@ fn test_mp4a_encodings() {
     let mut decoder = rodio::Decoder::new(BufReader::new(file)).unwrap();
     assert!(decoder.any(|x| x != 0)); // Assert not all zeros
 }
+
+#[cfg(feature = "symphonia-wav")]
+#[test]
+fn test_select_track() {
+    use rodio::decoder::DecoderBuilder;
+
+    // Two tracks of 16 bit mono PCM, a 440 Hz tone and a 1 kHz tone
+    let open = || std::fs::File::open("assets/multitrack.m4a").unwrap();
+    let first = DecoderBuilder::new(open()).build().unwrap();
+    assert_eq!(first.track_count(), 2);
+    let second = DecoderBuilder::new(open())
+        .with_track_index(1)
+        .build()
+        .unwrap();
+    assert_eq!(second.track_count(), 2);
+
+    let first: Vec<i16> = first.collect();
+    let second: Vec<i16> = second.collect();
+    assert_eq!(first.len(), 800);
+    assert_eq!(second.len(), 800);
+    assert_ne!(first, second);
+    // Both start at zero, only the 1 kHz tone has a period of 8 samples.
+    assert!(second[8].abs() < 10);
+    assert!(first[8].abs() > 1000);
+
+    assert!(DecoderBuilder::new(open())
+        .with_track_index(2)
+        .build()
+        .is_err());
+}
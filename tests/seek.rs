@@ -242,3 +242,56 @@ fn get_rl(format: &str) -> Decoder<impl Read + Seek> {
     let file = std::fs::File::open(asset).unwrap();
     rodio::Decoder::new(BufReader::new(file)).unwrap()
 }
+
+#[cfg(feature = "symphonia-mp3")]
+#[test]
+fn seek_reports_landed_position() {
+    let mut decoder = get_music("mp3");
+    let total = decoder.total_duration().unwrap();
+    let frame = Duration::from_secs_f64(1.0 / decoder.sample_rate() as f64);
+
+    // Falls between two samples, lands on one of them.
+    let requested = Duration::from_nanos(1_234_567_891);
+    let landed = decoder.try_seek_reported(requested).unwrap();
+    assert_ne!(landed, requested);
+    assert!(landed.max(requested) - landed.min(requested) <= frame);
+
+    // Past the end lands just before the end.
+    let requested = Duration::from_secs(999);
+    let landed = decoder.try_seek_reported(requested).unwrap();
+    assert!(landed <= total, "landed at {landed:?} of {total:?}");
+    assert!(total - landed < Duration::from_millis(50));
+}
+
+#[cfg(feature = "symphonia-mp3")]
+#[test]
+fn seek_reports_landed_position_with_fastest() {
+    use rodio::decoder::{DecoderBuilder, SeekMode};
+
+    let open = |mode| {
+        let file = std::fs::File::open("assets/music.mp3").unwrap();
+        DecoderBuilder::new(BufReader::new(file))
+            .with_seek_mode(mode)
+            .build()
+            .unwrap()
+    };
+
+    // Jumps to the start of a packet instead of the requested sample.
+    let requested = Duration::from_millis(1234);
+    let mut fastest = open(SeekMode::Fastest);
+    let landed = fastest.try_seek_reported(requested).unwrap();
+    assert!(landed < requested);
+    assert!(requested - landed > Duration::from_millis(1));
+
+    // Playing continues where the seek reports it landed. The first packets decode
+    // differently without the ones before them, so they are skipped.
+    let channels = fastest.channels() as usize;
+    let frames = (landed.as_secs_f64() * fastest.sample_rate() as f64).round() as usize;
+    let skip = 4 * 1152 * channels;
+    let fastest: Vec<_> = fastest.skip(skip).take(1000).collect();
+    let from_start: Vec<_> = open(SeekMode::Nearest)
+        .skip(frames * channels + skip)
+        .take(1000)
+        .collect();
+    assert_eq!(fastest, from_start);
+}
@@ -33,3 +33,250 @@ fn test_wav_encodings() {
     let mut decoder = rodio::Decoder::new(BufReader::new(file)).unwrap();
     assert!(decoder.any(|x| x != 0));
 }
+
+#[cfg(all(feature = "wav", not(feature = "symphonia-wav")))]
+#[test]
+fn test_wav_into_inner() {
+    use std::io::{Cursor, Seek, SeekFrom};
+
+    let data = std::fs::read("assets/music.wav").unwrap();
+    let len = data.len() as u64;
+    let mut decoder = rodio::Decoder::new(Cursor::new(data)).unwrap();
+    assert!(decoder.by_ref().take(1000).any(|x| x != 0));
+
+    let mut cursor = decoder.into_inner();
+    let pos = cursor.position();
+    assert!(pos > 0 && pos < len, "reader stopped at {pos} of {len}");
+
+    cursor.seek(SeekFrom::Start(0)).unwrap();
+    let mut decoder = rodio::Decoder::new(cursor).unwrap();
+    assert!(decoder.any(|x| x != 0));
+}
+
+#[cfg(feature = "wav")]
+#[test]
+fn test_wav_decode_range() {
+    use rodio::decoder::DecoderBuilder;
+    use rodio::Source;
+    use std::io::Cursor;
+    use std::time::Duration;
+
+    // Mono 16 bit wav at 1000 Hz where every sample holds its own index.
+    let samples: Vec<i16> = (0..1000).collect();
+    let data_len = samples.len() as u32 * 2;
+    let mut wav = Vec::new();
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // channels
+    wav.extend_from_slice(&1000u32.to_le_bytes()); // sample rate
+    wav.extend_from_slice(&2000u32.to_le_bytes()); // byte rate
+    wav.extend_from_slice(&2u16.to_le_bytes()); // block align
+    wav.extend_from_slice(&16u16.to_le_bytes()); // bits per sample
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        wav.extend_from_slice(&sample.to_le_bytes());
+    }
+
+    let decoder = DecoderBuilder::new(Cursor::new(wav))
+        .with_range(Duration::from_millis(100), Duration::from_millis(250))
+        .build()
+        .unwrap();
+    assert_eq!(decoder.total_duration(), Some(Duration::from_millis(150)));
+    assert_eq!(decoder.collect::<Vec<_>>(), (100..250).collect::<Vec<_>>());
+}
+
+#[cfg(feature = "wav")]
+#[test]
+fn test_wav_planar_chunks() {
+    use rodio::Source;
+    use std::io::BufReader;
+
+    let open = || {
+        let file = std::fs::File::open("assets/music.wav").unwrap();
+        rodio::Decoder::new(BufReader::new(file)).unwrap()
+    };
+    assert_eq!(open().channels(), 2);
+
+    let interleaved: Vec<f32> = open().convert_samples().collect();
+    let left: Vec<f32> = open()
+        .planar_chunks(1000)
+        .flat_map(|mut block| block.swap_remove(0))
+        .collect();
+    let even: Vec<f32> = interleaved.iter().copied().step_by(2).collect();
+    assert_eq!(left, even);
+}
+
+#[cfg(any(feature = "wav", feature = "symphonia-wav"))]
+#[test]
+fn test_wav_total_frames() {
+    use rodio::Source;
+    use std::io::BufReader;
+
+    // 2 channels
+    let file = std::fs::File::open("assets/lmms16bit.wav").unwrap();
+    let decoder = rodio::Decoder::new(BufReader::new(file)).unwrap();
+    let channels = decoder.channels() as u64;
+    let total_frames = decoder.total_frames();
+    let samples = decoder.count() as u64;
+    assert_eq!(total_frames, Some(samples / channels));
+}
+
+#[cfg(feature = "wav")]
+#[test]
+fn test_wav_replayable() {
+    use std::io::{Cursor, Read, Seek, SeekFrom};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    struct CountingReader {
+        inner: Cursor<Vec<u8>>,
+        bytes_read: Arc<AtomicUsize>,
+    }
+
+    impl Read for CountingReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let len = self.inner.read(buf)?;
+            self.bytes_read.fetch_add(len, Ordering::Relaxed);
+            Ok(len)
+        }
+    }
+
+    impl Seek for CountingReader {
+        fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+            self.inner.seek(pos)
+        }
+    }
+
+    let data = std::fs::read("assets/beep.wav").unwrap();
+    let bytes_read = Arc::new(AtomicUsize::new(0));
+    let reader = CountingReader {
+        inner: Cursor::new(data),
+        bytes_read: bytes_read.clone(),
+    };
+    let sound = rodio::Decoder::new(reader).unwrap().into_replayable();
+    let read_once = bytes_read.load(Ordering::Relaxed);
+
+    let first: Vec<f32> = sound.replay().collect();
+    let second: Vec<f32> = sound.clone().collect();
+    assert!(first.iter().any(|&sample| sample != 0.0));
+    assert_eq!(first, second);
+    assert_eq!(bytes_read.load(Ordering::Relaxed), read_once);
+}
+
+#[cfg(all(feature = "wav", not(feature = "symphonia-wav")))]
+#[test]
+fn test_wav_probe() {
+    use rodio::Source;
+    use std::io::BufReader;
+
+    let open = || BufReader::new(std::fs::File::open("assets/lmms24bit.wav").unwrap());
+    let info = rodio::Decoder::probe(open()).unwrap();
+    let decoder = rodio::Decoder::new(open()).unwrap();
+    assert_eq!(info.codec, "wav");
+    assert_eq!(info.channels, decoder.channels());
+    assert_eq!(info.sample_rate, decoder.sample_rate());
+    assert_eq!(info.total_duration, decoder.total_duration());
+    assert_eq!(info.bits_per_sample, Some(24));
+}
+
+#[cfg(feature = "wav")]
+#[test]
+fn test_wav_probe_cached() {
+    use rodio::decoder::ProbeCache;
+    use std::io::Cursor;
+
+    let beep = std::fs::read("assets/beep.wav").unwrap();
+    let music = std::fs::read("assets/music.wav").unwrap();
+    let expected = rodio::Decoder::probe(Cursor::new(beep.clone())).unwrap();
+
+    let mut cache = ProbeCache::new();
+    for _ in 0..10 {
+        let info = rodio::Decoder::probe_cached(Cursor::new(beep.clone()), &mut cache).unwrap();
+        assert_eq!(info, expected);
+    }
+    let info = rodio::Decoder::probe_cached(Cursor::new(music), &mut cache).unwrap();
+    assert_ne!(info, expected);
+
+    // Only the first copy of the beep and the music were probed.
+    assert_eq!(cache.misses(), 2);
+    assert_eq!(cache.hits(), 9);
+    assert_eq!(cache.len(), 2);
+}
+
+#[cfg(all(feature = "wav", not(feature = "symphonia-wav")))]
+#[test]
+fn test_wav_following() {
+    let ramp = |range: std::ops::Range<i16>| range.collect::<Vec<_>>();
+    let path = std::env::temp_dir().join(format!("rodio-following-{}.wav", std::process::id()));
+    let spec = hound::WavSpec {
+        channels: 2,
+        sample_rate: 1000,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+    for sample in ramp(1..101) {
+        writer.write_sample(sample).unwrap();
+    }
+    writer.flush().unwrap();
+
+    let (mut decoder, handle) = rodio::Decoder::new_following(&path).unwrap();
+    assert_eq!(decoder.by_ref().take(100).collect::<Vec<_>>(), ramp(1..101));
+    // Waits for more data in silence instead of ending.
+    assert!(decoder.by_ref().take(100).all(|sample| sample == 0));
+
+    for sample in ramp(101..201) {
+        writer.write_sample(sample).unwrap();
+    }
+    writer.flush().unwrap();
+    let first = decoder.by_ref().take(1000).find(|&sample| sample != 0);
+    let mut continued = vec![first.expect("new data was not picked up")];
+    continued.extend(decoder.by_ref().take(99));
+    assert_eq!(continued, ramp(101..201));
+
+    // Plays what was written before the stop, then ends.
+    for sample in ramp(201..211) {
+        writer.write_sample(sample).unwrap();
+    }
+    writer.finalize().unwrap();
+    handle.stop();
+    let rest: Vec<i16> = decoder.filter(|&sample| sample != 0).collect();
+    assert_eq!(rest, ramp(201..211));
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(all(feature = "wav", not(feature = "symphonia-wav")))]
+#[test]
+fn test_wav_following_seek_error() {
+    use rodio::Source;
+    use std::time::Duration;
+
+    let path =
+        std::env::temp_dir().join(format!("rodio-following-seek-{}.wav", std::process::id()));
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: 1000,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+    for sample in 0..100i16 {
+        writer.write_sample(sample).unwrap();
+    }
+    writer.finalize().unwrap();
+
+    let (mut decoder, _handle) = rodio::Decoder::new_following(&path).unwrap();
+    assert!(decoder.try_seek(Duration::from_millis(50)).is_ok());
+    assert_eq!(decoder.next(), Some(50));
+
+    // Without its headers the file can not be decoded again to seek in it.
+    std::fs::File::create(&path).unwrap();
+    assert!(decoder.try_seek(Duration::ZERO).is_err());
+
+    std::fs::remove_file(&path).unwrap();
+}